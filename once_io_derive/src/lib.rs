@@ -0,0 +1,258 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Fields, GenericParam,
+    Generics, Ident, LitByteStr, LitInt, Member, Result, Type,
+};
+
+#[derive(Clone, Copy)]
+enum Endian {
+    Big,
+    Little,
+    Native,
+}
+
+#[derive(Default)]
+struct Attrs {
+    endian: Option<Endian>,
+    len_prefix: Option<Type>,
+    pad_to: Option<LitInt>,
+    magic: Option<LitByteStr>,
+}
+
+impl Attrs {
+    fn parse(attrs: &[Attribute]) -> Result<Attrs> {
+        let mut parsed = Attrs::default();
+        for attr in attrs {
+            if attr.path().is_ident("endian") {
+                let ident: Ident = attr.parse_args()?;
+                parsed.endian = Some(match ident.to_string().as_str() {
+                    "be" => Endian::Big,
+                    "le" => Endian::Little,
+                    "native" => Endian::Native,
+                    _ => {
+                        return Err(Error::new(
+                            ident.span(),
+                            "expected one of `be`, `le` or `native`",
+                        ))
+                    }
+                });
+            } else if attr.path().is_ident("len_prefix") {
+                parsed.len_prefix = Some(attr.parse_args()?);
+            } else if attr.path().is_ident("pad_to") {
+                let align: LitInt = attr.parse_args()?;
+                if align.base10_parse::<u64>()? == 0 {
+                    return Err(Error::new(align.span(), "alignment must not be zero"));
+                }
+                parsed.pad_to = Some(align);
+            } else if attr.path().is_ident("magic") {
+                parsed.magic = Some(attr.parse_args()?);
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+struct Field {
+    member: Member,
+    binding: Ident,
+    ty: Type,
+    attrs: Attrs,
+}
+
+struct Input {
+    name: Ident,
+    generics: Generics,
+    attrs: Attrs,
+    fields: Vec<Field>,
+    named: bool,
+}
+
+impl Input {
+    fn parse(input: DeriveInput) -> Result<Input> {
+        let data = match input.data {
+            Data::Struct(data) => data,
+            _ => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "binary derives only support structs",
+                ))
+            }
+        };
+        let named = matches!(data.fields, Fields::Named(_));
+        let fields = data
+            .fields
+            .into_iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let (member, binding) = match field.ident {
+                    Some(ident) => (Member::Named(ident.clone()), ident),
+                    None => (
+                        Member::Unnamed(index.into()),
+                        format_ident!("__field{}", index),
+                    ),
+                };
+                Ok(Field {
+                    member,
+                    binding,
+                    ty: field.ty,
+                    attrs: Attrs::parse(&field.attrs)?,
+                })
+            })
+            .collect::<Result<Vec<Field>>>()?;
+        Ok(Input {
+            name: input.ident,
+            generics: input.generics,
+            attrs: Attrs::parse(&input.attrs)?,
+            fields,
+            named,
+        })
+    }
+
+    fn generics(&self, bound: TokenStream2) -> Generics {
+        let mut generics = self.generics.clone();
+        for param in &mut generics.params {
+            if let GenericParam::Type(param) = param {
+                param.bounds.push(parse_quote!(#bound));
+            }
+        }
+        generics
+    }
+}
+
+fn with_endian(endian: Option<Endian>, io: &Ident, write: bool) -> TokenStream2 {
+    match (endian, write) {
+        (None, _) => quote!(#io),
+        (Some(Endian::Big), false) => quote!(&mut ::once_io::read_num::ReadEndianExt::be(#io)),
+        (Some(Endian::Little), false) => quote!(&mut ::once_io::read_num::ReadEndianExt::le(#io)),
+        (Some(Endian::Native), false) => {
+            quote!(&mut ::once_io::read_num::ReadEndianExt::native(#io))
+        }
+        (Some(Endian::Big), true) => {
+            quote!(&mut ::once_io::write_num::WriteEndianExt::be_writer(#io))
+        }
+        (Some(Endian::Little), true) => {
+            quote!(&mut ::once_io::write_num::WriteEndianExt::le_writer(#io))
+        }
+        (Some(Endian::Native), true) => {
+            quote!(&mut ::once_io::write_num::WriteEndianExt::native_writer(#io))
+        }
+    }
+}
+
+fn expand_read(input: &Input) -> TokenStream2 {
+    let reader = Ident::new("__once_io_reader", Span::call_site());
+    let mut body = Vec::new();
+    if let Some(magic) = &input.attrs.magic {
+        body.push(quote!(#reader.read_magic(#magic)?;));
+    }
+    for field in &input.fields {
+        let endian = field.attrs.endian.or(input.attrs.endian);
+        let io = with_endian(endian, &reader, false);
+        let Field { binding, ty, .. } = field;
+        if let Some(magic) = &field.attrs.magic {
+            body.push(quote!(#reader.read_magic(#magic)?;));
+        }
+        body.push(match &field.attrs.len_prefix {
+            Some(prefix) => quote! {
+                let #binding: #ty = ::once_io::layout::read_len_prefixed::<#prefix, #ty, _>(#io)?;
+            },
+            None => quote! {
+                let #binding: #ty = <#ty as ::once_io::layout::ReadBinary>::read_binary(#io)?;
+            },
+        });
+        if let Some(align) = &field.attrs.pad_to {
+            body.push(quote!(#reader.read_align(#align)?;));
+        }
+    }
+    if let Some(align) = &input.attrs.pad_to {
+        body.push(quote!(#reader.read_align(#align)?;));
+    }
+    let bindings = input.fields.iter().map(|field| &field.binding);
+    let construct = if input.named {
+        quote!(Self { #(#bindings),* })
+    } else {
+        quote!(Self(#(#bindings),*))
+    };
+    let name = &input.name;
+    let generics = input.generics(quote!(::once_io::layout::ReadBinary));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::once_io::layout::ReadBinary for #name #ty_generics #where_clause {
+            fn read_binary<__R>(reader: &mut __R) -> ::std::io::Result<Self>
+            where
+                __R: ::once_io::read_num::ReadNum + ::std::io::Read + ?Sized,
+            {
+                let mut #reader = ::once_io::layout::Counted::new(reader);
+                let #reader = &mut #reader;
+                #(#body)*
+                Ok(#construct)
+            }
+        }
+    }
+}
+
+fn expand_write(input: &Input) -> TokenStream2 {
+    let writer = Ident::new("__once_io_writer", Span::call_site());
+    let mut body = Vec::new();
+    if let Some(magic) = &input.attrs.magic {
+        body.push(quote!(::std::io::Write::write_all(#writer, #magic)?;));
+    }
+    for field in &input.fields {
+        let endian = field.attrs.endian.or(input.attrs.endian);
+        let io = with_endian(endian, &writer, true);
+        let Field { member, ty, .. } = field;
+        if let Some(magic) = &field.attrs.magic {
+            body.push(quote!(::std::io::Write::write_all(#writer, #magic)?;));
+        }
+        body.push(match &field.attrs.len_prefix {
+            Some(prefix) => quote! {
+                ::once_io::layout::write_len_prefixed::<#prefix, #ty, _>(&self.#member, #io)?;
+            },
+            None => quote! {
+                ::once_io::layout::WriteBinary::write_binary(&self.#member, #io)?;
+            },
+        });
+        if let Some(align) = &field.attrs.pad_to {
+            body.push(quote!(#writer.write_align(#align)?;));
+        }
+    }
+    if let Some(align) = &input.attrs.pad_to {
+        body.push(quote!(#writer.write_align(#align)?;));
+    }
+    let name = &input.name;
+    let generics = input.generics(quote!(::once_io::layout::WriteBinary));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::once_io::layout::WriteBinary for #name #ty_generics #where_clause {
+            fn write_binary<__W>(&self, writer: &mut __W) -> ::std::io::Result<()>
+            where
+                __W: ::once_io::write_num::WriteNum + ::std::io::Write + ?Sized,
+            {
+                let mut #writer = ::once_io::layout::Counted::new(writer);
+                let #writer = &mut #writer;
+                #(#body)*
+                Ok(())
+            }
+        }
+    }
+}
+
+fn derive(input: TokenStream, expand: fn(&Input) -> TokenStream2) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match Input::parse(input) {
+        Ok(input) => expand(&input).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(ReadBinary, attributes(endian, len_prefix, pad_to, magic))]
+pub fn derive_read_binary(input: TokenStream) -> TokenStream {
+    derive(input, expand_read)
+}
+
+#[proc_macro_derive(WriteBinary, attributes(endian, len_prefix, pad_to, magic))]
+pub fn derive_write_binary(input: TokenStream) -> TokenStream {
+    derive(input, expand_write)
+}
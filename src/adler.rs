@@ -0,0 +1,91 @@
+use std::io::{Read, Result, Seek};
+
+use crate::Stream;
+
+const MOD_ADLER: u32 = 65521;
+const NMAX: usize = 5552;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    pub fn new() -> Adler32 {
+        Adler32 { a: 1, b: 0 }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for block in bytes.chunks(NMAX) {
+            for byte in block {
+                self.a += *byte as u32;
+                self.b += self.a;
+            }
+            self.a %= MOD_ADLER;
+            self.b %= MOD_ADLER;
+        }
+    }
+
+    pub fn finish(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+impl Default for Adler32 {
+    fn default() -> Adler32 {
+        Adler32::new()
+    }
+}
+
+pub fn adler32(bytes: &[u8]) -> u32 {
+    let mut adler = Adler32::new();
+    adler.update(bytes);
+    adler.finish()
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn adler32(&mut self) -> Result<u32> {
+        self.digest_remainder(Adler32::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, SeekFrom};
+
+    use super::*;
+
+    #[test]
+    fn adler32_check_value() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"123456789"), 0x091e_01de);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn adler32_large_input() {
+        let data = vec![0xffu8; 100_000];
+        let mut adler = Adler32::new();
+        adler.update(&data[..7]);
+        adler.update(&data[7..]);
+        let (a, b) = data.iter().fold((1u64, 0u64), |(a, b), byte| {
+            let a = (a + *byte as u64) % 65521;
+            (a, (b + a) % 65521)
+        });
+        assert_eq!(adler.finish(), ((b << 16) | a) as u32);
+    }
+
+    #[test]
+    fn adler32_chunk() {
+        let mut cursor = Cursor::new(b"xWikipediay".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(1)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(9)).unwrap();
+        assert_eq!(chunk.adler32().unwrap(), 0x11e6_0398);
+        assert_eq!(cursor.position(), 10);
+    }
+}
@@ -0,0 +1,179 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+use crate::Stream;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlignOrigin {
+    Start,
+    Chunk,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Alignment {
+    align: u64,
+    origin: AlignOrigin,
+    require_zero: bool,
+    fill: u8,
+}
+
+impl Alignment {
+    pub fn new(align: u64) -> Alignment {
+        Alignment {
+            align,
+            origin: AlignOrigin::Chunk,
+            require_zero: false,
+            fill: 0,
+        }
+    }
+
+    pub fn origin(mut self, origin: AlignOrigin) -> Alignment {
+        self.origin = origin;
+        self
+    }
+
+    pub fn require_zero(mut self, require_zero: bool) -> Alignment {
+        self.require_zero = require_zero;
+        self
+    }
+
+    pub fn fill(mut self, fill: u8) -> Alignment {
+        self.fill = fill;
+        self
+    }
+
+    fn padding<T, E>(&self, stream: &mut Stream<'_, T, E>) -> Result<u64>
+    where
+        T: Seek,
+    {
+        if self.align == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "alignment must not be zero",
+            ));
+        }
+        let mut pos = stream.inner.stream_position()?;
+        if self.origin == AlignOrigin::Chunk {
+            pos -= stream.origin_pos;
+        }
+        Ok(pos.next_multiple_of(self.align) - pos)
+    }
+
+    pub fn skip<T, E>(&self, stream: &mut Stream<'_, T, E>) -> Result<u64>
+    where
+        T: Read + Seek,
+    {
+        let padding = self.padding(stream)?;
+        let remainder_len = stream.remainder_len()?;
+        if padding > remainder_len {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!(
+                    "{} padding bytes exceed the remaining {} bytes",
+                    padding, remainder_len
+                ),
+            ));
+        }
+        if self.require_zero {
+            let start = stream.inner.stream_position()?;
+            let mut buf = vec![0u8; padding as usize];
+            stream.read_exact(&mut buf)?;
+            if let Some(index) = buf.iter().position(|byte| *byte != 0) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "non-zero padding byte {:#04x} at offset {}",
+                        buf[index],
+                        start + index as u64
+                    ),
+                ));
+            }
+        } else {
+            stream.inner.seek(SeekFrom::Current(padding as i64))?;
+        }
+        Ok(padding)
+    }
+
+    pub fn pad<T, E>(&self, stream: &mut Stream<'_, T, E>) -> Result<u64>
+    where
+        T: Write + Seek,
+    {
+        let padding = self.padding(stream)?;
+        stream.write_all(&vec![self.fill; padding as usize])?;
+        Ok(padding)
+    }
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn align_to(&mut self, align: u64) -> Result<u64> {
+        Alignment::new(align).skip(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn align_to_chunk_origin() {
+        let mut cursor = Cursor::new(b"xabc\x00\x00\x00\x00d".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(1)).unwrap();
+        let mut chunk = stream.borrow_chunk(None).unwrap();
+        chunk.seek(SeekFrom::Current(3)).unwrap();
+        assert_eq!(chunk.align_to(4).unwrap(), 1);
+        assert_eq!(chunk.align_to(4).unwrap(), 0);
+        assert_eq!(cursor.position(), 5);
+    }
+
+    #[test]
+    fn align_to_file_origin() {
+        let mut cursor = Cursor::new(b"xabc\x00\x00\x00\x00d".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(1)).unwrap();
+        let mut chunk = stream.borrow_chunk(None).unwrap();
+        chunk.seek(SeekFrom::Current(4)).unwrap();
+        let alignment = Alignment::new(8)
+            .origin(AlignOrigin::Start)
+            .require_zero(true);
+        assert_eq!(alignment.skip(&mut chunk).unwrap(), 3);
+        assert_eq!(cursor.position(), 8);
+    }
+
+    #[test]
+    fn align_skip_errors() {
+        let mut cursor = Cursor::new(b"a\x00\x01\x00".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(1)).unwrap();
+        let err = Alignment::new(4)
+            .require_zero(true)
+            .skip(&mut stream)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "non-zero padding byte 0x01 at offset 2");
+        stream.seek(SeekFrom::Start(1)).unwrap();
+        let err = stream.align_to(8).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let err = stream.align_to(0).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn align_pad() {
+        let mut cursor = Cursor::new(vec![0u8; 8]);
+        let mut stream = Stream::new(&mut cursor);
+        stream.write_all(b"abc").unwrap();
+        let alignment = Alignment::new(4).fill(0xff);
+        assert_eq!(alignment.pad(&mut stream).unwrap(), 1);
+        stream.write_all(b"d").unwrap();
+        let mut chunk = stream.borrow_chunk(Some(2)).unwrap();
+        assert_eq!(alignment.pad(&mut chunk).unwrap(), 0);
+        chunk.write_all(b"e").unwrap();
+        let err = Alignment::new(4).pad(&mut chunk).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        assert_eq!(cursor.get_ref(), b"abc\xffde\x00\x00");
+    }
+}
@@ -0,0 +1,175 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+fn is_padding(byte: &u8) -> bool {
+    *byte == b' ' || *byte == 0
+}
+
+fn check_radix(radix: u32) -> Result<()> {
+    if !(2..=36).contains(&radix) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("unsupported radix {}", radix),
+        ));
+    }
+    Ok(())
+}
+
+fn parse_ascii_u64(field: &[u8], radix: u32) -> Result<u64> {
+    check_radix(radix)?;
+    let start = field.iter().position(|b| !is_padding(b));
+    let end = field.iter().rposition(|b| !is_padding(b));
+    let digits = match (start, end) {
+        (Some(start), Some(end)) => &field[start..=end],
+        _ => return Ok(0),
+    };
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "invalid base {} number field {:?}",
+                radix,
+                String::from_utf8_lossy(field)
+            ),
+        )
+    };
+    if digits[0] == b'+' {
+        return Err(invalid());
+    }
+    let digits = std::str::from_utf8(digits).map_err(|_| invalid())?;
+    u64::from_str_radix(digits, radix).map_err(|_| invalid())
+}
+
+pub trait ReadAsciiNum: Read {
+    fn read_ascii_u64(&mut self, width: usize, radix: u32) -> Result<u64> {
+        check_radix(radix)?;
+        let mut field = vec![0u8; width];
+        self.read_exact(&mut field)?;
+        parse_ascii_u64(&field, radix)
+    }
+
+    fn read_ascii_octal(&mut self, width: usize) -> Result<u64> {
+        self.read_ascii_u64(width, 8)
+    }
+
+    fn read_ascii_decimal(&mut self, width: usize) -> Result<u64> {
+        self.read_ascii_u64(width, 10)
+    }
+
+    fn read_ascii_hex(&mut self, width: usize) -> Result<u64> {
+        self.read_ascii_u64(width, 16)
+    }
+}
+
+impl<R> ReadAsciiNum for R where R: Read + ?Sized {}
+
+fn format_radix(mut value: u64, radix: u32) -> Vec<u8> {
+    let mut digits = Vec::new();
+    loop {
+        let digit = (value % radix as u64) as u32;
+        digits.push(std::char::from_digit(digit, radix).unwrap() as u8);
+        value /= radix as u64;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    digits
+}
+
+pub trait WriteAsciiNum: Write {
+    fn write_ascii_u64(&mut self, value: u64, width: usize, radix: u32, fill: u8) -> Result<()> {
+        check_radix(radix)?;
+        let digits = format_radix(value, radix);
+        if digits.len() > width {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "value {} does not fit in {} base {} digits",
+                    value, width, radix
+                ),
+            ));
+        }
+        let mut field = vec![fill; width];
+        field[width - digits.len()..].copy_from_slice(&digits);
+        self.write_all(&field)
+    }
+
+    fn write_ascii_octal(&mut self, value: u64, width: usize) -> Result<()> {
+        self.write_ascii_u64(value, width, 8, b'0')
+    }
+
+    fn write_ascii_decimal(&mut self, value: u64, width: usize) -> Result<()> {
+        self.write_ascii_u64(value, width, 10, b' ')
+    }
+
+    fn write_ascii_hex(&mut self, value: u64, width: usize) -> Result<()> {
+        self.write_ascii_u64(value, width, 16, b'0')
+    }
+}
+
+impl<W> WriteAsciiNum for W where W: Write + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn read_tar_style_octal() {
+        let mut cursor = Cursor::new(b"0000644\0 1750 \0\0\0\0\0\0\0\0\0".to_vec());
+        assert_eq!(cursor.read_ascii_octal(8).unwrap(), 0o644);
+        assert_eq!(cursor.read_ascii_octal(7).unwrap(), 0o1750);
+        assert_eq!(cursor.read_ascii_octal(8).unwrap(), 0);
+    }
+
+    #[test]
+    fn read_ascii_decimal_and_hex() {
+        let mut cursor = Cursor::new(b"  1234  ff0A".to_vec());
+        assert_eq!(cursor.read_ascii_decimal(6).unwrap(), 1234);
+        assert_eq!(cursor.read_ascii_hex(6).unwrap(), 0xff0a);
+    }
+
+    #[test]
+    fn read_ascii_invalid_digits() {
+        let mut cursor = Cursor::new(b"0000849\0".to_vec());
+        let result = cursor.read_ascii_octal(8);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let mut cursor = Cursor::new(b"12 34".to_vec());
+        let result = cursor.read_ascii_decimal(5);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let mut cursor = Cursor::new(b" +42".to_vec());
+        let result = cursor.read_ascii_decimal(4);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_ascii_unsupported_radix() {
+        let mut cursor = Cursor::new(b"1010".to_vec());
+        for radix in [0, 1, 37] {
+            let result = cursor.read_ascii_u64(4, radix);
+            assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+        }
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.read_ascii_u64(4, 2).unwrap(), 10);
+    }
+
+    #[test]
+    fn write_ascii_numbers() {
+        let mut out = Vec::new();
+        out.write_ascii_octal(0o644, 7).unwrap();
+        out.write_ascii_decimal(42, 5).unwrap();
+        out.write_ascii_hex(0xbeef, 6).unwrap();
+        assert_eq!(out, b"0000644   4200beef");
+        let mut cursor = Cursor::new(out);
+        assert_eq!(cursor.read_ascii_octal(7).unwrap(), 0o644);
+        assert_eq!(cursor.read_ascii_decimal(5).unwrap(), 42);
+        assert_eq!(cursor.read_ascii_hex(6).unwrap(), 0xbeef);
+    }
+
+    #[test]
+    fn write_ascii_number_too_wide() {
+        let result = Vec::new().write_ascii_decimal(123456, 5);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    io::{Error, ErrorKind, Result, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tokio::io::{self, AsyncSeek, AsyncSeekExt, AsyncWrite};
+
+use crate::{
+    chunk::{check_patchable, length_field},
+    tlv::FieldEncoding,
+};
+
+pub struct AsyncChunkWriter<W>
+where
+    W: AsyncWrite + AsyncSeek + Unpin,
+{
+    inner: W,
+    length: FieldEncoding,
+    includes_header: bool,
+    open: Vec<u64>,
+}
+
+impl<W> AsyncChunkWriter<W>
+where
+    W: AsyncWrite + AsyncSeek + Unpin,
+{
+    pub fn new(inner: W, length: FieldEncoding) -> AsyncChunkWriter<W> {
+        AsyncChunkWriter {
+            inner,
+            length,
+            includes_header: false,
+            open: Vec::new(),
+        }
+    }
+
+    pub fn includes_header(mut self, includes_header: bool) -> AsyncChunkWriter<W> {
+        self.includes_header = includes_header;
+        self
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    pub async fn begin_chunk(&mut self) -> Result<()> {
+        check_patchable(self.length)?;
+        let start = self.inner.stream_position().await?;
+        let (_, field) = length_field(self.length, true, 0, 0)?;
+        io::AsyncWriteExt::write_all(&mut self.inner, &field).await?;
+        self.open.push(start);
+        Ok(())
+    }
+
+    pub async fn end_chunk(&mut self) -> Result<u64> {
+        let start = self
+            .open
+            .pop()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "no open chunk to end"))?;
+        let end = self.inner.stream_position().await?;
+        let (len, field) = length_field(self.length, self.includes_header, start, end)?;
+        self.inner.seek(SeekFrom::Start(start)).await?;
+        io::AsyncWriteExt::write_all(&mut self.inner, &field).await?;
+        self.inner.seek(SeekFrom::Start(end)).await?;
+        Ok(len)
+    }
+
+    pub async fn write_chunk(&mut self, body: &[u8]) -> Result<u64> {
+        self.begin_chunk().await?;
+        io::AsyncWriteExt::write_all(&mut self.inner, body).await?;
+        self.end_chunk().await
+    }
+
+    pub async fn into_inner(mut self) -> Result<W> {
+        while !self.open.is_empty() {
+            self.end_chunk().await?;
+        }
+        Ok(self.inner)
+    }
+}
+
+impl<W> AsyncWrite for AsyncChunkWriter<W>
+where
+    W: AsyncWrite + AsyncSeek + Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{
+        async_write_num::{AsyncWriteEndianExt, AsyncWriteNum},
+        read_num::Endianness,
+    };
+
+    #[tokio::test]
+    async fn async_nested_chunks() {
+        let mut writer =
+            AsyncChunkWriter::new(Cursor::new(Vec::new()), FieldEncoding::U16(Endianness::Big));
+        writer.begin_chunk().await.unwrap();
+        writer.be_writer().write_u32(0xdead_beef).await.unwrap();
+        assert_eq!(writer.write_chunk(b"abc").await.unwrap(), 3);
+        assert_eq!(writer.depth(), 1);
+        assert_eq!(writer.end_chunk().await.unwrap(), 9);
+        let data = writer.into_inner().await.unwrap().into_inner();
+        assert_eq!(data, [0, 9, 0xde, 0xad, 0xbe, 0xef, 0, 3, b'a', b'b', b'c']);
+    }
+
+    #[tokio::test]
+    async fn async_chunk_includes_header_and_unclosed() {
+        let mut writer =
+            AsyncChunkWriter::new(Cursor::new(Vec::new()), FieldEncoding::U8).includes_header(true);
+        writer.begin_chunk().await.unwrap();
+        io::AsyncWriteExt::write_all(&mut writer, b"xy")
+            .await
+            .unwrap();
+        let data = writer.into_inner().await.unwrap().into_inner();
+        assert_eq!(data, [3, b'x', b'y']);
+    }
+
+    #[tokio::test]
+    async fn async_chunk_errors() {
+        let mut writer = AsyncChunkWriter::new(Cursor::new(Vec::new()), FieldEncoding::Varint);
+        let err = writer.begin_chunk().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        let err = writer.end_chunk().await.unwrap_err();
+        assert_eq!(err.to_string(), "no open chunk to end");
+    }
+}
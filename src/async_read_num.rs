@@ -0,0 +1,269 @@
+use std::{
+    future::Future,
+    io::Result,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tokio::io::{self, AsyncRead, ReadBuf};
+
+use crate::{
+    read_num::{
+        BigEndianReader, DynEndianRead, EndianRead, Endianness, FromEndianBytes,
+        LittleEndianReader, NativeEndianReader, StaticEndianness,
+    },
+    string::{check_len_prefix, invalid_utf8, missing_nul},
+    tlv::VarintDecoder,
+};
+
+pub trait AsyncReadNum: AsyncRead + Unpin {
+    fn endianness(&self) -> Endianness;
+
+    fn read_num<N: FromEndianBytes>(&mut self) -> impl Future<Output = Result<N>> {
+        async move {
+            let endianness = AsyncReadNum::endianness(self);
+            let mut bytes = N::Bytes::default();
+            io::AsyncReadExt::read_exact(self, bytes.as_mut()).await?;
+            Ok(N::from_endian_bytes(bytes, endianness))
+        }
+    }
+
+    fn read_u8(&mut self) -> impl Future<Output = Result<u8>> {
+        self.read_num()
+    }
+
+    fn read_u16(&mut self) -> impl Future<Output = Result<u16>> {
+        self.read_num()
+    }
+
+    fn read_u32(&mut self) -> impl Future<Output = Result<u32>> {
+        self.read_num()
+    }
+
+    fn read_u64(&mut self) -> impl Future<Output = Result<u64>> {
+        self.read_num()
+    }
+
+    fn read_u128(&mut self) -> impl Future<Output = Result<u128>> {
+        self.read_num()
+    }
+
+    fn read_i8(&mut self) -> impl Future<Output = Result<i8>> {
+        self.read_num()
+    }
+
+    fn read_i16(&mut self) -> impl Future<Output = Result<i16>> {
+        self.read_num()
+    }
+
+    fn read_i32(&mut self) -> impl Future<Output = Result<i32>> {
+        self.read_num()
+    }
+
+    fn read_i64(&mut self) -> impl Future<Output = Result<i64>> {
+        self.read_num()
+    }
+
+    fn read_i128(&mut self) -> impl Future<Output = Result<i128>> {
+        self.read_num()
+    }
+
+    fn read_usize(&mut self) -> impl Future<Output = Result<usize>> {
+        self.read_num()
+    }
+
+    fn read_isize(&mut self) -> impl Future<Output = Result<isize>> {
+        self.read_num()
+    }
+
+    fn read_f32(&mut self) -> impl Future<Output = Result<f32>> {
+        self.read_num()
+    }
+
+    fn read_f64(&mut self) -> impl Future<Output = Result<f64>> {
+        self.read_num()
+    }
+
+    fn read_varint(&mut self) -> impl Future<Output = Result<u64>> {
+        async move {
+            let mut decoder = VarintDecoder::new();
+            loop {
+                if let Some(value) = decoder.push(self.read_num::<u8>().await?)? {
+                    return Ok(value);
+                }
+            }
+        }
+    }
+
+    fn read_cstr_bytes(&mut self, max_len: usize) -> impl Future<Output = Result<Vec<u8>>> {
+        async move {
+            let mut bytes = Vec::new();
+            for _ in 0..max_len {
+                match self.read_num::<u8>().await? {
+                    0 => return Ok(bytes),
+                    byte => bytes.push(byte),
+                }
+            }
+            Err(missing_nul(max_len))
+        }
+    }
+
+    fn read_cstr(&mut self, max_len: usize) -> impl Future<Output = Result<String>> {
+        async move { String::from_utf8(self.read_cstr_bytes(max_len).await?).map_err(invalid_utf8) }
+    }
+
+    fn read_len_prefixed_bytes<L>(&mut self, max_len: u64) -> impl Future<Output = Result<Vec<u8>>>
+    where
+        L: FromEndianBytes + TryInto<u64>,
+    {
+        async move {
+            let len = check_len_prefix(self.read_num::<L>().await?, max_len)?;
+            let mut bytes = vec![0u8; len as usize];
+            io::AsyncReadExt::read_exact(self, &mut bytes).await?;
+            Ok(bytes)
+        }
+    }
+
+    fn read_len_prefixed_string<L>(&mut self, max_len: u64) -> impl Future<Output = Result<String>>
+    where
+        L: FromEndianBytes + TryInto<u64>,
+    {
+        async move {
+            String::from_utf8(self.read_len_prefixed_bytes::<L>(max_len).await?)
+                .map_err(invalid_utf8)
+        }
+    }
+
+    fn read_pstr(&mut self) -> impl Future<Output = Result<String>> {
+        self.read_len_prefixed_string::<u8>(u8::MAX as u64)
+    }
+}
+
+impl<R, E> AsyncRead for EndianRead<R, E>
+where
+    R: AsyncRead + Unpin,
+    E: Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        Pin::new(self.get_mut().get_mut()).poll_read(cx, buf)
+    }
+}
+
+impl<R, E> AsyncReadNum for EndianRead<R, E>
+where
+    R: AsyncRead + Unpin,
+    E: StaticEndianness + Unpin,
+{
+    fn endianness(&self) -> Endianness {
+        E::endianness()
+    }
+}
+
+impl<R> AsyncRead for DynEndianRead<R>
+where
+    R: AsyncRead + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        Pin::new(self.get_mut().get_mut()).poll_read(cx, buf)
+    }
+}
+
+impl<R> AsyncReadNum for DynEndianRead<R>
+where
+    R: AsyncRead + Unpin,
+{
+    fn endianness(&self) -> Endianness {
+        DynEndianRead::endianness(self)
+    }
+}
+
+pub trait AsyncReadEndianExt: AsyncRead + Unpin {
+    fn be(&mut self) -> EndianRead<&mut Self, BigEndianReader> {
+        EndianRead::new(self)
+    }
+
+    fn le(&mut self) -> EndianRead<&mut Self, LittleEndianReader> {
+        EndianRead::new(self)
+    }
+
+    fn native(&mut self) -> EndianRead<&mut Self, NativeEndianReader> {
+        EndianRead::new(self)
+    }
+}
+
+impl<R> AsyncReadEndianExt for R where R: AsyncRead + Unpin + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use super::*;
+    use crate::async_stream::AsyncStream;
+
+    #[tokio::test]
+    async fn async_read_numbers() {
+        let data = [
+            0x12, 0x34, 0x56, 0x78, 0x12, 0x34, 0x56, 0x78, 0x3f, 0x80, 0x00, 0x00, 0xff,
+        ];
+        let mut reader = Cursor::new(data);
+        assert_eq!(reader.be().read_u32().await.unwrap(), 0x1234_5678);
+        assert_eq!(reader.le().read_u32().await.unwrap(), 0x7856_3412);
+        assert_eq!(reader.be().read_f32().await.unwrap(), 1.0);
+        assert_eq!(reader.native().read_i8().await.unwrap(), -1);
+        let err = reader.be().read_u16().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[tokio::test]
+    async fn async_read_dynamic_endianness() {
+        let mut reader = DynEndianRead::new(&[0x01, 0x02, 0x01, 0x02][..], Endianness::Big);
+        assert_eq!(reader.read_u16().await.unwrap(), 0x0102);
+        reader.set_endianness(Endianness::Little);
+        assert_eq!(reader.read_num::<u16>().await.unwrap(), 0x0201);
+    }
+
+    #[tokio::test]
+    async fn async_read_varint_and_strings() {
+        let mut data = vec![0xac, 0x02];
+        data.extend_from_slice(b"name\0");
+        data.extend_from_slice(&[0x00, 0x03]);
+        data.extend_from_slice(b"abc");
+        data.push(2);
+        data.extend_from_slice(b"hi");
+        let mut cursor = Cursor::new(data);
+        let mut stream = AsyncStream::new(&mut cursor).await.unwrap();
+        let mut reader = stream.be();
+        assert_eq!(reader.read_varint().await.unwrap(), 300);
+        assert_eq!(reader.read_cstr(16).await.unwrap(), "name");
+        assert_eq!(
+            reader.read_len_prefixed_string::<u16>(8).await.unwrap(),
+            "abc"
+        );
+        assert_eq!(reader.read_pstr().await.unwrap(), "hi");
+    }
+
+    #[tokio::test]
+    async fn async_read_errors() {
+        let mut reader = Cursor::new([0xff; 11]);
+        let err = reader.be().read_varint().await.unwrap_err();
+        assert_eq!(err.to_string(), "varint overflows 64 bits");
+        let mut reader = Cursor::new(b"unterminated");
+        let err = reader.be().read_cstr(4).await.unwrap_err();
+        assert_eq!(err.to_string(), "no NUL terminator found within 4 bytes");
+        let mut reader = Cursor::new([0x00, 0x09]);
+        let err = reader
+            .be()
+            .read_len_prefixed_bytes::<u16>(4)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "length prefix 9 exceeds the maximum of 4");
+    }
+}
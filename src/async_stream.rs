@@ -0,0 +1,378 @@
+use std::{
+    io::{Error, ErrorKind, Result, SeekFrom},
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncSeek, AsyncSeekExt, AsyncWrite, ReadBuf};
+
+enum Position<'a> {
+    Owned(u64),
+    Borrowed(&'a mut u64),
+}
+
+impl Position<'_> {
+    fn get(&self) -> u64 {
+        match self {
+            Position::Owned(pos) => *pos,
+            Position::Borrowed(pos) => **pos,
+        }
+    }
+
+    fn set(&mut self, value: u64) {
+        match self {
+            Position::Owned(pos) => *pos = value,
+            Position::Borrowed(pos) => **pos = value,
+        }
+    }
+
+    fn borrow(&mut self) -> Position<'_> {
+        match self {
+            Position::Owned(pos) => Position::Borrowed(pos),
+            Position::Borrowed(pos) => Position::Borrowed(pos),
+        }
+    }
+}
+
+enum SeekState {
+    Idle,
+    Length(SeekFrom),
+    Target { pos: u64, invalid: bool },
+}
+
+pub struct AsyncStream<'a, T> {
+    inner: &'a mut T,
+    origin_pos: u64,
+    limit_pos: u64,
+    pos: Position<'a>,
+    seek: SeekState,
+}
+
+impl<'a, T> AsyncStream<'a, T> {
+    pub fn with_position(inner: &'a mut T, pos: u64) -> AsyncStream<'a, T> {
+        AsyncStream {
+            inner,
+            origin_pos: 0,
+            limit_pos: u64::MAX,
+            pos: Position::Owned(pos),
+            seek: SeekState::Idle,
+        }
+    }
+
+    pub fn borrow_chunk(&mut self, limit: Option<u64>) -> Result<AsyncStream<'_, T>> {
+        let origin_pos = self.pos.get();
+        let limit_pos = match limit {
+            None => u64::MAX,
+            Some(l) => std::cmp::min(origin_pos.saturating_add(l), self.limit_pos),
+        };
+        Ok(AsyncStream {
+            inner: self.inner,
+            origin_pos,
+            limit_pos,
+            pos: self.pos.borrow(),
+            seek: SeekState::Idle,
+        })
+    }
+
+    pub fn position(&self) -> u64 {
+        self.pos.get() - self.origin_pos
+    }
+
+    fn room(&self) -> u64 {
+        self.limit_pos.saturating_sub(self.pos.get())
+    }
+
+    fn advance(&mut self, len: usize) {
+        self.pos.set(self.pos.get() + len as u64);
+    }
+
+    fn resolve_seek(&mut self, position: SeekFrom, len: u64) -> u64 {
+        let end_position = std::cmp::min(len, self.limit_pos);
+        let final_position = match position {
+            SeekFrom::Current(n) => self.pos.get().checked_add_signed(n),
+            SeekFrom::End(n) => end_position.checked_add_signed(n),
+            SeekFrom::Start(n) => self.origin_pos.checked_add(n),
+        }
+        .filter(|f| *f >= self.origin_pos && *f <= end_position);
+        let (pos, invalid) = match final_position {
+            Some(f) => (f, false),
+            None => (self.pos.get(), true),
+        };
+        self.seek = SeekState::Target { pos, invalid };
+        pos
+    }
+
+    fn finish_seek(&mut self, pos: u64, invalid: bool) -> Result<u64> {
+        self.seek = SeekState::Idle;
+        self.pos.set(pos);
+        if invalid {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            ));
+        }
+        Ok(pos - self.origin_pos)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<'a, T> AsyncStream<'a, T>
+where
+    T: AsyncSeek + Unpin,
+{
+    pub async fn new(inner: &'a mut T) -> Result<AsyncStream<'a, T>> {
+        let pos = inner.stream_position().await?;
+        Ok(AsyncStream::with_position(inner, pos))
+    }
+
+    pub async fn remainder_len(&mut self) -> Result<u64> {
+        let len = self.inner.seek(SeekFrom::End(0)).await?;
+        let pos = self.pos.get();
+        self.inner.seek(SeekFrom::Start(pos)).await?;
+        Ok(std::cmp::min(len, self.limit_pos).saturating_sub(pos))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> AsyncRead for AsyncStream<'_, T>
+where
+    T: AsyncRead + AsyncSeek + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        let len = std::cmp::min(this.room(), buf.remaining() as u64) as usize;
+        if len == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        let mut limited = ReadBuf::new(buf.initialize_unfilled_to(len));
+        ready!(Pin::new(&mut *this.inner).poll_read(cx, &mut limited))?;
+        let read = limited.filled().len();
+        buf.advance(read);
+        this.advance(read);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> AsyncWrite for AsyncStream<'_, T>
+where
+    T: AsyncWrite + AsyncSeek + Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+        let len = std::cmp::min(this.room(), buf.len() as u64) as usize;
+        if len == 0 {
+            return Poll::Ready(Ok(0));
+        }
+        let written = ready!(Pin::new(&mut *this.inner).poll_write(cx, &buf[..len]))?;
+        this.advance(written);
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(&mut *self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(&mut *self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> AsyncSeek for AsyncStream<'_, T>
+where
+    T: AsyncSeek + Unpin,
+{
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> Result<()> {
+        let this = self.get_mut();
+        if !matches!(this.seek, SeekState::Idle) {
+            return Err(Error::other("other seek operation is pending"));
+        }
+        Pin::new(&mut *this.inner).start_seek(SeekFrom::End(0))?;
+        this.seek = SeekState::Length(position);
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<u64>> {
+        let this = self.get_mut();
+        loop {
+            match this.seek {
+                SeekState::Idle => return Poll::Ready(Ok(this.pos.get() - this.origin_pos)),
+                SeekState::Length(position) => {
+                    let len = ready!(Pin::new(&mut *this.inner).poll_complete(cx))?;
+                    let pos = this.resolve_seek(position, len);
+                    Pin::new(&mut *this.inner).start_seek(SeekFrom::Start(pos))?;
+                }
+                SeekState::Target { pos, invalid } => {
+                    ready!(Pin::new(&mut *this.inner).poll_complete(cx))?;
+                    return Poll::Ready(this.finish_seek(pos, invalid));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<T> futures_io::AsyncRead for AsyncStream<'_, T>
+where
+    T: futures_io::AsyncRead + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+        let len = std::cmp::min(this.room(), buf.len() as u64) as usize;
+        if len == 0 {
+            return Poll::Ready(Ok(0));
+        }
+        let read = ready!(Pin::new(&mut *this.inner).poll_read(cx, &mut buf[..len]))?;
+        this.advance(read);
+        Poll::Ready(Ok(read))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<T> futures_io::AsyncWrite for AsyncStream<'_, T>
+where
+    T: futures_io::AsyncWrite + Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+        let len = std::cmp::min(this.room(), buf.len() as u64) as usize;
+        if len == 0 {
+            return Poll::Ready(Ok(0));
+        }
+        let written = ready!(Pin::new(&mut *this.inner).poll_write(cx, &buf[..len]))?;
+        this.advance(written);
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(&mut *self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(&mut *self.get_mut().inner).poll_close(cx)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<T> futures_io::AsyncSeek for AsyncStream<'_, T>
+where
+    T: futures_io::AsyncSeek + Unpin,
+{
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        position: SeekFrom,
+    ) -> Poll<Result<u64>> {
+        let this = self.get_mut();
+        loop {
+            match this.seek {
+                SeekState::Idle => this.seek = SeekState::Length(position),
+                SeekState::Length(position) => {
+                    let len = ready!(Pin::new(&mut *this.inner).poll_seek(cx, SeekFrom::End(0)))?;
+                    this.resolve_seek(position, len);
+                }
+                SeekState::Target { pos, invalid } => {
+                    ready!(Pin::new(&mut *this.inner).poll_seek(cx, SeekFrom::Start(pos)))?;
+                    return Poll::Ready(this.finish_seek(pos, invalid));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_chunk_limits() {
+        use std::io::Cursor;
+
+        use tokio::io::AsyncReadExt;
+
+        let mut cursor = Cursor::new(b"0123456789".to_vec());
+        let mut stream = AsyncStream::new(&mut cursor).await.unwrap();
+        assert_eq!(stream.seek(SeekFrom::Start(2)).await.unwrap(), 2);
+        let mut chunk = stream.borrow_chunk(Some(5)).unwrap();
+        assert_eq!(chunk.remainder_len().await.unwrap(), 5);
+        let mut text = String::new();
+        chunk.read_to_string(&mut text).await.unwrap();
+        assert_eq!(text, "23456");
+        assert_eq!(chunk.seek(SeekFrom::End(-2)).await.unwrap(), 3);
+        let mut inner = chunk.borrow_chunk(None).unwrap();
+        assert_eq!(inner.read_u8().await.unwrap(), b'5');
+        assert_eq!(chunk.position(), 4);
+        assert_eq!(stream.position(), 6);
+        assert_eq!(stream.read_u8().await.unwrap(), b'6');
+        assert_eq!(cursor.position(), 7);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_seek_errors() {
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(b"0123456789".to_vec());
+        let mut stream = AsyncStream::new(&mut cursor).await.unwrap();
+        stream.seek(SeekFrom::Start(4)).await.unwrap();
+        let mut chunk = stream.borrow_chunk(Some(3)).unwrap();
+        chunk.seek(SeekFrom::Current(1)).await.unwrap();
+        let err = chunk.seek(SeekFrom::Start(4)).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(chunk.seek(SeekFrom::Current(-2)).await.is_err());
+        assert_eq!(chunk.position(), 1);
+        assert_eq!(chunk.seek(SeekFrom::End(0)).await.unwrap(), 3);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_write_limit() {
+        use std::io::Cursor;
+
+        use tokio::io::AsyncWriteExt;
+
+        let mut cursor = Cursor::new(vec![0u8; 6]);
+        let mut stream = AsyncStream::new(&mut cursor).await.unwrap();
+        stream.write_all(b"ab").await.unwrap();
+        let mut chunk = stream.borrow_chunk(Some(2)).unwrap();
+        let err = chunk.write_all(b"cde").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        stream.write_all(b"f").await.unwrap();
+        assert_eq!(cursor.get_ref(), b"abcdf\x00");
+    }
+
+    #[cfg(feature = "futures-io")]
+    #[test]
+    fn futures_chunk_limits() {
+        use futures::{
+            executor::block_on,
+            io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, Cursor},
+        };
+
+        block_on(async {
+            let mut cursor = Cursor::new(b"0123456789".to_vec());
+            let mut stream = AsyncStream::with_position(&mut cursor, 0);
+            stream.seek(SeekFrom::Start(3)).await.unwrap();
+            let mut chunk = stream.borrow_chunk(Some(4)).unwrap();
+            let mut text = String::new();
+            chunk.read_to_string(&mut text).await.unwrap();
+            assert_eq!(text, "3456");
+            assert!(chunk.seek(SeekFrom::End(1)).await.is_err());
+            assert_eq!(chunk.seek(SeekFrom::End(-1)).await.unwrap(), 3);
+            assert_eq!(chunk.write(b"xy").await.unwrap(), 1);
+            assert_eq!(stream.position(), 7);
+            assert_eq!(cursor.get_ref(), b"012345x789");
+        });
+    }
+}
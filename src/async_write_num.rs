@@ -0,0 +1,226 @@
+use std::{
+    future::Future,
+    io::Result,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tokio::io::{self, AsyncWrite};
+
+use crate::{
+    read_num::{Endianness, StaticEndianness},
+    string::{check_interior_nul, len_prefix, pstr_len},
+    tlv::encode_varint,
+    write_num::{
+        BigEndianWriter, EndianWrite, LittleEndianWriter, NativeEndianWriter, ToEndianBytes,
+    },
+};
+
+pub trait AsyncWriteNum: AsyncWrite + Unpin {
+    fn endianness(&self) -> Endianness;
+
+    fn write_num<N: ToEndianBytes>(&mut self, value: N) -> impl Future<Output = Result<()>> {
+        async move {
+            let bytes = value.to_endian_bytes(AsyncWriteNum::endianness(self));
+            io::AsyncWriteExt::write_all(self, bytes.as_ref()).await
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_u16(&mut self, value: u16) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_u32(&mut self, value: u32) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_u64(&mut self, value: u64) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_u128(&mut self, value: u128) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_i8(&mut self, value: i8) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_i16(&mut self, value: i16) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_i32(&mut self, value: i32) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_i64(&mut self, value: i64) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_i128(&mut self, value: i128) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_usize(&mut self, value: usize) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_isize(&mut self, value: isize) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_f32(&mut self, value: f32) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_f64(&mut self, value: f64) -> impl Future<Output = Result<()>> {
+        self.write_num(value)
+    }
+
+    fn write_varint(&mut self, value: u64) -> impl Future<Output = Result<()>> {
+        async move {
+            let (buf, len) = encode_varint(value);
+            io::AsyncWriteExt::write_all(self, &buf[..len]).await
+        }
+    }
+
+    fn write_cstr(&mut self, value: &str) -> impl Future<Output = Result<()>> {
+        async move {
+            check_interior_nul(value)?;
+            io::AsyncWriteExt::write_all(self, value.as_bytes()).await?;
+            io::AsyncWriteExt::write_all(self, &[0]).await
+        }
+    }
+
+    fn write_len_prefixed_bytes<L>(&mut self, value: &[u8]) -> impl Future<Output = Result<()>>
+    where
+        L: ToEndianBytes + TryFrom<usize>,
+    {
+        async move {
+            self.write_num(len_prefix::<L>(value.len())?).await?;
+            io::AsyncWriteExt::write_all(self, value).await
+        }
+    }
+
+    fn write_len_prefixed_str<L>(&mut self, value: &str) -> impl Future<Output = Result<()>>
+    where
+        L: ToEndianBytes + TryFrom<usize>,
+    {
+        self.write_len_prefixed_bytes::<L>(value.as_bytes())
+    }
+
+    fn write_pstr(&mut self, value: &str) -> impl Future<Output = Result<()>> {
+        async move {
+            self.write_num(pstr_len(value)?).await?;
+            io::AsyncWriteExt::write_all(self, value.as_bytes()).await
+        }
+    }
+}
+
+impl<W, E> AsyncWrite for EndianWrite<W, E>
+where
+    W: AsyncWrite + Unpin,
+    E: Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        Pin::new(self.get_mut().get_mut()).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(self.get_mut().get_mut()).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(self.get_mut().get_mut()).poll_shutdown(cx)
+    }
+}
+
+impl<W, E> AsyncWriteNum for EndianWrite<W, E>
+where
+    W: AsyncWrite + Unpin,
+    E: StaticEndianness + Unpin,
+{
+    fn endianness(&self) -> Endianness {
+        E::endianness()
+    }
+}
+
+pub trait AsyncWriteEndianExt: AsyncWrite + Unpin {
+    fn be_writer(&mut self) -> EndianWrite<&mut Self, BigEndianWriter> {
+        EndianWrite::new(self)
+    }
+
+    fn le_writer(&mut self) -> EndianWrite<&mut Self, LittleEndianWriter> {
+        EndianWrite::new(self)
+    }
+
+    fn native_writer(&mut self) -> EndianWrite<&mut Self, NativeEndianWriter> {
+        EndianWrite::new(self)
+    }
+}
+
+impl<W> AsyncWriteEndianExt for W where W: AsyncWrite + Unpin + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use super::*;
+    use crate::async_read_num::{AsyncReadEndianExt, AsyncReadNum};
+
+    #[tokio::test]
+    async fn async_write_numbers() {
+        let mut buf = Vec::new();
+        buf.be_writer().write_u32(0x1234_5678).await.unwrap();
+        buf.le_writer().write_u16(0x0102).await.unwrap();
+        buf.be_writer().write_f32(1.0).await.unwrap();
+        buf.native_writer().write_i8(-1).await.unwrap();
+        assert_eq!(
+            buf,
+            [0x12, 0x34, 0x56, 0x78, 0x02, 0x01, 0x3f, 0x80, 0x00, 0x00, 0xff]
+        );
+    }
+
+    #[tokio::test]
+    async fn async_write_round_trip() {
+        let mut buf = Vec::new();
+        let mut writer = buf.le_writer();
+        writer.write_varint(300).await.unwrap();
+        writer.write_cstr("name").await.unwrap();
+        writer.write_len_prefixed_str::<u16>("abc").await.unwrap();
+        writer.write_pstr("hi").await.unwrap();
+        writer.write_i64(-42).await.unwrap();
+        let mut reader = std::io::Cursor::new(buf);
+        let mut reader = reader.le();
+        assert_eq!(reader.read_varint().await.unwrap(), 300);
+        assert_eq!(reader.read_cstr(8).await.unwrap(), "name");
+        assert_eq!(
+            reader.read_len_prefixed_string::<u16>(8).await.unwrap(),
+            "abc"
+        );
+        assert_eq!(reader.read_pstr().await.unwrap(), "hi");
+        assert_eq!(reader.read_i64().await.unwrap(), -42);
+    }
+
+    #[tokio::test]
+    async fn async_write_errors() {
+        let mut buf = Vec::new();
+        let err = buf.be_writer().write_cstr("a\0b").await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "string contains an interior NUL at index 1"
+        );
+        let err = buf
+            .be_writer()
+            .write_len_prefixed_bytes::<u8>(&[0; 300])
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(buf.is_empty());
+    }
+}
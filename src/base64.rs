@@ -0,0 +1,356 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Base64Alphabet {
+    Standard,
+    UrlSafe,
+}
+
+impl Base64Alphabet {
+    fn symbols(self) -> &'static [u8; 64] {
+        match self {
+            Base64Alphabet::Standard => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/"
+            }
+            Base64Alphabet::UrlSafe => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"
+            }
+        }
+    }
+
+    fn decode(self, symbol: u8) -> Option<u8> {
+        match (symbol, self) {
+            (b'A'..=b'Z', _) => Some(symbol - b'A'),
+            (b'a'..=b'z', _) => Some(symbol - b'a' + 26),
+            (b'0'..=b'9', _) => Some(symbol - b'0' + 52),
+            (b'+', Base64Alphabet::Standard) | (b'-', Base64Alphabet::UrlSafe) => Some(62),
+            (b'/', Base64Alphabet::Standard) | (b'_', Base64Alphabet::UrlSafe) => Some(63),
+            _ => None,
+        }
+    }
+}
+
+pub struct Base64Reader<R>
+where
+    R: Read,
+{
+    inner: R,
+    alphabet: Base64Alphabet,
+    input: Box<[u8; 1024]>,
+    input_pos: usize,
+    input_len: usize,
+    output: [u8; 3],
+    output_pos: usize,
+    output_len: usize,
+    finished: bool,
+}
+
+impl<R> Base64Reader<R>
+where
+    R: Read,
+{
+    pub fn new(inner: R, alphabet: Base64Alphabet) -> Base64Reader<R> {
+        Base64Reader {
+            inner,
+            alphabet,
+            input: Box::new([0u8; 1024]),
+            input_pos: 0,
+            input_len: 0,
+            output: [0u8; 3],
+            output_pos: 0,
+            output_len: 0,
+            finished: false,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn next_symbol(&mut self) -> Result<Option<u8>> {
+        loop {
+            if self.input_pos == self.input_len {
+                self.input_len = match self.inner.read(&mut self.input[..]) {
+                    Ok(n) => n,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                };
+                self.input_pos = 0;
+                if self.input_len == 0 {
+                    return Ok(None);
+                }
+            }
+            let symbol = self.input[self.input_pos];
+            self.input_pos += 1;
+            if !symbol.is_ascii_whitespace() {
+                return Ok(Some(symbol));
+            }
+        }
+    }
+
+    fn decode_quantum(&mut self) -> Result<()> {
+        let mut sextets = [0u8; 4];
+        let mut count = 0;
+        let mut padding = 0;
+        while count + padding < 4 {
+            match self.next_symbol()? {
+                None if count == 0 && padding == 0 => {
+                    self.finished = true;
+                    return Ok(());
+                }
+                None if padding == 0 && count > 1 => break,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "truncated base64 quantum",
+                    ))
+                }
+                Some(b'=') if count >= 2 => padding += 1,
+                Some(symbol) if padding == 0 => {
+                    sextets[count] = self.alphabet.decode(symbol).ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::InvalidData,
+                            format!("invalid base64 symbol {:?}", symbol as char),
+                        )
+                    })?;
+                    count += 1;
+                }
+                Some(symbol) => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("unexpected base64 symbol {:?} in padding", symbol as char),
+                    ))
+                }
+            }
+        }
+        let value = (sextets[0] as u32) << 18
+            | (sextets[1] as u32) << 12
+            | (sextets[2] as u32) << 6
+            | sextets[3] as u32;
+        self.output = [(value >> 16) as u8, (value >> 8) as u8, value as u8];
+        self.output_pos = 0;
+        self.output_len = count - 1;
+        if count < 4 {
+            self.finished = true;
+            if let Some(symbol) = self.next_symbol()? {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("base64 symbol {:?} after the final quantum", symbol as char),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R> Read for Base64Reader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut len = 0;
+        while len < buf.len() {
+            if self.output_pos == self.output_len {
+                if self.finished || (len > 0 && self.input_pos == self.input_len) {
+                    break;
+                }
+                self.decode_quantum()?;
+                continue;
+            }
+            let n = std::cmp::min(buf.len() - len, self.output_len - self.output_pos);
+            buf[len..len + n].copy_from_slice(&self.output[self.output_pos..self.output_pos + n]);
+            self.output_pos += n;
+            len += n;
+        }
+        Ok(len)
+    }
+}
+
+pub struct Base64Writer<W>
+where
+    W: Write,
+{
+    inner: W,
+    alphabet: Base64Alphabet,
+    padding: bool,
+    pending: [u8; 3],
+    pending_len: usize,
+}
+
+impl<W> Base64Writer<W>
+where
+    W: Write,
+{
+    pub fn new(inner: W, alphabet: Base64Alphabet) -> Base64Writer<W> {
+        Base64Writer {
+            inner,
+            alphabet,
+            padding: true,
+            pending: [0u8; 3],
+            pending_len: 0,
+        }
+    }
+
+    pub fn with_padding(mut self, padding: bool) -> Base64Writer<W> {
+        self.padding = padding;
+        self
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    fn encode(&self, bytes: &[u8], out: &mut Vec<u8>) {
+        let symbols = self.alphabet.symbols();
+        let mut triple = [0u8; 3];
+        triple[..bytes.len()].copy_from_slice(bytes);
+        let value = (triple[0] as u32) << 16 | (triple[1] as u32) << 8 | triple[2] as u32;
+        for index in 0..=bytes.len() {
+            out.push(symbols[(value >> (18 - index * 6)) as usize & 0x3f]);
+        }
+        if self.padding {
+            out.extend(std::iter::repeat_n(b'=', 3 - bytes.len()));
+        }
+    }
+
+    pub fn finish(mut self) -> Result<W> {
+        if self.pending_len > 0 {
+            let mut out = Vec::with_capacity(4);
+            self.encode(&self.pending[..self.pending_len], &mut out);
+            self.inner.write_all(&out)?;
+            self.pending_len = 0;
+        }
+        Ok(self.inner)
+    }
+}
+
+impl<W> Write for Base64Writer<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let mut out = Vec::with_capacity((buf.len() / 3 + 1) * 4);
+        let mut rest = buf;
+        if self.pending_len > 0 {
+            let n = std::cmp::min(3 - self.pending_len, rest.len());
+            self.pending[self.pending_len..self.pending_len + n].copy_from_slice(&rest[..n]);
+            self.pending_len += n;
+            rest = &rest[n..];
+            if self.pending_len < 3 {
+                return Ok(buf.len());
+            }
+            let pending = self.pending;
+            self.encode(&pending, &mut out);
+            self.pending_len = 0;
+        }
+        let mut triples = rest.chunks_exact(3);
+        for triple in &mut triples {
+            self.encode(triple, &mut out);
+        }
+        let remainder = triples.remainder();
+        self.pending[..remainder.len()].copy_from_slice(remainder);
+        self.pending_len = remainder.len();
+        self.inner.write_all(&out)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::*;
+    use crate::read_num::{ReadEndianExt, ReadNum};
+
+    fn encode(data: &[u8], alphabet: Base64Alphabet, padding: bool) -> String {
+        let mut writer = Base64Writer::new(Vec::new(), alphabet).with_padding(padding);
+        for piece in data.chunks(2) {
+            writer.write_all(piece).unwrap();
+        }
+        String::from_utf8(writer.finish().unwrap()).unwrap()
+    }
+
+    fn decode(text: &str, alphabet: Base64Alphabet) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        let inner = BufReader::with_capacity(3, Cursor::new(text.as_bytes().to_vec()));
+        Base64Reader::new(inner, alphabet).read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    #[test]
+    fn rfc4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (plain, encoded) in vectors {
+            assert_eq!(
+                encode(plain.as_bytes(), Base64Alphabet::Standard, true),
+                encoded
+            );
+            assert_eq!(
+                decode(encoded, Base64Alphabet::Standard).unwrap(),
+                plain.as_bytes()
+            );
+            let unpadded = encoded.trim_end_matches('=');
+            assert_eq!(
+                encode(plain.as_bytes(), Base64Alphabet::Standard, false),
+                unpadded
+            );
+            assert_eq!(
+                decode(unpadded, Base64Alphabet::Standard).unwrap(),
+                plain.as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn url_safe_alphabet() {
+        let data = [0xfb, 0xff, 0xbf];
+        assert_eq!(encode(&data, Base64Alphabet::Standard, true), "+/+/");
+        assert_eq!(encode(&data, Base64Alphabet::UrlSafe, true), "-_-_");
+        assert_eq!(decode("-_-_", Base64Alphabet::UrlSafe).unwrap(), data);
+        let result = decode("-_-_", Base64Alphabet::Standard);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn decode_with_whitespace_and_numeric_reads() {
+        let text = "AAEC\r\nAwQF\n BgcI";
+        let inner = Cursor::new(text.as_bytes());
+        let mut reader = Base64Reader::new(inner, Base64Alphabet::Standard);
+        let mut reader = reader.be();
+        assert_eq!(reader.read_u16().unwrap(), 0x0001);
+        assert_eq!(reader.read_u32().unwrap(), 0x0203_0405);
+        assert_eq!(reader.read_u16().unwrap(), 0x0607);
+        assert_eq!(reader.read_u8().unwrap(), 0x08);
+        let result = reader.read_u8();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn decode_invalid() {
+        for text in ["Z", "Zg=", "Zg==Zg==", "Z===", "Zm9v!", "Zg=x"] {
+            let result = decode(text, Base64Alphabet::Standard);
+            assert_eq!(
+                result.unwrap_err().kind(),
+                ErrorKind::InvalidData,
+                "{}",
+                text
+            );
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::io::{Error, ErrorKind, Read, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BcdFormat {
+    Packed,
+    Unpacked,
+}
+
+fn invalid_digit(nibble: u8) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("invalid BCD digit {:#x}", nibble),
+    )
+}
+
+fn decode_digit(nibble: u8) -> Result<u64> {
+    match nibble {
+        0..=9 => Ok(nibble as u64),
+        _ => Err(invalid_digit(nibble)),
+    }
+}
+
+fn read_bcd<R>(reader: &mut R, digits: usize, format: BcdFormat) -> Result<u64>
+where
+    R: Read + ?Sized,
+{
+    let mut buf = [0u8; 16];
+    let len = match format {
+        BcdFormat::Packed => digits / 2,
+        BcdFormat::Unpacked => digits,
+    };
+    reader.read_exact(&mut buf[..len])?;
+    let mut value = 0u64;
+    for byte in &buf[..len] {
+        value = match format {
+            BcdFormat::Packed => {
+                value * 100 + decode_digit(byte >> 4)? * 10 + decode_digit(byte & 0x0f)?
+            }
+            BcdFormat::Unpacked if byte >> 4 == 0 => value * 10 + decode_digit(*byte)?,
+            BcdFormat::Unpacked => return Err(invalid_digit(*byte)),
+        };
+    }
+    Ok(value)
+}
+
+pub trait ReadBcd: Read {
+    fn read_bcd_u8(&mut self, format: BcdFormat) -> Result<u8> {
+        Ok(read_bcd(self, 2, format)? as u8)
+    }
+
+    fn read_bcd_u16(&mut self, format: BcdFormat) -> Result<u16> {
+        Ok(read_bcd(self, 4, format)? as u16)
+    }
+
+    fn read_bcd_u32(&mut self, format: BcdFormat) -> Result<u32> {
+        Ok(read_bcd(self, 8, format)? as u32)
+    }
+}
+
+impl<R> ReadBcd for R where R: Read + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn read_packed_bcd() {
+        let mut cursor = Cursor::new([0x42, 0x12, 0x34, 0x98, 0x76, 0x54, 0x32]);
+        assert_eq!(cursor.read_bcd_u8(BcdFormat::Packed).unwrap(), 42);
+        assert_eq!(cursor.read_bcd_u16(BcdFormat::Packed).unwrap(), 1234);
+        assert_eq!(cursor.read_bcd_u32(BcdFormat::Packed).unwrap(), 98765432);
+    }
+
+    #[test]
+    fn read_unpacked_bcd() {
+        let mut cursor = Cursor::new([0x04, 0x02, 0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(cursor.read_bcd_u8(BcdFormat::Unpacked).unwrap(), 42);
+        assert_eq!(cursor.read_bcd_u16(BcdFormat::Unpacked).unwrap(), 1234);
+    }
+
+    #[test]
+    fn read_bcd_with_invalid_nibble() {
+        let mut cursor = Cursor::new([0x4a]);
+        let result = cursor.read_bcd_u8(BcdFormat::Packed);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let mut cursor = Cursor::new([0x14, 0x02]);
+        let result = cursor.read_bcd_u8(BcdFormat::Unpacked);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_bcd_when_eof() {
+        let mut cursor = Cursor::new([0x12]);
+        let result = cursor.read_bcd_u16(BcdFormat::Packed);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}
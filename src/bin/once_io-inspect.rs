@@ -0,0 +1,427 @@
+use std::{
+    cell::Cell,
+    env,
+    fs::File,
+    io::{self, Error, ErrorKind, Read, Result, Seek, SeekFrom, Write},
+    process,
+    rc::Rc,
+};
+
+use once_io::{
+    adler::Adler32,
+    crc::Crc32,
+    frame::FrameFormat,
+    hexdump::HexdumpConfig,
+    read_num::Endianness,
+    riff::RiffFormat,
+    tlv::{FieldEncoding, TlvFormat},
+    Stream,
+};
+
+const USAGE: &str = "usage: once_io-inspect <file> [options]
+
+walkers (default: the whole file as one chunk):
+  --range OFFSET:LEN    inspect an explicit region, may be repeated
+  --tlv TAG,LEN         walk TLV records with the given field encodings
+  --riff | --iff        walk RIFF or IFF chunks, descending into containers
+  --frames LEN          walk length-prefixed frames
+
+reports:
+  --hexdump[=BYTES]     hexdump each chunk (default: first 256 bytes)
+  --width N             hexdump row width (default: 16)
+  --digest NAME         crc32, adler32 or sha256, may be repeated
+  --stats               byte entropy and distinct byte count
+
+field encodings: u8, u16be, u16le, u32be, u32le, u64be, u64le, varint";
+
+enum Walker {
+    Whole,
+    Ranges(Vec<(u64, u64)>),
+    Tlv(TlvFormat),
+    Riff(RiffFormat),
+    Frames(FrameFormat),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DigestKind {
+    Crc32,
+    Adler32,
+    Sha256,
+}
+
+struct Options {
+    path: String,
+    walker: Walker,
+    hexdump: Option<u64>,
+    width: usize,
+    digests: Vec<DigestKind>,
+    stats: bool,
+}
+
+fn invalid_arg(message: String) -> Error {
+    Error::new(ErrorKind::InvalidInput, message)
+}
+
+fn parse_u64(value: &str) -> Result<u64> {
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed.map_err(|_| invalid_arg(format!("invalid number {:?}", value)))
+}
+
+fn parse_encoding(value: &str) -> Result<FieldEncoding> {
+    Ok(match value {
+        "u8" => FieldEncoding::U8,
+        "u16be" => FieldEncoding::U16(Endianness::Big),
+        "u16le" => FieldEncoding::U16(Endianness::Little),
+        "u32be" => FieldEncoding::U32(Endianness::Big),
+        "u32le" => FieldEncoding::U32(Endianness::Little),
+        "u64be" => FieldEncoding::U64(Endianness::Big),
+        "u64le" => FieldEncoding::U64(Endianness::Little),
+        "varint" => FieldEncoding::Varint,
+        _ => return Err(invalid_arg(format!("unknown field encoding {:?}", value))),
+    })
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options> {
+    let mut options = Options {
+        path: String::new(),
+        walker: Walker::Whole,
+        hexdump: None,
+        width: 16,
+        digests: Vec::new(),
+        stats: false,
+    };
+    let mut path = None;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| invalid_arg(format!("{} expects a value", name)))
+        };
+        match arg.as_str() {
+            "--range" => {
+                let range = value("--range")?;
+                let (offset, len) = range
+                    .split_once(':')
+                    .ok_or_else(|| invalid_arg(format!("invalid range {:?}", range)))?;
+                let range = (parse_u64(offset)?, parse_u64(len)?);
+                match &mut options.walker {
+                    Walker::Ranges(ranges) => ranges.push(range),
+                    walker => *walker = Walker::Ranges(vec![range]),
+                }
+            }
+            "--tlv" => {
+                let fields = value("--tlv")?;
+                let (tag, len) = fields
+                    .split_once(',')
+                    .ok_or_else(|| invalid_arg(format!("invalid TLV fields {:?}", fields)))?;
+                options.walker =
+                    Walker::Tlv(TlvFormat::new(parse_encoding(tag)?, parse_encoding(len)?));
+            }
+            "--riff" => options.walker = Walker::Riff(RiffFormat::Riff),
+            "--iff" => options.walker = Walker::Riff(RiffFormat::Iff),
+            "--frames" => {
+                let length = parse_encoding(&value("--frames")?)?;
+                options.walker = Walker::Frames(FrameFormat::new(length));
+            }
+            "--hexdump" => options.hexdump = Some(256),
+            "--width" => {
+                options.width = parse_u64(&value("--width")?)? as usize;
+                if options.width == 0 {
+                    return Err(invalid_arg("--width must not be zero".to_string()));
+                }
+            }
+            "--digest" => options.digests.push(match value("--digest")?.as_str() {
+                "crc32" => DigestKind::Crc32,
+                "adler32" => DigestKind::Adler32,
+                "sha256" => DigestKind::Sha256,
+                name => return Err(invalid_arg(format!("unknown digest {:?}", name))),
+            }),
+            "--stats" => options.stats = true,
+            _ if arg.starts_with("--hexdump=") => {
+                options.hexdump = Some(parse_u64(&arg["--hexdump=".len()..])?);
+            }
+            _ if arg.starts_with("--") => {
+                return Err(invalid_arg(format!("unknown option {:?}", arg)))
+            }
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(invalid_arg(format!("unexpected argument {:?}", arg))),
+        }
+    }
+    options.path = path.ok_or_else(|| invalid_arg("missing input file".to_string()))?;
+    Ok(options)
+}
+
+struct Tracked<T> {
+    inner: T,
+    pos: Rc<Cell<u64>>,
+}
+
+impl<T: Read> Read for Tracked<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.pos.set(self.pos.get() + len as u64);
+        Ok(len)
+    }
+}
+
+impl<T: Seek> Seek for Tracked<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let pos = self.inner.seek(pos)?;
+        self.pos.set(pos);
+        Ok(pos)
+    }
+}
+
+struct Inspector<'o, W> {
+    out: &'o mut W,
+    options: &'o Options,
+    pos: Rc<Cell<u64>>,
+    chunks: u64,
+    bytes: u64,
+}
+
+impl<W: Write> Inspector<'_, W> {
+    fn report<T, E>(&mut self, label: &str, chunk: &mut Stream<'_, T, E>) -> Result<()>
+    where
+        T: Read + Seek,
+    {
+        let offset = self.pos.get();
+        let len = chunk.remainder_len()?;
+        self.chunks += 1;
+        self.bytes += len;
+        writeln!(self.out, "{} at 0x{:x}, {} bytes", label, offset, len)?;
+        for digest in &self.options.digests {
+            chunk.seek(SeekFrom::Start(0))?;
+            match digest {
+                DigestKind::Crc32 => writeln!(
+                    self.out,
+                    "  crc32   {:08x}",
+                    chunk.digest_remainder(Crc32::new())?
+                )?,
+                DigestKind::Adler32 => writeln!(
+                    self.out,
+                    "  adler32 {:08x}",
+                    chunk.digest_remainder(Adler32::new())?
+                )?,
+                DigestKind::Sha256 => {
+                    let digest = chunk.digest_remainder(sha2::Sha256::default())?;
+                    let hex = digest
+                        .iter()
+                        .map(|b| format!("{:02x}", b))
+                        .collect::<String>();
+                    writeln!(self.out, "  sha256  {}", hex)?
+                }
+            }
+        }
+        if self.options.stats {
+            chunk.seek(SeekFrom::Start(0))?;
+            let histogram = chunk.histogram()?;
+            writeln!(
+                self.out,
+                "  entropy {:.3} bits/byte, {} distinct bytes",
+                histogram.entropy(),
+                histogram.distinct()
+            )?;
+        }
+        if let Some(max_len) = self.options.hexdump {
+            chunk.seek(SeekFrom::Start(0))?;
+            let config = HexdumpConfig::new()
+                .width(self.options.width)
+                .absolute_offsets(true);
+            chunk
+                .borrow_chunk(Some(max_len))?
+                .hexdump(self.out, &config)?;
+            if len > max_len {
+                writeln!(self.out, "  ... {} more bytes", len - max_len)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn run<W: Write>(options: &Options, out: &mut W) -> Result<()> {
+    let pos = Rc::new(Cell::new(0));
+    let mut file = Tracked {
+        inner: File::open(&options.path)?,
+        pos: pos.clone(),
+    };
+    let mut stream = Stream::new(&mut file);
+    let mut inspector = Inspector {
+        out,
+        options,
+        pos,
+        chunks: 0,
+        bytes: 0,
+    };
+    match &options.walker {
+        Walker::Whole => inspector.report("file", &mut stream)?,
+        Walker::Ranges(ranges) => {
+            for (i, &(offset, len)) in ranges.iter().enumerate() {
+                stream.seek(SeekFrom::Start(offset))?;
+                let mut chunk = stream.borrow_chunk(Some(len))?;
+                if chunk.remainder_len()? < len {
+                    return Err(Error::new(
+                        ErrorKind::UnexpectedEof,
+                        format!("range {} ends past the end of the file", i),
+                    ));
+                }
+                inspector.report(&format!("range {}", i), &mut chunk)?;
+            }
+        }
+        Walker::Tlv(format) => format.for_each(&mut stream, |tag, chunk| {
+            inspector.report(&format!("tag {}", tag), chunk)
+        })?,
+        Walker::Riff(format) => format.walk(&mut stream, |path, chunk| {
+            let label = path
+                .iter()
+                .map(|header| String::from_utf8_lossy(&header.id).into_owned())
+                .collect::<Vec<_>>()
+                .join("/");
+            inspector.report(&label, chunk)
+        })?,
+        Walker::Frames(format) => {
+            let mut index = 0;
+            while stream.remainder_len()? > 0 {
+                format.read_frame_with(&mut stream, |chunk| {
+                    inspector.report(&format!("frame {}", index), chunk)
+                })?;
+                index += 1;
+            }
+        }
+    }
+    writeln!(
+        inspector.out,
+        "{} chunks, {} bytes",
+        inspector.chunks, inspector.bytes
+    )
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(e) = run(&options, &mut io::stdout().lock()) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use once_io::{adler::adler32, crc::crc32, riff::RiffWriter};
+
+    use super::*;
+
+    fn temp_file(name: &str, data: &[u8]) -> PathBuf {
+        let path = env::temp_dir().join(format!("once_io_inspect_{}_{}", process::id(), name));
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    fn inspect(path: &Path, args: &[&str]) -> Result<String> {
+        let path = path.to_str().unwrap().to_string();
+        let args = std::iter::once(path).chain(args.iter().map(|arg| arg.to_string()));
+        let mut out = Vec::new();
+        run(&parse_args(args)?, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn inspect_ranges() {
+        let path = temp_file("ranges", b"header:0123456789:trailer");
+        let out = inspect(
+            &path,
+            &[
+                "--range",
+                "7:10",
+                "--range",
+                "0x13:6",
+                "--hexdump=8",
+                "--width",
+                "8",
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "range 0 at 0x7, 10 bytes\n\
+             00000007  30 31 32 33 34 35 36 37  |01234567|\n  \
+             ... 2 more bytes\n\
+             range 1 at 0x13, 6 bytes\n\
+             00000013  72 61 69 6c 65 72        |railer|\n\
+             2 chunks, 16 bytes\n"
+        );
+        let err = inspect(&path, &["--range", "20:10"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn inspect_riff_with_digests() {
+        let mut writer = RiffWriter::new(io::Cursor::new(Vec::new()), RiffFormat::Riff);
+        writer.begin_container(b"RIFF", b"WAVE").unwrap();
+        writer.write_chunk(b"fmt ", b"123456789").unwrap();
+        writer.write_chunk(b"data", b"\x00\x00\xff\xff").unwrap();
+        writer.end_chunk().unwrap();
+        let path = temp_file("riff", &writer.into_inner().unwrap().into_inner());
+        let out = inspect(&path, &["--riff", "--digest", "crc32", "--stats"]).unwrap();
+        let expected = format!(
+            "RIFF/fmt  at 0x14, 9 bytes\n  \
+             crc32   cbf43926\n  \
+             entropy 3.170 bits/byte, 9 distinct bytes\n\
+             RIFF/data at 0x26, 4 bytes\n  \
+             crc32   {:08x}\n  \
+             entropy 1.000 bits/byte, 2 distinct bytes\n\
+             2 chunks, 13 bytes\n",
+            crc32(b"\x00\x00\xff\xff")
+        );
+        assert_eq!(out, expected);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn inspect_tlv_and_frames() {
+        let path = temp_file("tlv", b"\x01\x02ab\x07\x00\x09\x03xyz");
+        let out = inspect(&path, &["--tlv", "u8,u8"]).unwrap();
+        assert_eq!(
+            out,
+            "tag 1 at 0x2, 2 bytes\ntag 7 at 0x6, 0 bytes\ntag 9 at 0x8, 3 bytes\n\
+             3 chunks, 5 bytes\n"
+        );
+        std::fs::remove_file(path).unwrap();
+        let path = temp_file("frames", b"\x00\x02hi\x00\x00\x00\x03abc");
+        let out = inspect(&path, &["--frames", "u16be", "--digest", "adler32"]).unwrap();
+        assert_eq!(
+            out,
+            format!(
+                "frame 0 at 0x2, 2 bytes\n  adler32 {:08x}\n\
+                 frame 1 at 0x6, 0 bytes\n  adler32 00000001\n\
+                 frame 2 at 0x8, 3 bytes\n  adler32 {:08x}\n\
+                 3 chunks, 5 bytes\n",
+                adler32(b"hi"),
+                adler32(b"abc")
+            )
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn parse_errors() {
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert!(args(&[]).is_err());
+        assert!(args(&["file", "--tlv", "u8"]).is_err());
+        assert!(args(&["file", "--digest", "md5"]).is_err());
+        assert!(args(&["file", "--range"]).is_err());
+        assert!(args(&["file", "--width", "0"]).is_err());
+        assert!(args(&["file", "other"]).is_err());
+        assert!(args(&["file", "--frames", "u24"]).is_err());
+    }
+}
@@ -0,0 +1,218 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{
+    bits::{BitOrder, BitReader, BitWriter},
+    read_num::Endianness,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitField {
+    pub name: &'static str,
+    pub bits: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitLayout {
+    fields: Vec<BitField>,
+    order: BitOrder,
+    endianness: Option<Endianness>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitFields {
+    names: Vec<&'static str>,
+    values: Vec<u64>,
+}
+
+impl BitFields {
+    pub fn get(&self, name: &str) -> Option<u64> {
+        let index = self.names.iter().position(|n| *n == name)?;
+        Some(self.values[index])
+    }
+
+    pub fn values(&self) -> &[u64] {
+        &self.values
+    }
+}
+
+impl BitLayout {
+    pub fn new(order: BitOrder) -> BitLayout {
+        BitLayout {
+            fields: Vec::new(),
+            order,
+            endianness: None,
+        }
+    }
+
+    pub fn field(mut self, name: &'static str, bits: u32) -> BitLayout {
+        self.fields.push(BitField { name, bits });
+        self
+    }
+
+    pub fn endianness(mut self, endianness: Endianness) -> BitLayout {
+        self.endianness = Some(endianness);
+        self
+    }
+
+    pub fn fields(&self) -> &[BitField] {
+        &self.fields
+    }
+
+    pub fn total_bits(&self) -> u32 {
+        self.fields.iter().map(|f| f.bits).sum()
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.total_bits().div_ceil(8) as usize
+    }
+
+    fn reverses_bytes(&self) -> bool {
+        matches!(
+            (self.order, self.endianness),
+            (BitOrder::MsbFirst, Some(Endianness::Little))
+                | (BitOrder::LsbFirst, Some(Endianness::Big))
+        )
+    }
+
+    pub fn unpack(&self, bytes: &[u8]) -> Result<BitFields> {
+        if bytes.len() != self.byte_len() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "bit layout spans {} bytes, got {}",
+                    self.byte_len(),
+                    bytes.len()
+                ),
+            ));
+        }
+        let mut run = bytes.to_vec();
+        if self.reverses_bytes() {
+            run.reverse();
+        }
+        let mut reader = BitReader::new(&run[..], self.order);
+        let values = self
+            .fields
+            .iter()
+            .map(|f| reader.read_bits(f.bits))
+            .collect::<Result<Vec<u64>>>()?;
+        Ok(BitFields {
+            names: self.fields.iter().map(|f| f.name).collect(),
+            values,
+        })
+    }
+
+    pub fn pack(&self, values: &[u64]) -> Result<Vec<u8>> {
+        if values.len() != self.fields.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "bit layout has {} fields, got {} values",
+                    self.fields.len(),
+                    values.len()
+                ),
+            ));
+        }
+        let mut writer = BitWriter::new(Vec::with_capacity(self.byte_len()), self.order);
+        for (field, value) in self.fields.iter().zip(values) {
+            if field.bits < 64 && *value >> field.bits != 0 {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "value {} does not fit in the {}-bit field {}",
+                        value, field.bits, field.name
+                    ),
+                ));
+            }
+            writer.write_bits(*value, field.bits)?;
+        }
+        let mut run = writer.into_inner()?;
+        if self.reverses_bytes() {
+            run.reverse();
+        }
+        Ok(run)
+    }
+
+    pub fn read_from<R: Read + ?Sized>(&self, reader: &mut R) -> Result<BitFields> {
+        let mut bytes = vec![0u8; self.byte_len()];
+        reader.read_exact(&mut bytes)?;
+        self.unpack(&bytes)
+    }
+
+    pub fn write_to<W: Write + ?Sized>(&self, writer: &mut W, values: &[u64]) -> Result<()> {
+        writer.write_all(&self.pack(values)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn header(order: BitOrder) -> BitLayout {
+        BitLayout::new(order)
+            .field("version", 3)
+            .field("flags", 5)
+            .field("length", 12)
+    }
+
+    #[test]
+    fn unpack_msb_first() {
+        let layout = header(BitOrder::MsbFirst);
+        assert_eq!(layout.total_bits(), 20);
+        assert_eq!(layout.byte_len(), 3);
+        let fields = layout.unpack(&[0b0100_0011, 0x12, 0x30]).unwrap();
+        assert_eq!(fields.get("version"), Some(2));
+        assert_eq!(fields.get("flags"), Some(3));
+        assert_eq!(fields.get("length"), Some(0x123));
+        assert_eq!(fields.get("missing"), None);
+        assert_eq!(fields.values(), [2, 3, 0x123]);
+    }
+
+    #[test]
+    fn unpack_with_endianness() {
+        let layout = BitLayout::new(BitOrder::MsbFirst)
+            .field("high", 4)
+            .field("low", 12)
+            .endianness(Endianness::Little);
+        let fields = layout.unpack(&0xa123u16.to_le_bytes()).unwrap();
+        assert_eq!(fields.values(), [0xa, 0x123]);
+        let layout = BitLayout::new(BitOrder::LsbFirst)
+            .field("low", 12)
+            .field("high", 4);
+        let fields = layout.unpack(&0xa123u16.to_le_bytes()).unwrap();
+        assert_eq!(fields.values(), [0x123, 0xa]);
+        let layout = layout.endianness(Endianness::Big);
+        let fields = layout.unpack(&0xa123u16.to_be_bytes()).unwrap();
+        assert_eq!(fields.values(), [0x123, 0xa]);
+    }
+
+    #[test]
+    fn pack_round_trip() {
+        for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            for endianness in [Endianness::Big, Endianness::Little] {
+                let layout = header(order).endianness(endianness);
+                let mut out = Vec::new();
+                layout.write_to(&mut out, &[5, 0x1f, 0xabc]).unwrap();
+                assert_eq!(out.len(), 3);
+                let fields = layout.read_from(&mut Cursor::new(out)).unwrap();
+                assert_eq!(fields.values(), [5, 0x1f, 0xabc]);
+            }
+        }
+    }
+
+    #[test]
+    fn pack_invalid_values() {
+        let layout = header(BitOrder::MsbFirst);
+        let err = layout.pack(&[8, 0, 0]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            err.to_string(),
+            "value 8 does not fit in the 3-bit field version"
+        );
+        let result = layout.pack(&[1, 2]);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+        let result = layout.unpack(&[0, 0]);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+}
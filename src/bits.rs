@@ -0,0 +1,630 @@
+use alloc::format;
+
+use crate::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    MsbFirst,
+    LsbFirst,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignedEncoding {
+    TwosComplement,
+    OnesComplement,
+    SignMagnitude,
+}
+
+fn mask(bits: u32) -> u128 {
+    (1u128 << bits) - 1
+}
+
+fn check_bit_count(bits: u32) -> Result<()> {
+    if bits > 64 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("cannot handle {} bits at once, the maximum is 64", bits),
+        ));
+    }
+    Ok(())
+}
+
+pub struct BitReader<R>
+where
+    R: Read,
+{
+    inner: R,
+    order: BitOrder,
+    buffer: u128,
+    available: u32,
+    position: u64,
+}
+
+impl<R> BitReader<R>
+where
+    R: Read,
+{
+    pub fn new(inner: R, order: BitOrder) -> BitReader<R> {
+        BitReader {
+            inner,
+            order,
+            buffer: 0,
+            available: 0,
+            position: 0,
+        }
+    }
+
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    pub fn bit_position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn is_aligned(&self) -> bool {
+        self.position.is_multiple_of(8)
+    }
+
+    pub fn byte_align(&mut self) -> Result<()> {
+        self.read_bits(self.available % 8)?;
+        Ok(())
+    }
+
+    pub fn into_inner_aligned(mut self) -> Result<R>
+    where
+        R: Seek,
+    {
+        if !self.is_aligned() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("bit position {} is not byte-aligned", self.position),
+            ));
+        }
+        if self.available > 0 {
+            self.inner
+                .seek(SeekFrom::Current(-((self.available / 8) as i64)))?;
+        }
+        Ok(self.inner)
+    }
+
+    fn fill(&mut self, bits: u32) -> Result<()> {
+        while self.available < bits {
+            let mut byte = [0u8; 1];
+            self.inner.read_exact(&mut byte)?;
+            match self.order {
+                BitOrder::MsbFirst => self.buffer = (self.buffer << 8) | byte[0] as u128,
+                BitOrder::LsbFirst => self.buffer |= (byte[0] as u128) << self.available,
+            }
+            self.available += 8;
+        }
+        Ok(())
+    }
+
+    pub fn peek_bits(&mut self, bits: u32) -> Result<u64> {
+        check_bit_count(bits)?;
+        self.fill(bits)?;
+        let value = match self.order {
+            BitOrder::MsbFirst => self.buffer >> (self.available - bits),
+            BitOrder::LsbFirst => self.buffer,
+        };
+        Ok((value & mask(bits)) as u64)
+    }
+
+    pub fn read_bits(&mut self, bits: u32) -> Result<u64> {
+        let value = self.peek_bits(bits)?;
+        self.available -= bits;
+        self.position += bits as u64;
+        match self.order {
+            BitOrder::MsbFirst => self.buffer &= mask(self.available),
+            BitOrder::LsbFirst => self.buffer >>= bits,
+        }
+        Ok(value)
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool> {
+        Ok(self.read_bits(1)? == 1)
+    }
+
+    pub fn read_signed_bits(&mut self, bits: u32) -> Result<i64> {
+        self.read_signed_bits_as(bits, SignedEncoding::TwosComplement)
+    }
+
+    pub fn read_signed_bits_as(&mut self, bits: u32, encoding: SignedEncoding) -> Result<i64> {
+        let raw = self.read_bits(bits)?;
+        if bits == 0 {
+            return Ok(0);
+        }
+        let negative = raw >> (bits - 1) & 1 == 1;
+        Ok(match (encoding, negative) {
+            (_, false) => raw as i64,
+            (SignedEncoding::TwosComplement, true) => ((raw << (64 - bits)) as i64) >> (64 - bits),
+            (SignedEncoding::OnesComplement, true) => -((!raw & mask(bits) as u64) as i64),
+            (SignedEncoding::SignMagnitude, true) => -((raw & mask(bits - 1) as u64) as i64),
+        })
+    }
+
+    pub fn read_unary(&mut self, terminator: bool) -> Result<u64> {
+        let mut count = 0;
+        while self.read_bit()? != terminator {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    pub fn read_elias_gamma(&mut self) -> Result<u64> {
+        let mut leading_zeros = 0;
+        while !self.read_bit()? {
+            leading_zeros += 1;
+            if leading_zeros == 64 {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "variable-length code with 64 or more leading zeros",
+                ));
+            }
+        }
+        Ok((1u64 << leading_zeros) | self.read_bits(leading_zeros)?)
+    }
+
+    pub fn read_elias_delta(&mut self) -> Result<u64> {
+        let len = self.read_elias_gamma()?;
+        if len > 64 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Elias delta code for a {}-bit value", len),
+            ));
+        }
+        let bits = len as u32 - 1;
+        Ok((1u64 << bits) | self.read_bits(bits)?)
+    }
+
+    pub fn read_ue(&mut self) -> Result<u64> {
+        Ok(self.read_elias_gamma()? - 1)
+    }
+
+    pub fn read_se(&mut self) -> Result<i64> {
+        let code = self.read_ue()?;
+        let magnitude = (code >> 1) as i64;
+        if code & 1 == 1 {
+            Ok(magnitude + 1)
+        } else {
+            Ok(-magnitude)
+        }
+    }
+}
+
+pub struct BitWriter<W>
+where
+    W: Write,
+{
+    inner: W,
+    order: BitOrder,
+    buffer: u128,
+    pending: u32,
+    pad_bit: bool,
+    position: u64,
+}
+
+impl<W> BitWriter<W>
+where
+    W: Write,
+{
+    pub fn new(inner: W, order: BitOrder) -> BitWriter<W> {
+        BitWriter {
+            inner,
+            order,
+            buffer: 0,
+            pending: 0,
+            pad_bit: false,
+            position: 0,
+        }
+    }
+
+    pub fn with_pad_bit(mut self, pad_bit: bool) -> BitWriter<W> {
+        self.pad_bit = pad_bit;
+        self
+    }
+
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn write_bits(&mut self, value: u64, bits: u32) -> Result<()> {
+        check_bit_count(bits)?;
+        let value = value as u128 & mask(bits);
+        match self.order {
+            BitOrder::MsbFirst => self.buffer = (self.buffer << bits) | value,
+            BitOrder::LsbFirst => self.buffer |= value << self.pending,
+        }
+        self.pending += bits;
+        self.position += bits as u64;
+        let mut bytes = [0u8; 9];
+        let mut len = 0;
+        while self.pending >= 8 {
+            self.pending -= 8;
+            match self.order {
+                BitOrder::MsbFirst => {
+                    bytes[len] = (self.buffer >> self.pending) as u8;
+                    self.buffer &= mask(self.pending);
+                }
+                BitOrder::LsbFirst => {
+                    bytes[len] = self.buffer as u8;
+                    self.buffer >>= 8;
+                }
+            }
+            len += 1;
+        }
+        self.inner.write_all(&bytes[..len])
+    }
+
+    pub fn write_bit(&mut self, bit: bool) -> Result<()> {
+        self.write_bits(bit as u64, 1)
+    }
+
+    pub fn write_unary(&mut self, value: u64, terminator: bool) -> Result<()> {
+        let fill = if terminator { 0 } else { u64::MAX };
+        let mut remaining = value;
+        while remaining > 0 {
+            let bits = core::cmp::min(remaining, 64) as u32;
+            self.write_bits(fill, bits)?;
+            remaining -= bits as u64;
+        }
+        self.write_bit(terminator)
+    }
+
+    pub fn write_elias_gamma(&mut self, value: u64) -> Result<()> {
+        if value == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Elias codes cannot represent zero",
+            ));
+        }
+        let bits = 63 - value.leading_zeros();
+        self.write_bits(0, bits)?;
+        self.write_bit(true)?;
+        self.write_bits(value, bits)
+    }
+
+    pub fn write_elias_delta(&mut self, value: u64) -> Result<()> {
+        if value == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Elias codes cannot represent zero",
+            ));
+        }
+        let bits = 63 - value.leading_zeros();
+        self.write_elias_gamma(bits as u64 + 1)?;
+        self.write_bits(value, bits)
+    }
+
+    pub fn bit_position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn is_aligned(&self) -> bool {
+        self.pending == 0
+    }
+
+    pub fn byte_align(&mut self) -> Result<()> {
+        if self.pending == 0 {
+            return Ok(());
+        }
+        let padding = 8 - self.pending;
+        let pad = if self.pad_bit { u64::MAX } else { 0 };
+        self.write_bits(pad, padding)
+    }
+
+    pub fn into_inner(mut self) -> Result<W> {
+        self.byte_align()?;
+        Ok(self.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{read_num::ReadNum, Stream};
+
+    #[test]
+    fn read_bits_msb_first() {
+        let mut reader =
+            BitReader::new(Cursor::new([0b1011_0011, 0b1100_0101]), BitOrder::MsbFirst);
+        assert!(reader.read_bit().unwrap());
+        assert_eq!(reader.read_bits(3).unwrap(), 0b011);
+        assert_eq!(reader.read_bits(8).unwrap(), 0b0011_1100);
+        assert_eq!(reader.read_bits(4).unwrap(), 0b0101);
+        let result = reader.read_bit();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_bits_lsb_first() {
+        let mut reader =
+            BitReader::new(Cursor::new([0b1011_0011, 0b1100_0101]), BitOrder::LsbFirst);
+        assert!(reader.read_bit().unwrap());
+        assert_eq!(reader.read_bits(3).unwrap(), 0b001);
+        assert_eq!(reader.read_bits(8).unwrap(), 0b0101_1011);
+        assert_eq!(reader.read_bits(4).unwrap(), 0b1100);
+    }
+
+    #[test]
+    fn read_64_bits_unaligned() {
+        let mut data = vec![0xff];
+        data.extend(0x0123_4567_89ab_cdefu64.to_be_bytes());
+        let mut reader = BitReader::new(Cursor::new(data), BitOrder::MsbFirst);
+        assert_eq!(reader.read_bits(4).unwrap(), 0xf);
+        assert_eq!(reader.read_bits(64).unwrap(), 0xf012_3456_789a_bcde);
+        assert_eq!(reader.read_bits(4).unwrap(), 0xf);
+        assert_eq!(reader.read_bits(0).unwrap(), 0);
+        let result = reader.read_bits(65);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn read_signed_bits() {
+        let mut reader = BitReader::new(Cursor::new([0b1011_0111, 0xff]), BitOrder::MsbFirst);
+        assert_eq!(reader.read_signed_bits(3).unwrap(), -3);
+        assert_eq!(reader.read_signed_bits(5).unwrap(), -9);
+        assert_eq!(reader.read_signed_bits(1).unwrap(), -1);
+        assert_eq!(reader.read_signed_bits(0).unwrap(), 0);
+        assert_eq!(reader.read_signed_bits(7).unwrap(), -1);
+        let mut reader = BitReader::new(Cursor::new([0x3f]), BitOrder::MsbFirst);
+        assert_eq!(reader.read_signed_bits(4).unwrap(), 3);
+        assert_eq!(reader.read_signed_bits(4).unwrap(), -1);
+    }
+
+    #[test]
+    fn read_signed_bits_64() {
+        let data = i64::MIN.to_le_bytes();
+        let mut reader = BitReader::new(Cursor::new(data), BitOrder::LsbFirst);
+        assert_eq!(reader.read_signed_bits(64).unwrap(), i64::MIN);
+    }
+
+    #[test]
+    fn read_signed_bits_legacy_encodings() {
+        let data = [0b1011_0100, 0b1111_1000];
+        let mut reader = BitReader::new(Cursor::new(data), BitOrder::MsbFirst);
+        let ones = SignedEncoding::OnesComplement;
+        assert_eq!(reader.read_signed_bits_as(4, ones).unwrap(), -4);
+        assert_eq!(reader.read_signed_bits_as(4, ones).unwrap(), 4);
+        assert_eq!(reader.read_signed_bits_as(4, ones).unwrap(), 0);
+        let mut reader = BitReader::new(Cursor::new(data), BitOrder::MsbFirst);
+        let sign_magnitude = SignedEncoding::SignMagnitude;
+        assert_eq!(reader.read_signed_bits_as(4, sign_magnitude).unwrap(), -3);
+        assert_eq!(reader.read_signed_bits_as(4, sign_magnitude).unwrap(), 4);
+        assert_eq!(reader.read_signed_bits_as(4, sign_magnitude).unwrap(), -7);
+        assert_eq!(reader.read_signed_bits_as(4, sign_magnitude).unwrap(), 0);
+    }
+
+    #[test]
+    fn read_exp_golomb() {
+        let data = [0b1010_0110, 0b0100_0010, 0b1000_1000];
+        let mut reader = BitReader::new(Cursor::new(data), BitOrder::MsbFirst);
+        assert_eq!(reader.read_ue().unwrap(), 0);
+        assert_eq!(reader.read_ue().unwrap(), 1);
+        assert_eq!(reader.read_ue().unwrap(), 2);
+        assert_eq!(reader.read_ue().unwrap(), 3);
+        assert_eq!(reader.read_se().unwrap(), -2);
+        assert_eq!(reader.read_se().unwrap(), 4);
+    }
+
+    #[test]
+    fn read_exp_golomb_signed() {
+        let data = [0b1010_0110, 0b0100_0010, 0b1001_1000];
+        let mut reader = BitReader::new(Cursor::new(data), BitOrder::MsbFirst);
+        let values: Vec<i64> = (0..6).map(|_| reader.read_se().unwrap()).collect();
+        assert_eq!(values, [0, 1, -1, 2, -2, 3]);
+    }
+
+    #[test]
+    fn read_exp_golomb_large_values() {
+        let mut data = vec![0u8; 7];
+        data.extend([0x80, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let mut reader = BitReader::new(Cursor::new(data.clone()), BitOrder::MsbFirst);
+        assert_eq!(reader.read_ue().unwrap(), (1 << 56) - 1);
+        data[7] = 0;
+        let mut reader = BitReader::new(Cursor::new(data), BitOrder::MsbFirst);
+        let result = reader.read_ue();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_unary() {
+        let mut reader =
+            BitReader::new(Cursor::new([0b1110_0100, 0b1000_0000]), BitOrder::MsbFirst);
+        assert_eq!(reader.read_unary(false).unwrap(), 3);
+        assert_eq!(reader.read_unary(false).unwrap(), 0);
+        assert_eq!(reader.read_unary(true).unwrap(), 0);
+        assert_eq!(reader.read_unary(true).unwrap(), 2);
+    }
+
+    #[test]
+    fn read_elias_gamma_and_delta() {
+        let data = [0b1010_0110, 0b0100_0111, 0b0100_0000];
+        let mut reader = BitReader::new(Cursor::new(data), BitOrder::MsbFirst);
+        assert_eq!(reader.read_elias_gamma().unwrap(), 1);
+        assert_eq!(reader.read_elias_gamma().unwrap(), 2);
+        assert_eq!(reader.read_elias_gamma().unwrap(), 3);
+        assert_eq!(reader.read_elias_gamma().unwrap(), 4);
+        assert_eq!(reader.read_elias_delta().unwrap(), 6);
+        assert_eq!(reader.read_elias_delta().unwrap(), 1);
+    }
+
+    #[test]
+    fn variable_length_codes_round_trip() {
+        let values = [1, 2, 7, 8, 1000, u32::MAX as u64, u64::MAX];
+        for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            let mut writer = BitWriter::new(Vec::new(), order);
+            for value in values {
+                writer.write_elias_gamma(value).unwrap();
+                writer.write_elias_delta(value).unwrap();
+                writer.write_unary(value % 70, value % 2 == 0).unwrap();
+            }
+            let data = writer.into_inner().unwrap();
+            let mut reader = BitReader::new(Cursor::new(data), order);
+            for value in values {
+                assert_eq!(reader.read_elias_gamma().unwrap(), value);
+                assert_eq!(reader.read_elias_delta().unwrap(), value);
+                assert_eq!(reader.read_unary(value % 2 == 0).unwrap(), value % 70);
+            }
+        }
+    }
+
+    #[test]
+    fn write_elias_zero() {
+        let mut writer = BitWriter::new(Vec::new(), BitOrder::MsbFirst);
+        let result = writer.write_elias_gamma(0);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+        let result = writer.write_elias_delta(0);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn reader_alignment() {
+        let mut reader = BitReader::new(Cursor::new([0xab, 0xcd, 0xef]), BitOrder::MsbFirst);
+        assert!(reader.is_aligned());
+        reader.byte_align().unwrap();
+        assert_eq!(reader.bit_position(), 0);
+        assert_eq!(reader.read_bits(3).unwrap(), 0b101);
+        assert_eq!(reader.bit_position(), 3);
+        assert!(!reader.is_aligned());
+        reader.byte_align().unwrap();
+        assert_eq!(reader.bit_position(), 8);
+        assert!(reader.is_aligned());
+        assert_eq!(reader.read_bits(8).unwrap(), 0xcd);
+    }
+
+    #[test]
+    fn writer_alignment() {
+        let mut writer = BitWriter::new(Vec::new(), BitOrder::LsbFirst);
+        writer.write_bits(0b11, 2).unwrap();
+        assert_eq!(writer.bit_position(), 2);
+        assert!(!writer.is_aligned());
+        writer.byte_align().unwrap();
+        assert_eq!(writer.bit_position(), 8);
+        assert!(writer.is_aligned());
+        writer.byte_align().unwrap();
+        writer.write_bits(0x5a, 8).unwrap();
+        assert_eq!(writer.bit_position(), 16);
+        assert_eq!(writer.get_ref(), &[0b11, 0x5a]);
+    }
+
+    #[test]
+    fn switch_between_bits_and_bytes() {
+        let mut cursor = Cursor::new([0xa0, 0x12, 0x34, 0x56, 0x78, 0xff]);
+        let mut stream = Stream::new(&mut cursor).be();
+        let mut reader = BitReader::new(&mut stream, BitOrder::MsbFirst);
+        assert_eq!(reader.read_bits(4).unwrap(), 0xa);
+        assert_eq!(reader.peek_bits(20).unwrap(), 0x01234);
+        let mut unaligned = BitReader::new(Cursor::new([0u8]), BitOrder::MsbFirst);
+        unaligned.read_bit().unwrap();
+        let result = unaligned.into_inner_aligned();
+        assert_eq!(result.err().unwrap().kind(), ErrorKind::InvalidInput);
+        reader.byte_align().unwrap();
+        let stream = reader.into_inner_aligned().unwrap();
+        assert_eq!(stream.read_u16().unwrap(), 0x1234);
+        let mut reader = BitReader::new(stream, BitOrder::MsbFirst);
+        assert_eq!(reader.read_bits(8).unwrap(), 0x56);
+        let stream = reader.into_inner_aligned().unwrap();
+        assert_eq!(stream.read_u8().unwrap(), 0x78);
+    }
+
+    #[test]
+    fn peek_bits() {
+        let mut reader = BitReader::new(Cursor::new([0xa5, 0x0f]), BitOrder::MsbFirst);
+        assert_eq!(reader.peek_bits(12).unwrap(), 0xa50);
+        assert_eq!(reader.peek_bits(4).unwrap(), 0xa);
+        assert_eq!(reader.read_bits(4).unwrap(), 0xa);
+        assert_eq!(reader.peek_bits(12).unwrap(), 0x50f);
+        assert_eq!(reader.read_bits(12).unwrap(), 0x50f);
+    }
+
+    #[test]
+    fn read_bits_within_chunk() {
+        let mut cursor = Cursor::new([0xff, 0xff, 0xff]);
+        let mut stream = Stream::new(&mut cursor);
+        let chunk = stream.borrow_chunk(Some(1)).unwrap();
+        let mut reader = BitReader::new(chunk, BitOrder::LsbFirst);
+        assert_eq!(reader.read_bits(8).unwrap(), 0xff);
+        let result = reader.read_bit();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn write_bits_msb_first() {
+        let mut writer = BitWriter::new(Vec::new(), BitOrder::MsbFirst);
+        writer.write_bit(true).unwrap();
+        writer.write_bits(0b011, 3).unwrap();
+        writer.write_bits(0b0011_1100, 8).unwrap();
+        writer.write_bits(0b0101, 4).unwrap();
+        assert_eq!(writer.into_inner().unwrap(), [0b1011_0011, 0b1100_0101]);
+    }
+
+    #[test]
+    fn write_bits_lsb_first() {
+        let mut writer = BitWriter::new(Vec::new(), BitOrder::LsbFirst);
+        writer.write_bit(true).unwrap();
+        writer.write_bits(0b001, 3).unwrap();
+        writer.write_bits(0b0101_1011, 8).unwrap();
+        writer.write_bits(0b1100, 4).unwrap();
+        assert_eq!(writer.into_inner().unwrap(), [0b1011_0011, 0b1100_0101]);
+    }
+
+    #[test]
+    fn write_bits_with_padding() {
+        let mut writer = BitWriter::new(Vec::new(), BitOrder::MsbFirst).with_pad_bit(true);
+        writer.write_bits(0b101, 3).unwrap();
+        writer.byte_align().unwrap();
+        writer.byte_align().unwrap();
+        writer.write_bits(0, 2).unwrap();
+        assert_eq!(writer.into_inner().unwrap(), [0b1011_1111, 0b0011_1111]);
+        let mut writer = BitWriter::new(Vec::new(), BitOrder::LsbFirst);
+        writer.write_bits(0b101, 3).unwrap();
+        assert_eq!(writer.into_inner().unwrap(), [0b0000_0101]);
+    }
+
+    #[test]
+    fn write_bits_round_trip() {
+        let fields = [
+            (0x1u64, 1),
+            (0x2a, 6),
+            (0xdead_beef_cafe_f00d, 64),
+            (0x3, 2),
+        ];
+        for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            let mut writer = BitWriter::new(Vec::new(), order);
+            for (value, bits) in fields {
+                writer.write_bits(value, bits).unwrap();
+            }
+            writer.write_bits(u64::MAX, 0).unwrap();
+            let data = writer.into_inner().unwrap();
+            assert_eq!(data.len(), 10);
+            let mut reader = BitReader::new(Cursor::new(data), order);
+            for (value, bits) in fields {
+                assert_eq!(reader.read_bits(bits).unwrap(), value);
+            }
+        }
+    }
+}
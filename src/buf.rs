@@ -0,0 +1,235 @@
+use std::io::{Cursor, Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use ::bytes::{buf::UninitSlice, Buf, BufMut, Bytes};
+
+use crate::Stream;
+
+pub struct BufSource<B> {
+    buf: B,
+    pos: u64,
+    len: u64,
+}
+
+impl<B> BufSource<B>
+where
+    B: Buf,
+{
+    pub fn new(buf: B) -> BufSource<B> {
+        BufSource {
+            len: buf.remaining() as u64,
+            buf,
+            pos: 0,
+        }
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<Bytes> {
+        if len > self.buf.remaining() {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("fewer than {} bytes left to borrow", len),
+            ));
+        }
+        self.pos += len as u64;
+        Ok(self.buf.copy_to_bytes(len))
+    }
+
+    pub fn get_ref(&self) -> &B {
+        &self.buf
+    }
+
+    pub fn get_mut(&mut self) -> &mut B {
+        &mut self.buf
+    }
+
+    pub fn into_inner(self) -> B {
+        self.buf
+    }
+}
+
+impl<B> Read for BufSource<B>
+where
+    B: Buf,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = std::cmp::min(buf.len(), self.buf.remaining());
+        self.buf.copy_to_slice(&mut buf[..len]);
+        self.pos += len as u64;
+        Ok(len)
+    }
+}
+
+impl<B> Seek for BufSource<B>
+where
+    B: Buf,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(n) => self.len.checked_add_signed(n),
+            SeekFrom::Current(n) => self.pos.checked_add_signed(n),
+        };
+        match target {
+            Some(target) if target < self.pos => Err(Error::new(
+                ErrorKind::Unsupported,
+                "cannot seek backwards in a Buf",
+            )),
+            Some(target) => {
+                let skip = std::cmp::min(target - self.pos, self.buf.remaining() as u64);
+                self.buf.advance(skip as usize);
+                self.pos = target;
+                Ok(target)
+            }
+            None => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+
+    fn stream_len(&mut self) -> Result<u64> {
+        Ok(self.len)
+    }
+
+    fn stream_position(&mut self) -> Result<u64> {
+        Ok(self.pos)
+    }
+}
+
+impl<T, E> Stream<'_, Cursor<T>, E>
+where
+    T: AsRef<[u8]>,
+{
+    fn region(&self) -> (usize, usize) {
+        let len = self.inner.get_ref().as_ref().len() as u64;
+        let end = std::cmp::min(len, self.limit_pos);
+        let pos = std::cmp::min(self.inner.position(), end);
+        (pos as usize, end as usize)
+    }
+
+    fn advance_region(&mut self, cnt: usize) {
+        let (pos, end) = self.region();
+        assert!(
+            cnt <= end - pos,
+            "cannot advance past the end of the stream region"
+        );
+        self.inner.set_position((pos + cnt) as u64);
+    }
+}
+
+impl<T, E> Buf for Stream<'_, Cursor<T>, E>
+where
+    T: AsRef<[u8]>,
+{
+    fn remaining(&self) -> usize {
+        let (pos, end) = self.region();
+        end - pos
+    }
+
+    fn chunk(&self) -> &[u8] {
+        let (pos, end) = self.region();
+        &self.inner.get_ref().as_ref()[pos..end]
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.advance_region(cnt)
+    }
+}
+
+unsafe impl<T, E> BufMut for Stream<'_, Cursor<T>, E>
+where
+    T: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn remaining_mut(&self) -> usize {
+        let (pos, end) = self.region();
+        end - pos
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        let (pos, end) = self.region();
+        UninitSlice::new(&mut self.inner.get_mut().as_mut()[pos..end])
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        self.advance_region(cnt)
+    }
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn read_to_bytes(&mut self, max_len: u64) -> Result<Bytes> {
+        self.read_to_vec(max_len).map(Bytes::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_num::ReadNum;
+
+    #[test]
+    fn buf_source_reads_numbers_and_chunks() {
+        let buf = Bytes::from_static(b"\x00\x00\x00\x04ab").chain(&b"cdtail"[..]);
+        let mut source = BufSource::new(buf);
+        let mut stream = Stream::new(&mut source).be();
+        let len = stream.read_u32().unwrap();
+        let mut chunk = stream.borrow_chunk(Some(len as u64)).unwrap();
+        assert_eq!(chunk.read_u16().unwrap(), 0x6162);
+        assert_eq!(chunk.remainder_len().unwrap(), 2);
+        chunk.seek(SeekFrom::End(0)).unwrap();
+        assert_eq!(stream.remainder_len().unwrap(), 4);
+        let err = stream.seek(SeekFrom::Start(0)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        let mut tail = String::new();
+        stream.read_to_string(&mut tail).unwrap();
+        assert_eq!(tail, "tail");
+    }
+
+    #[test]
+    fn buf_source_read_bytes_is_zero_copy() {
+        let data = Bytes::from_static(b"headerpayload");
+        let mut source = BufSource::new(data.clone());
+        source.seek(SeekFrom::Current(6)).unwrap();
+        let payload = source.read_bytes(7).unwrap();
+        assert_eq!(payload, "payload");
+        assert_eq!(payload.as_ptr(), data[6..].as_ptr());
+        let err = source.read_bytes(1).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn stream_region_as_buf() {
+        let data = b"\x00\x12\x34\x56\x78rest".to_vec();
+        let mut cursor = Cursor::new(&data[..]);
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(1)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(4)).unwrap();
+        assert_eq!(chunk.remaining(), 4);
+        assert_eq!(chunk.chunk().as_ptr(), data[1..].as_ptr());
+        assert_eq!(chunk.get_u16(), 0x1234);
+        assert_eq!(chunk.get_u16_le(), 0x7856);
+        assert!(!chunk.has_remaining());
+        assert_eq!(cursor.position(), 5);
+    }
+
+    #[test]
+    fn stream_region_as_buf_mut() {
+        let mut cursor = Cursor::new([0u8; 8]);
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(2)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(4)).unwrap();
+        chunk.put_u16(0xabcd);
+        chunk.put_slice(b"xy");
+        assert_eq!(chunk.remaining_mut(), 0);
+        assert_eq!(cursor.into_inner(), *b"\x00\x00\xab\xcdxy\x00\x00");
+    }
+
+    #[test]
+    fn read_to_bytes() {
+        let mut cursor = Cursor::new(b"\x02hi".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(1)).unwrap();
+        assert_eq!(stream.read_to_bytes(8).unwrap(), "hi");
+    }
+}
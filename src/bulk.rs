@@ -0,0 +1,324 @@
+use std::{
+    io::{Read, Result, Write},
+    mem::{self, MaybeUninit},
+    ptr, slice,
+};
+
+use crate::{
+    read_num::{Endianness, NumReaderEndianness, Numeric, ReadNum},
+    write_num::{NumWriterEndianness, WriteNum},
+};
+
+const STAGING_LEN: usize = 4096;
+
+mod private {
+    pub trait Sealed {}
+}
+
+pub trait BulkNum: Numeric + Copy + private::Sealed {
+    fn swap_bytes(self) -> Self;
+}
+
+macro_rules! impl_bulk_num_int {
+    ($type: ty) => {
+        impl private::Sealed for $type {}
+
+        impl BulkNum for $type {
+            fn swap_bytes(self) -> $type {
+                <$type>::swap_bytes(self)
+            }
+        }
+    };
+}
+
+macro_rules! impl_bulk_num_float {
+    ($type: ty) => {
+        impl private::Sealed for $type {}
+
+        impl BulkNum for $type {
+            fn swap_bytes(self) -> $type {
+                <$type>::from_bits(self.to_bits().swap_bytes())
+            }
+        }
+    };
+}
+
+impl_bulk_num_int! {u8}
+impl_bulk_num_int! {u16}
+impl_bulk_num_int! {u32}
+impl_bulk_num_int! {u64}
+impl_bulk_num_int! {u128}
+impl_bulk_num_int! {i8}
+impl_bulk_num_int! {i16}
+impl_bulk_num_int! {i32}
+impl_bulk_num_int! {i64}
+impl_bulk_num_int! {i128}
+impl_bulk_num_int! {usize}
+impl_bulk_num_int! {isize}
+impl_bulk_num_float! {f32}
+impl_bulk_num_float! {f64}
+
+fn fix_endianness<N: BulkNum>(values: &mut [N], endianness: Endianness) {
+    if endianness != Endianness::native() {
+        for value in values.iter_mut() {
+            *value = value.swap_bytes();
+        }
+    }
+}
+
+pub trait ReadBulk: ReadNum + Read {
+    fn read_into<N: BulkNum>(&mut self, dst: &mut [N]) -> Result<()>
+    where
+        Self::Reader: NumReaderEndianness<Self>,
+    {
+        // SAFETY: every `BulkNum` is a primitive number for which any bit pattern is valid.
+        let bytes = unsafe {
+            slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u8, mem::size_of_val(dst))
+        };
+        self.read_exact(bytes)?;
+        fix_endianness(dst, self.endianness());
+        Ok(())
+    }
+
+    fn read_into_uninit<'b, N: BulkNum>(
+        &mut self,
+        dst: &'b mut [MaybeUninit<N>],
+    ) -> Result<&'b mut [N]>
+    where
+        Self::Reader: NumReaderEndianness<Self>,
+    {
+        let endianness = self.endianness();
+        let mut staging = [0u8; STAGING_LEN];
+        for values in dst.chunks_mut(STAGING_LEN / mem::size_of::<N>()) {
+            let bytes = &mut staging[..mem::size_of_val(values)];
+            self.read_exact(bytes)?;
+            // SAFETY: `bytes` covers exactly the memory of `values`, and any bit pattern is a
+            // valid `BulkNum`, so the chunk is initialized once the copy completes.
+            let values = unsafe {
+                ptr::copy_nonoverlapping(
+                    bytes.as_ptr(),
+                    values.as_mut_ptr() as *mut u8,
+                    bytes.len(),
+                );
+                slice::from_raw_parts_mut(values.as_mut_ptr() as *mut N, values.len())
+            };
+            fix_endianness(values, endianness);
+        }
+        // SAFETY: every chunk of `dst` was initialized by the loop above.
+        Ok(unsafe { slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut N, dst.len()) })
+    }
+
+    fn read_extend<N: BulkNum>(&mut self, vec: &mut Vec<N>, count: usize) -> Result<()>
+    where
+        Self::Reader: NumReaderEndianness<Self>,
+    {
+        vec.reserve(count);
+        let len = vec.len();
+        self.read_into_uninit(&mut vec.spare_capacity_mut()[..count])?;
+        // SAFETY: the first `count` elements of the spare capacity were initialized above.
+        unsafe { vec.set_len(len + count) };
+        Ok(())
+    }
+}
+
+impl<R> ReadBulk for R where R: ReadNum + Read + ?Sized {}
+
+fn as_bytes<N: BulkNum>(values: &[N]) -> &[u8] {
+    // SAFETY: every `BulkNum` is a primitive number without padding bytes.
+    unsafe { slice::from_raw_parts(values.as_ptr() as *const u8, mem::size_of_val(values)) }
+}
+
+pub trait WriteBulk: WriteNum + Write {
+    fn write_slice<N: BulkNum>(&mut self, src: &[N]) -> Result<()>
+    where
+        Self::Writer: NumWriterEndianness<Self>,
+    {
+        if <Self as WriteNum>::Writer::endianness(self) == Endianness::native() {
+            return self.write_all(as_bytes(src));
+        }
+        let mut staging =
+            Vec::with_capacity(std::cmp::min(src.len(), STAGING_LEN / mem::size_of::<N>()));
+        for values in src.chunks(STAGING_LEN / mem::size_of::<N>()) {
+            staging.clear();
+            staging.extend(values.iter().map(|value| value.swap_bytes()));
+            self.write_all(as_bytes(&staging))?;
+        }
+        Ok(())
+    }
+
+    fn write_u16_slice(&mut self, src: &[u16]) -> Result<()>
+    where
+        Self::Writer: NumWriterEndianness<Self>,
+    {
+        self.write_slice(src)
+    }
+
+    fn write_u32_slice(&mut self, src: &[u32]) -> Result<()>
+    where
+        Self::Writer: NumWriterEndianness<Self>,
+    {
+        self.write_slice(src)
+    }
+
+    fn write_u64_slice(&mut self, src: &[u64]) -> Result<()>
+    where
+        Self::Writer: NumWriterEndianness<Self>,
+    {
+        self.write_slice(src)
+    }
+
+    fn write_i16_slice(&mut self, src: &[i16]) -> Result<()>
+    where
+        Self::Writer: NumWriterEndianness<Self>,
+    {
+        self.write_slice(src)
+    }
+
+    fn write_i32_slice(&mut self, src: &[i32]) -> Result<()>
+    where
+        Self::Writer: NumWriterEndianness<Self>,
+    {
+        self.write_slice(src)
+    }
+
+    fn write_i64_slice(&mut self, src: &[i64]) -> Result<()>
+    where
+        Self::Writer: NumWriterEndianness<Self>,
+    {
+        self.write_slice(src)
+    }
+
+    fn write_f32_slice(&mut self, src: &[f32]) -> Result<()>
+    where
+        Self::Writer: NumWriterEndianness<Self>,
+    {
+        self.write_slice(src)
+    }
+
+    fn write_f64_slice(&mut self, src: &[f64]) -> Result<()>
+    where
+        Self::Writer: NumWriterEndianness<Self>,
+    {
+        self.write_slice(src)
+    }
+}
+
+impl<W> WriteBulk for W where W: WriteNum + Write + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Cursor, ErrorKind},
+        marker::PhantomData,
+    };
+
+    use super::*;
+    use crate::{
+        read_num::ReadEndianExt,
+        write_num::{BigEndianWriter, LittleEndianWriter, NumWriter},
+        Stream,
+    };
+
+    #[test]
+    fn read_into_slice() {
+        let mut cursor = Cursor::new([0x00, 0x01, 0x00, 0x02, 0x00, 0x03]);
+        let mut values = [0u16; 3];
+        cursor.be().read_into(&mut values).unwrap();
+        assert_eq!(values, [1, 2, 3]);
+    }
+
+    #[test]
+    fn read_into_uninit_slice() {
+        let mut data = Vec::new();
+        for value in [1.5f32, -2.0, 3.25] {
+            data.extend(value.to_le_bytes());
+        }
+        let mut cursor = Cursor::new(data);
+        let mut values = [MaybeUninit::<f32>::uninit(); 3];
+        let values = cursor.le().read_into_uninit(&mut values).unwrap();
+        assert_eq!(values, [1.5, -2.0, 3.25]);
+    }
+
+    #[test]
+    fn read_extend_vec() {
+        let mut data = Vec::new();
+        for value in [10u32, 20, 30, 40] {
+            data.extend(value.to_be_bytes());
+        }
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor).be();
+        let mut values = vec![5u32];
+        stream.read_extend(&mut values, 3).unwrap();
+        stream.read_extend(&mut values, 1).unwrap();
+        assert_eq!(values, [5, 10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn read_extend_past_limit() {
+        let mut cursor = Cursor::new([0u8; 16]);
+        let mut stream = Stream::new(&mut cursor);
+        let mut chunk = stream.borrow_chunk(Some(7)).unwrap();
+        let mut values: Vec<u32> = Vec::new();
+        let result = chunk.read_extend(&mut values, 2);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert!(values.is_empty());
+    }
+
+    struct CountingWriter<E> {
+        inner: Vec<u8>,
+        writes: usize,
+        endianness: PhantomData<E>,
+    }
+
+    impl<E> Write for CountingWriter<E> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.writes += 1;
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<E> WriteNum for CountingWriter<E>
+    where
+        E: NumWriter<CountingWriter<E>>,
+    {
+        type Writer = E;
+    }
+
+    fn counting_writer<E>() -> CountingWriter<E> {
+        CountingWriter {
+            inner: Vec::new(),
+            writes: 0,
+            endianness: PhantomData,
+        }
+    }
+
+    #[test]
+    fn write_slices() {
+        let mut writer = counting_writer::<BigEndianWriter>();
+        writer.write_u16_slice(&[1, 2]).unwrap();
+        writer.write_f32_slice(&[1.5]).unwrap();
+        assert_eq!(
+            writer.inner,
+            [0x00, 0x01, 0x00, 0x02, 0x3f, 0xc0, 0x00, 0x00]
+        );
+        let mut writer = counting_writer::<LittleEndianWriter>();
+        writer.write_i32_slice(&[-2]).unwrap();
+        assert_eq!(writer.inner, [0xfe, 0xff, 0xff, 0xff]);
+    }
+
+    #[test]
+    fn write_slice_batches_writes() {
+        let values: Vec<u32> = (0..5000).collect();
+        let mut writer = counting_writer::<BigEndianWriter>();
+        writer.write_u32_slice(&values).unwrap();
+        assert_eq!(writer.writes, 5);
+        let mut reader = Cursor::new(writer.inner);
+        let mut read_back: Vec<u32> = Vec::new();
+        reader.be().read_extend(&mut read_back, 5000).unwrap();
+        assert_eq!(read_back, values);
+    }
+}
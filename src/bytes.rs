@@ -0,0 +1,158 @@
+use std::io::{Cursor, Error, ErrorKind, Read, Result, Seek};
+
+use crate::Stream;
+
+pub trait ReadBytes: Read {
+    fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut buf = [0u8; N];
+        self.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+impl<R> ReadBytes for R where R: Read + ?Sized {}
+
+fn unexpected_eof(len: usize) -> Error {
+    Error::new(
+        ErrorKind::UnexpectedEof,
+        format!("fewer than {} bytes left to borrow", len),
+    )
+}
+
+pub trait ReadRef<'b> {
+    fn read_bytes_ref(&mut self, len: usize) -> Result<&'b [u8]>;
+
+    fn read_str_ref(&mut self, len: usize) -> Result<&'b str> {
+        let bytes = self.read_bytes_ref(len)?;
+        std::str::from_utf8(bytes).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+}
+
+impl<'b> ReadRef<'b> for &'b [u8] {
+    fn read_bytes_ref(&mut self, len: usize) -> Result<&'b [u8]> {
+        if len > self.len() {
+            return Err(unexpected_eof(len));
+        }
+        let (head, tail) = self.split_at(len);
+        *self = tail;
+        Ok(head)
+    }
+}
+
+impl<'b> ReadRef<'b> for Cursor<&'b [u8]> {
+    fn read_bytes_ref(&mut self, len: usize) -> Result<&'b [u8]> {
+        let backing: &'b [u8] = self.get_ref();
+        let start = std::cmp::min(self.position(), backing.len() as u64) as usize;
+        let mut remainder = &backing[start..];
+        let bytes = remainder.read_bytes_ref(len)?;
+        self.set_position((start + len) as u64);
+        Ok(bytes)
+    }
+}
+
+impl<'b, T, E> ReadRef<'b> for Stream<'_, T, E>
+where
+    T: ReadRef<'b> + Seek,
+{
+    fn read_bytes_ref(&mut self, len: usize) -> Result<&'b [u8]> {
+        if len as u64 > self.remainder_len()? {
+            return Err(unexpected_eof(len));
+        }
+        self.inner.read_bytes_ref(len)
+    }
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn read_to_vec(&mut self, max_len: u64) -> Result<Vec<u8>> {
+        let remainder_len = self.remainder_len()?;
+        if remainder_len > max_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "remaining {} bytes exceed the {} byte cap",
+                    remainder_len, max_len
+                ),
+            ));
+        }
+        let mut buf = Vec::with_capacity(remainder_len as usize);
+        self.read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::SeekFrom;
+
+    use super::*;
+
+    #[test]
+    fn read_bytes() {
+        let mut cursor = Cursor::new([1, 2, 3, 4, 5, 6]);
+        assert_eq!(cursor.read_bytes::<4>().unwrap(), [1, 2, 3, 4]);
+        let hash: [u8; 2] = cursor.read_bytes().unwrap();
+        assert_eq!(hash, [5, 6]);
+        assert_eq!(cursor.read_bytes::<0>().unwrap(), []);
+    }
+
+    #[test]
+    fn read_bytes_over_limit() {
+        let mut cursor = Cursor::new([0u8; 8]);
+        let mut stream = Stream::new(&mut cursor);
+        let mut chunk = stream.borrow_chunk(Some(3)).unwrap();
+        let result = chunk.read_bytes::<4>();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_bytes_ref_from_slice() {
+        let data = b"abcdef".to_vec();
+        let mut slice = &data[..];
+        let head = slice.read_bytes_ref(2).unwrap();
+        let tail = slice.read_str_ref(4).unwrap();
+        assert_eq!(head, b"ab");
+        assert_eq!(tail, "cdef");
+        assert!(std::ptr::eq(head.as_ptr(), data.as_ptr()));
+        let result = slice.read_bytes_ref(1);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_str_ref_from_stream_chunk() {
+        let data = b"\x05hello world".to_vec();
+        let mut cursor = Cursor::new(&data[..]);
+        let mut stream = Stream::new(&mut cursor);
+        let _ = stream.seek(SeekFrom::Start(1));
+        let mut chunk = stream.borrow_chunk(Some(5)).unwrap();
+        let word = chunk.read_str_ref(5).unwrap();
+        let result = chunk.read_bytes_ref(1);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(word, "hello");
+        assert_eq!(cursor.position(), 6);
+        assert_eq!(cursor.read_str_ref(6).unwrap(), " world");
+    }
+
+    #[test]
+    fn read_str_ref_invalid_utf8() {
+        let mut cursor = Cursor::new(&b"\xff\xfe"[..]);
+        let result = cursor.read_str_ref(2);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_to_vec() {
+        let mut cursor = Cursor::new(b"\x03abcdef".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let _ = stream.seek(SeekFrom::Start(1));
+        let mut chunk = stream.borrow_chunk(Some(3)).unwrap();
+        assert_eq!(chunk.read_to_vec(3).unwrap(), b"abc");
+        assert_eq!(chunk.read_to_vec(0).unwrap(), b"");
+        let err = stream.read_to_vec(2).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "remaining 3 bytes exceed the 2 byte cap");
+        assert_eq!(stream.read_to_vec(3).unwrap(), b"def");
+    }
+}
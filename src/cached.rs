@@ -0,0 +1,192 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use crate::range_fetch::BlockCache;
+
+pub struct CachedStream<T> {
+    inner: T,
+    cache: BlockCache,
+    pos: u64,
+    len: Option<u64>,
+}
+
+impl<T> CachedStream<T>
+where
+    T: Read + Seek,
+{
+    pub fn new(inner: T) -> CachedStream<T> {
+        CachedStream {
+            inner,
+            cache: BlockCache::new(),
+            pos: 0,
+            len: None,
+        }
+    }
+
+    pub fn block_size(mut self, block_size: usize) -> CachedStream<T> {
+        self.cache.set_block_size(block_size);
+        self
+    }
+
+    pub fn capacity(mut self, capacity: usize) -> CachedStream<T> {
+        self.cache.set_capacity(capacity);
+        self
+    }
+
+    pub fn cached_blocks(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+        self.len = None;
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn len(&mut self) -> Result<u64> {
+        match self.len {
+            Some(len) => Ok(len),
+            None => {
+                let len = self.inner.stream_len()?;
+                self.len = Some(len);
+                Ok(len)
+            }
+        }
+    }
+}
+
+pub(crate) fn load_block<T: Read + Seek>(
+    inner: &mut T,
+    offset: u64,
+    len: usize,
+) -> Result<Vec<u8>> {
+    inner.seek(SeekFrom::Start(offset))?;
+    let mut block = Vec::with_capacity(len);
+    inner.take(len as u64).read_to_end(&mut block)?;
+    Ok(block)
+}
+
+impl<T> Read for CachedStream<T>
+where
+    T: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let stream_len = self.len()?;
+        if buf.is_empty() || self.pos >= stream_len {
+            return Ok(0);
+        }
+        let inner = &mut self.inner;
+        let len = self.cache.read(self.pos, stream_len, buf, |offset, len| {
+            load_block(inner, offset, len)
+        })?;
+        self.pos += len as u64;
+        Ok(len)
+    }
+}
+
+impl<T> Seek for CachedStream<T>
+where
+    T: Read + Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(n) => self.len()?.checked_add_signed(n),
+            SeekFrom::Current(n) => self.pos.checked_add_signed(n),
+        };
+        self.pos = target.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.pos)
+    }
+
+    fn stream_len(&mut self) -> Result<u64> {
+        self.len()
+    }
+
+    fn stream_position(&mut self) -> Result<u64> {
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{read_num::ReadNum, Stream};
+
+    struct CountingSeeks {
+        inner: Cursor<Vec<u8>>,
+        seeks: usize,
+    }
+
+    impl Read for CountingSeeks {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for CountingSeeks {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+            self.seeks += 1;
+            self.inner.seek(pos)
+        }
+    }
+
+    fn backend() -> CountingSeeks {
+        CountingSeeks {
+            inner: Cursor::new((0..=255).collect()),
+            seeks: 0,
+        }
+    }
+
+    #[test]
+    fn cached_pointer_chasing() {
+        let mut cached = CachedStream::new(backend()).block_size(32).capacity(4);
+        let mut stream = Stream::new(&mut cached).be();
+        let mut pointer = 0x10u64;
+        for _ in 0..20 {
+            stream.seek(SeekFrom::Start(pointer)).unwrap();
+            pointer = stream.read_u8().unwrap() as u64 ^ 0x30;
+        }
+        assert_eq!(pointer, 0x10);
+        let seeks = cached.get_ref().seeks;
+        assert_eq!(cached.cached_blocks(), 2);
+        stream = Stream::new(&mut cached).be();
+        stream.seek(SeekFrom::Start(0x3e)).unwrap();
+        assert_eq!(stream.read_u32().unwrap(), 0x3e3f_4041);
+        assert_eq!(cached.get_ref().seeks, seeks + 1);
+    }
+
+    #[test]
+    fn cached_lru_eviction_and_eof() {
+        let mut cached = CachedStream::new(backend()).block_size(64).capacity(2);
+        let mut byte = [0u8; 1];
+        for pos in [0, 64, 0, 128, 64, 0] {
+            cached.seek(SeekFrom::Start(pos)).unwrap();
+            cached.read_exact(&mut byte).unwrap();
+            assert_eq!(byte[0], pos as u8);
+        }
+        assert_eq!(cached.cached_blocks(), 2);
+        cached.seek(SeekFrom::End(-2)).unwrap();
+        let mut tail = Vec::new();
+        cached.read_to_end(&mut tail).unwrap();
+        assert_eq!(tail, [254, 255]);
+        let err = cached.seek(SeekFrom::Current(-300)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+}
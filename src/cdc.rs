@@ -0,0 +1,311 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek};
+
+use crate::{lazy::ChunkSpec, Stream};
+
+const fn gear_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut index = 0;
+    while index < 256 {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut value = state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[index] = value ^ (value >> 31);
+        index += 1;
+    }
+    table
+}
+
+static GEAR: [u64; 256] = gear_table();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CdcConfig {
+    min_size: usize,
+    avg_size: usize,
+    max_size: usize,
+    normalization: u32,
+}
+
+impl CdcConfig {
+    pub fn new(min_size: usize, avg_size: usize, max_size: usize) -> CdcConfig {
+        CdcConfig {
+            min_size,
+            avg_size,
+            max_size,
+            normalization: 2,
+        }
+    }
+
+    pub fn normalization(mut self, normalization: u32) -> CdcConfig {
+        self.normalization = normalization;
+        self
+    }
+
+    fn masks(&self) -> Result<(u64, u64)> {
+        if self.min_size == 0 || self.min_size > self.avg_size || self.avg_size > self.max_size {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "chunk sizes must satisfy 0 < min {} <= avg {} <= max {}",
+                    self.min_size, self.avg_size, self.max_size
+                ),
+            ));
+        }
+        let bits = self.avg_size.ilog2();
+        if self.normalization >= bits || bits + self.normalization >= 64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "normalization level {} is out of range for an average size of {}",
+                    self.normalization, self.avg_size
+                ),
+            ));
+        }
+        let mask = |bits: u32| !0u64 << (64 - bits);
+        Ok((
+            mask(bits + self.normalization),
+            mask(bits - self.normalization),
+        ))
+    }
+}
+
+impl Default for CdcConfig {
+    fn default() -> CdcConfig {
+        CdcConfig::new(2048, 8192, 65536)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CdcChunk {
+    pub spec: ChunkSpec,
+    pub fingerprint: u64,
+}
+
+pub struct CdcChunks<'s, 'a, T, E>
+where
+    T: Seek,
+{
+    stream: &'s mut Stream<'a, T, E>,
+    config: CdcConfig,
+    buf: Vec<u8>,
+    start: usize,
+    end: usize,
+    offset: Option<u64>,
+    eof: bool,
+    done: bool,
+}
+
+impl<'a, T, E> Stream<'a, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn cdc_chunks(&mut self, config: CdcConfig) -> CdcChunks<'_, 'a, T, E> {
+        CdcChunks {
+            stream: self,
+            config,
+            buf: Vec::new(),
+            start: 0,
+            end: 0,
+            offset: None,
+            eof: false,
+            done: false,
+        }
+    }
+}
+
+impl<T, E> CdcChunks<'_, '_, T, E>
+where
+    T: Read + Seek,
+{
+    fn fill(&mut self) -> Result<()> {
+        if self.buf.len() < self.config.max_size {
+            self.buf.resize(self.config.max_size, 0);
+        }
+        if self.end - self.start >= self.config.max_size || self.eof {
+            return Ok(());
+        }
+        self.buf.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+        while self.end < self.config.max_size {
+            let len = self.stream.read(&mut self.buf[self.end..])?;
+            if len == 0 {
+                self.eof = true;
+                break;
+            }
+            self.end += len;
+        }
+        Ok(())
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<CdcChunk>> {
+        let (mask_s, mask_l) = self.config.masks()?;
+        let offset = match self.offset {
+            Some(offset) => offset,
+            None => *self.offset.insert(self.stream.inner.stream_position()?),
+        };
+        self.fill()?;
+        let data = &self.buf[self.start..self.end];
+        if data.is_empty() {
+            return Ok(None);
+        }
+        let (len, fingerprint) = cut(data, &self.config, mask_s, mask_l);
+        self.start += len;
+        self.offset = Some(offset + len as u64);
+        Ok(Some(CdcChunk {
+            spec: ChunkSpec {
+                offset,
+                len: len as u64,
+            },
+            fingerprint,
+        }))
+    }
+}
+
+fn cut(data: &[u8], config: &CdcConfig, mask_s: u64, mask_l: u64) -> (usize, u64) {
+    let mut hash = 0u64;
+    if data.len() <= config.min_size {
+        return (data.len(), hash);
+    }
+    let max_size = std::cmp::min(data.len(), config.max_size);
+    let normal_size = std::cmp::min(config.avg_size, max_size);
+    let mut index = config.min_size;
+    while index < normal_size {
+        hash = (hash << 1).wrapping_add(GEAR[data[index] as usize]);
+        if hash & mask_s == 0 {
+            return (index + 1, hash);
+        }
+        index += 1;
+    }
+    while index < max_size {
+        hash = (hash << 1).wrapping_add(GEAR[data[index] as usize]);
+        if hash & mask_l == 0 {
+            return (index + 1, hash);
+        }
+        index += 1;
+    }
+    (max_size, hash)
+}
+
+impl<T, E> Iterator for CdcChunks<'_, '_, T, E>
+where
+    T: Read + Seek,
+{
+    type Item = Result<CdcChunk>;
+
+    fn next(&mut self) -> Option<Result<CdcChunk>> {
+        if self.done {
+            return None;
+        }
+        let result = self.next_chunk().transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        io::{Cursor, SeekFrom},
+    };
+
+    use super::*;
+
+    fn noise(len: usize, mut state: u32) -> Vec<u8> {
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (state >> 24) as u8
+            })
+            .collect()
+    }
+
+    fn chunks(data: &[u8], config: CdcConfig) -> Vec<CdcChunk> {
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        stream
+            .cdc_chunks(config)
+            .collect::<Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn cdc_chunk_bounds() {
+        let data = noise(256 * 1024, 1);
+        let config = CdcConfig::new(1024, 4096, 16384);
+        let specs = chunks(&data, config);
+        assert!(specs.len() > 16);
+        let mut offset = 0;
+        for (index, CdcChunk { spec, .. }) in specs.iter().enumerate() {
+            assert_eq!(spec.offset, offset);
+            assert!(spec.len <= 16384);
+            assert!(spec.len >= 1024 || index == specs.len() - 1);
+            offset += spec.len;
+        }
+        assert_eq!(offset, data.len() as u64);
+        assert_eq!(specs, chunks(&data, config));
+    }
+
+    #[test]
+    fn cdc_boundaries_survive_insertion() {
+        let data = noise(256 * 1024, 7);
+        let mut shifted = b"inserted prefix".to_vec();
+        shifted.extend_from_slice(&data);
+        let config = CdcConfig::new(512, 2048, 8192);
+        let original = chunks(&data, config);
+        let ends = original
+            .iter()
+            .map(|chunk| (chunk.spec.offset + chunk.spec.len, chunk.fingerprint))
+            .collect::<HashSet<_>>();
+        let shared = chunks(&shifted, config)
+            .iter()
+            .filter(|chunk| {
+                let end = chunk.spec.offset + chunk.spec.len - 15;
+                ends.contains(&(end, chunk.fingerprint))
+            })
+            .count();
+        assert!(shared + 3 >= original.len());
+    }
+
+    #[test]
+    fn cdc_chunk_offsets_inside_chunk() {
+        let data = noise(5000, 3);
+        let mut cursor = Cursor::new(data.clone());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(40)).unwrap();
+        let mut chunk = stream.borrow_chunk(None).unwrap();
+        chunk.read_exact(&mut [0u8; 60]).unwrap();
+        let chunks = chunk
+            .cdc_chunks(CdcConfig::new(64, 256, 1024))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(chunks[0].spec.offset, 100);
+        let lens = chunks.iter().map(|chunk| chunk.spec.len).sum::<u64>();
+        assert_eq!(lens, 4900);
+        let last = chunks.last().unwrap().spec;
+        let mut tail = Vec::new();
+        last.open(&mut cursor)
+            .unwrap()
+            .read_to_end(&mut tail)
+            .unwrap();
+        assert_eq!(tail, data[(last.offset as usize)..]);
+    }
+
+    #[test]
+    fn cdc_invalid_config() {
+        let mut cursor = Cursor::new(vec![0u8; 10]);
+        let mut stream = Stream::new(&mut cursor);
+        let mut iter = stream.cdc_chunks(CdcConfig::new(100, 50, 200));
+        assert_eq!(
+            iter.next().unwrap().unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        assert!(iter.next().is_none());
+        let mut iter = stream.cdc_chunks(CdcConfig::new(1, 4, 8).normalization(2));
+        assert!(iter.next().unwrap().is_err());
+    }
+}
@@ -0,0 +1,479 @@
+use std::{
+    error, fmt,
+    io::{self, BufRead, Error, ErrorKind, Read, Result, Seek, SeekFrom, Write},
+};
+
+use crate::{
+    adler::Adler32,
+    crc::{Crc, Crc32},
+    digest::Digest,
+    tlv::FieldEncoding,
+    Stream,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChecksumPlacement {
+    Header,
+    Trailer,
+}
+
+pub trait Checksum {
+    fn update(&mut self, bytes: &[u8]);
+
+    fn finish(&self) -> u32;
+}
+
+impl Checksum for Crc32 {
+    fn update(&mut self, bytes: &[u8]) {
+        Crc32::update(self, bytes)
+    }
+
+    fn finish(&self) -> u32 {
+        Crc32::finish(self)
+    }
+}
+
+impl Checksum for Crc {
+    fn update(&mut self, bytes: &[u8]) {
+        Crc::update(self, bytes)
+    }
+
+    fn finish(&self) -> u32 {
+        Crc::finish(self)
+    }
+}
+
+impl Checksum for Adler32 {
+    fn update(&mut self, bytes: &[u8]) {
+        Adler32::update(self, bytes)
+    }
+
+    fn finish(&self) -> u32 {
+        Adler32::finish(self)
+    }
+}
+
+pub struct ChecksumReader<R, C> {
+    inner: R,
+    checksum: C,
+}
+
+pub type Crc32Reader<R> = ChecksumReader<R, Crc32>;
+pub type CrcReader<R> = ChecksumReader<R, Crc>;
+pub type Adler32Reader<R> = ChecksumReader<R, Adler32>;
+
+impl<R, C> ChecksumReader<R, C>
+where
+    C: Digest,
+{
+    pub fn new(inner: R, checksum: C) -> ChecksumReader<R, C> {
+        ChecksumReader { inner, checksum }
+    }
+
+    pub fn digest(&self) -> C::Output {
+        self.checksum.finish()
+    }
+
+    pub fn checksum(&self) -> &C {
+        &self.checksum
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, C> Read for ChecksumReader<R, C>
+where
+    R: Read,
+    C: Digest,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.checksum.update(&buf[..len]);
+        Ok(len)
+    }
+}
+
+impl<R, C> BufRead for ChecksumReader<R, C>
+where
+    R: BufRead,
+    C: Digest,
+{
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.checksum.update(&buf[..amt]);
+        }
+        self.inner.consume(amt)
+    }
+}
+
+pub struct ChecksumWriter<W, C> {
+    inner: W,
+    checksum: C,
+}
+
+pub type Crc32Writer<W> = ChecksumWriter<W, Crc32>;
+pub type CrcWriter<W> = ChecksumWriter<W, Crc>;
+pub type Adler32Writer<W> = ChecksumWriter<W, Adler32>;
+
+impl<W, C> ChecksumWriter<W, C>
+where
+    C: Digest,
+{
+    pub fn new(inner: W, checksum: C) -> ChecksumWriter<W, C> {
+        ChecksumWriter { inner, checksum }
+    }
+
+    pub fn digest(&self) -> C::Output {
+        self.checksum.finish()
+    }
+
+    pub fn checksum(&self) -> &C {
+        &self.checksum
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W, C> Write for ChecksumWriter<W, C>
+where
+    W: Write,
+    C: Digest,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.checksum.update(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W, C> ChecksumWriter<W, C>
+where
+    W: Write,
+    C: Checksum,
+{
+    pub fn finalize(mut self, field: FieldEncoding) -> Result<W> {
+        field.write(&mut self.inner, self.checksum.finish().into())?;
+        Ok(self.inner)
+    }
+}
+
+impl<W, C> ChecksumWriter<W, C>
+where
+    W: Write + Seek,
+    C: Checksum,
+{
+    pub fn finalize_at(mut self, pos: u64, field: FieldEncoding) -> Result<W> {
+        if field == FieldEncoding::Varint {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "varint checksum fields cannot be back-patched",
+            ));
+        }
+        let mut buf = Vec::with_capacity(field.encoded_len(0));
+        field.write(&mut buf, self.checksum.finish().into())?;
+        let end = self.inner.stream_position()?;
+        self.inner.seek(SeekFrom::Start(pos))?;
+        self.inner.write_all(&buf)?;
+        self.inner.seek(SeekFrom::Start(end))?;
+        Ok(self.inner)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChecksumMismatch<O> {
+    pub expected: O,
+    pub found: O,
+}
+
+impl<O> fmt::Display for ChecksumMismatch<O>
+where
+    O: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "checksum mismatch: expected {:02x?}, found {:02x?}",
+            self.expected, self.found
+        )
+    }
+}
+
+impl<O> error::Error for ChecksumMismatch<O> where O: fmt::Debug {}
+
+pub struct VerifiedChunk<'a, T, E, D>
+where
+    T: Seek,
+    D: Digest,
+{
+    chunk: Stream<'a, T, E>,
+    digest: D,
+    expected: D::Output,
+    remaining: u64,
+    verified: bool,
+}
+
+impl<T, E, D> VerifiedChunk<'_, T, E, D>
+where
+    T: Read + Seek,
+    D: Digest,
+    D::Output: Clone + PartialEq + fmt::Debug + Send + Sync + 'static,
+{
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    pub fn digest(&self) -> D::Output {
+        self.digest.finish()
+    }
+
+    pub fn expect_exhausted(&mut self) -> Result<()> {
+        if self.remaining > 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("{} bytes of the chunk were not consumed", self.remaining),
+            ));
+        }
+        self.verify()
+    }
+
+    fn verify(&mut self) -> Result<()> {
+        if self.verified {
+            return Ok(());
+        }
+        let found = self.digest.finish();
+        if found != self.expected {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                ChecksumMismatch {
+                    expected: self.expected.clone(),
+                    found,
+                },
+            ));
+        }
+        self.verified = true;
+        Ok(())
+    }
+}
+
+impl<T, E, D> Read for VerifiedChunk<'_, T, E, D>
+where
+    T: Read + Seek,
+    D: Digest,
+    D::Output: Clone + PartialEq + fmt::Debug + Send + Sync + 'static,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = std::cmp::min(buf.len() as u64, self.remaining) as usize;
+        let len = self.chunk.read(&mut buf[..len])?;
+        self.digest.update(&buf[..len]);
+        self.remaining -= len as u64;
+        if self.remaining == 0 {
+            self.verify()?;
+        }
+        Ok(len)
+    }
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn digest_remainder<D>(&mut self, digest: D) -> Result<D::Output>
+    where
+        D: Digest,
+    {
+        let mut reader = ChecksumReader::new(self, digest);
+        io::copy(&mut reader, &mut io::sink())?;
+        Ok(reader.digest())
+    }
+
+    pub fn verified_chunk<D>(
+        &mut self,
+        len: u64,
+        digest: D,
+        expected: D::Output,
+    ) -> Result<VerifiedChunk<'_, T, E, D>>
+    where
+        D: Digest,
+    {
+        let remainder_len = self.remainder_len()?;
+        if len > remainder_len {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!(
+                    "chunk of {} bytes exceeds the remaining {} bytes",
+                    len, remainder_len
+                ),
+            ));
+        }
+        Ok(VerifiedChunk {
+            chunk: self.borrow_chunk(Some(len))?,
+            digest,
+            expected,
+            remaining: len,
+            verified: false,
+        })
+    }
+}
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{
+        crc::{crc, CrcParams},
+        read_num::Endianness,
+    };
+
+    #[test]
+    fn crc32_reader() {
+        let mut reader = Crc32Reader::new(Cursor::new(b"123456789"), Crc32::new());
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf).unwrap();
+        io::copy(&mut reader, &mut io::sink()).unwrap();
+        assert_eq!(reader.digest(), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn crc16_buf_reader() {
+        let mut reader = CrcReader::new(
+            Cursor::new(b"12345\n6789"),
+            Crc::new(CrcParams::CRC16_XMODEM),
+        );
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "12345\n");
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(
+            reader.digest(),
+            crc(CrcParams::CRC16_XMODEM, b"12345\n6789")
+        );
+    }
+
+    #[test]
+    fn adler32_reader_writer() {
+        let mut reader = Adler32Reader::new(Cursor::new(b"Wikipedia"), Adler32::new());
+        let mut writer = Adler32Writer::new(Vec::new(), Adler32::new());
+        io::copy(&mut reader, &mut writer).unwrap();
+        assert_eq!(reader.digest(), 0x11e6_0398);
+        let data = writer
+            .finalize(FieldEncoding::U32(Endianness::Big))
+            .unwrap();
+        assert_eq!(data, b"Wikipedia\x11\xe6\x03\x98");
+    }
+
+    #[test]
+    fn checksum_trailer() {
+        let mut writer = CrcWriter::new(Vec::new(), Crc::new(CrcParams::CRC16_XMODEM));
+        writer.write_all(b"123456789").unwrap();
+        let data = writer
+            .finalize(FieldEncoding::U16(Endianness::Big))
+            .unwrap();
+        assert_eq!(data, b"123456789\x31\xc3");
+    }
+
+    #[test]
+    fn checksum_header() {
+        let field = FieldEncoding::U32(Endianness::Little);
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_all(b"\x00\x00\x00\x00").unwrap();
+        let mut writer = Crc32Writer::new(cursor, Crc32::new());
+        writer.write_all(b"123456789").unwrap();
+        let cursor = writer.finalize_at(0, field).unwrap();
+        assert_eq!(cursor.position(), 13);
+        assert_eq!(cursor.into_inner(), b"\x26\x39\xf4\xcb123456789");
+        let writer = Crc32Writer::new(Cursor::new(Vec::new()), Crc32::new());
+        assert_eq!(
+            writer
+                .finalize_at(0, FieldEncoding::Varint)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn verified_chunk() {
+        let mut cursor = Cursor::new(b"x123456789y".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.read_exact(&mut [0u8; 1]).unwrap();
+        let mut chunk = stream.verified_chunk(9, Crc32::new(), 0xcbf4_3926).unwrap();
+        let mut data = Vec::new();
+        chunk.read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"123456789");
+        chunk.expect_exhausted().unwrap();
+        assert_eq!(cursor.position(), 10);
+    }
+
+    #[test]
+    fn verified_chunk_mismatch() {
+        let mut cursor = Cursor::new(b"123456789".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let mut chunk = stream
+            .verified_chunk(9, Crc::new(CrcParams::CRC16_XMODEM), 0x1234)
+            .unwrap();
+        let mut buf = [0u8; 4];
+        chunk.read_exact(&mut buf).unwrap();
+        let err = chunk.expect_exhausted().unwrap_err();
+        assert_eq!(err.to_string(), "5 bytes of the chunk were not consumed");
+        let err = chunk.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(
+            err.get_ref()
+                .unwrap()
+                .downcast_ref::<ChecksumMismatch<u32>>(),
+            Some(&ChecksumMismatch {
+                expected: 0x1234,
+                found: 0x31c3,
+            })
+        );
+        assert!(chunk.expect_exhausted().is_err());
+        assert_eq!(
+            stream
+                .verified_chunk(1, Crc32::new(), 0)
+                .err()
+                .unwrap()
+                .kind(),
+            ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn crc_writer() {
+        let mut writer = Crc32Writer::new(Vec::new(), Crc32::new());
+        writer.write_all(b"12345").unwrap();
+        writer.write_all(b"6789").unwrap();
+        assert_eq!(writer.digest(), 0xcbf4_3926);
+        let mut writer = CrcWriter::new(Vec::new(), Crc::new(CrcParams::CRC16_MODBUS));
+        writer.write_all(b"123456789").unwrap();
+        assert_eq!(writer.digest(), 0x4b37);
+        assert_eq!(writer.into_inner(), b"123456789");
+    }
+}
@@ -0,0 +1,244 @@
+use std::io::{Error, ErrorKind, Result, Seek, SeekFrom, Write};
+
+use crate::{
+    checksum::{Checksum, ChecksumPlacement, ChecksumWriter},
+    tlv::FieldEncoding,
+};
+
+pub(crate) fn check_patchable(length: FieldEncoding) -> Result<()> {
+    if length == FieldEncoding::Varint {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "varint length fields cannot be back-patched",
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn length_field(
+    length: FieldEncoding,
+    includes_header: bool,
+    start: u64,
+    end: u64,
+) -> Result<(u64, Vec<u8>)> {
+    let header_len = length.encoded_len(0) as u64;
+    let len = if includes_header {
+        end - start
+    } else {
+        end - start - header_len
+    };
+    let mut field = Vec::with_capacity(header_len as usize);
+    length.write(&mut field, len)?;
+    Ok((len, field))
+}
+
+pub struct ChunkWriter<W>
+where
+    W: Write + Seek,
+{
+    inner: W,
+    length: FieldEncoding,
+    includes_header: bool,
+    open: Vec<u64>,
+}
+
+impl<W> ChunkWriter<W>
+where
+    W: Write + Seek,
+{
+    pub fn new(inner: W, length: FieldEncoding) -> ChunkWriter<W> {
+        ChunkWriter {
+            inner,
+            length,
+            includes_header: false,
+            open: Vec::new(),
+        }
+    }
+
+    pub fn includes_header(mut self, includes_header: bool) -> ChunkWriter<W> {
+        self.includes_header = includes_header;
+        self
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    pub fn begin_chunk(&mut self) -> Result<()> {
+        check_patchable(self.length)?;
+        let start = self.inner.stream_position()?;
+        self.length.write(&mut self.inner, 0)?;
+        self.open.push(start);
+        Ok(())
+    }
+
+    pub fn end_chunk(&mut self) -> Result<u64> {
+        let start = self
+            .open
+            .pop()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "no open chunk to end"))?;
+        let end = self.inner.stream_position()?;
+        let (len, field) = length_field(self.length, self.includes_header, start, end)?;
+        self.inner.seek(SeekFrom::Start(start))?;
+        self.inner.write_all(&field)?;
+        self.inner.seek(SeekFrom::Start(end))?;
+        Ok(len)
+    }
+
+    pub fn write_chunk(&mut self, body: &[u8]) -> Result<u64> {
+        self.begin_chunk()?;
+        self.inner.write_all(body)?;
+        self.end_chunk()
+    }
+
+    pub fn write_checksummed_chunk<C>(
+        &mut self,
+        body: &[u8],
+        checksum: C,
+        field: FieldEncoding,
+        placement: ChecksumPlacement,
+    ) -> Result<u64>
+    where
+        C: Checksum,
+    {
+        self.begin_chunk()?;
+        let header_pos = self.inner.stream_position()?;
+        if placement == ChecksumPlacement::Header {
+            field.write(&mut self.inner, 0)?;
+        }
+        let mut writer = ChecksumWriter::new(&mut self.inner, checksum);
+        writer.write_all(body)?;
+        match placement {
+            ChecksumPlacement::Header => writer.finalize_at(header_pos, field)?,
+            ChecksumPlacement::Trailer => writer.finalize(field)?,
+        };
+        self.end_chunk()
+    }
+
+    pub fn into_inner(mut self) -> Result<W> {
+        while !self.open.is_empty() {
+            self.end_chunk()?;
+        }
+        Ok(self.inner)
+    }
+}
+
+impl<W> Write for ChunkWriter<W>
+where
+    W: Write + Seek,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{
+        crc::{Crc, CrcParams},
+        read_num::Endianness,
+        Stream,
+    };
+
+    #[test]
+    fn nested_chunks() {
+        let mut writer =
+            ChunkWriter::new(Cursor::new(Vec::new()), FieldEncoding::U16(Endianness::Big));
+        writer.write_all(b"A").unwrap();
+        writer.begin_chunk().unwrap();
+        writer.write_all(b"xy").unwrap();
+        assert_eq!(writer.write_chunk(b"abc").unwrap(), 3);
+        writer.begin_chunk().unwrap();
+        assert_eq!(writer.depth(), 2);
+        let data = writer.into_inner().unwrap().into_inner();
+        assert_eq!(data, b"A\x00\x09xy\x00\x03abc\x00\x00");
+    }
+
+    #[test]
+    fn includes_header() {
+        let mut writer = ChunkWriter::new(
+            Cursor::new(Vec::new()),
+            FieldEncoding::U32(Endianness::Little),
+        )
+        .includes_header(true);
+        assert_eq!(writer.write_chunk(b"abcd").unwrap(), 8);
+        let data = writer.into_inner().unwrap().into_inner();
+        assert_eq!(data, b"\x08\x00\x00\x00abcd");
+    }
+
+    #[test]
+    fn checksummed_chunks() {
+        let mut writer = ChunkWriter::new(Cursor::new(Vec::new()), FieldEncoding::U8);
+        let field = FieldEncoding::U16(Endianness::Big);
+        let params = CrcParams::CRC16_XMODEM;
+        assert_eq!(
+            writer
+                .write_checksummed_chunk(
+                    b"123456789",
+                    Crc::new(params),
+                    field,
+                    ChecksumPlacement::Trailer
+                )
+                .unwrap(),
+            11
+        );
+        assert_eq!(
+            writer
+                .write_checksummed_chunk(
+                    b"123456789",
+                    Crc::new(params),
+                    field,
+                    ChecksumPlacement::Header
+                )
+                .unwrap(),
+            11
+        );
+        let data = writer.into_inner().unwrap().into_inner();
+        assert_eq!(data, b"\x0b123456789\x31\xc3\x0b\x31\xc3123456789");
+    }
+
+    #[test]
+    fn chunk_writer_inside_stream_limit() {
+        let mut cursor = Cursor::new(vec![0u8; 8]);
+        let mut stream = Stream::new(&mut cursor);
+        let mut chunk = stream.borrow_chunk(Some(4)).unwrap();
+        let mut writer = ChunkWriter::new(&mut chunk, FieldEncoding::U8);
+        writer.begin_chunk().unwrap();
+        assert!(writer.write_all(b"abcd").is_err());
+        assert_eq!(writer.end_chunk().unwrap(), 3);
+        assert_eq!(cursor.get_ref(), b"\x03abc\x00\x00\x00\x00");
+    }
+
+    #[test]
+    fn chunk_writer_errors() {
+        let mut writer = ChunkWriter::new(Cursor::new(Vec::new()), FieldEncoding::Varint);
+        assert_eq!(
+            writer.begin_chunk().unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        let mut writer = ChunkWriter::new(Cursor::new(Vec::new()), FieldEncoding::U8);
+        assert_eq!(
+            writer.end_chunk().unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        writer.begin_chunk().unwrap();
+        writer.write_all(&[0u8; 300]).unwrap();
+        assert!(writer.end_chunk().is_err());
+        assert_eq!(writer.get_mut().position(), 301);
+    }
+}
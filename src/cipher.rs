@@ -0,0 +1,233 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+pub trait Keystream {
+    fn apply(&mut self, offset: u64, buf: &mut [u8]);
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XorKeystream {
+    key: Vec<u8>,
+}
+
+impl XorKeystream {
+    pub fn new(key: &[u8]) -> XorKeystream {
+        XorKeystream { key: key.to_vec() }
+    }
+}
+
+impl Keystream for XorKeystream {
+    fn apply(&mut self, offset: u64, buf: &mut [u8]) {
+        if self.key.is_empty() {
+            return;
+        }
+        let start = (offset % self.key.len() as u64) as usize;
+        let key = self.key.iter().cycle().skip(start);
+        for (byte, k) in buf.iter_mut().zip(key) {
+            *byte ^= k;
+        }
+    }
+}
+
+#[cfg(feature = "aes")]
+pub struct AesCtr {
+    cipher: AesCtrCipher,
+}
+
+#[cfg(feature = "aes")]
+enum AesCtrCipher {
+    Aes128(Box<ctr::Ctr128BE<aes::Aes128>>),
+    Aes256(Box<ctr::Ctr128BE<aes::Aes256>>),
+}
+
+#[cfg(feature = "aes")]
+impl AesCtr {
+    pub fn aes128(key: &[u8; 16], iv: &[u8; 16]) -> AesCtr {
+        use ctr::cipher::KeyIvInit;
+
+        AesCtr {
+            cipher: AesCtrCipher::Aes128(Box::new(ctr::Ctr128BE::new(key.into(), iv.into()))),
+        }
+    }
+
+    pub fn aes256(key: &[u8; 32], iv: &[u8; 16]) -> AesCtr {
+        use ctr::cipher::KeyIvInit;
+
+        AesCtr {
+            cipher: AesCtrCipher::Aes256(Box::new(ctr::Ctr128BE::new(key.into(), iv.into()))),
+        }
+    }
+}
+
+#[cfg(feature = "aes")]
+impl Keystream for AesCtr {
+    fn apply(&mut self, offset: u64, buf: &mut [u8]) {
+        use ctr::cipher::{StreamCipher, StreamCipherSeek};
+
+        match &mut self.cipher {
+            AesCtrCipher::Aes128(cipher) => {
+                cipher.seek(offset);
+                cipher.apply_keystream(buf);
+            }
+            AesCtrCipher::Aes256(cipher) => {
+                cipher.seek(offset);
+                cipher.apply_keystream(buf);
+            }
+        }
+    }
+}
+
+pub struct DecryptReader<R, K> {
+    inner: R,
+    keystream: K,
+    base: u64,
+    pos: u64,
+}
+
+impl<R, K> DecryptReader<R, K>
+where
+    R: Seek,
+    K: Keystream,
+{
+    pub fn new(inner: R, keystream: K) -> Result<DecryptReader<R, K>> {
+        DecryptReader::with_base(inner, keystream, 0)
+    }
+
+    pub fn with_base(mut inner: R, keystream: K, base: u64) -> Result<DecryptReader<R, K>> {
+        let pos = inner.stream_position()?;
+        Ok(DecryptReader {
+            inner,
+            keystream,
+            base,
+            pos,
+        })
+    }
+
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, K> Read for DecryptReader<R, K>
+where
+    R: Read + Seek,
+    K: Keystream,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let offset = self.pos.checked_sub(self.base).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "position {} is before the keystream base {}",
+                    self.pos, self.base
+                ),
+            )
+        })?;
+        let len = self.inner.read(buf)?;
+        self.keystream.apply(offset, &mut buf[..len]);
+        self.pos += len as u64;
+        Ok(len)
+    }
+}
+
+impl<R, K> Seek for DecryptReader<R, K>
+where
+    R: Read + Seek,
+    K: Keystream,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        Ok(self.pos)
+    }
+
+    fn stream_position(&mut self) -> Result<u64> {
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{read_num::ReadNum, Stream};
+
+    fn xor(data: &[u8], key: &[u8]) -> Vec<u8> {
+        let mut data = data.to_vec();
+        XorKeystream::new(key).apply(0, &mut data);
+        data
+    }
+
+    #[test]
+    fn xor_round_trip() {
+        let encrypted = xor(b"hello world", b"key");
+        assert_ne!(encrypted, b"hello world");
+        assert_eq!(xor(&encrypted, b"key"), b"hello world");
+        assert_eq!(xor(b"abc", b""), b"abc");
+    }
+
+    #[test]
+    fn decrypt_reader_seek() {
+        let encrypted = xor(b"\x00\x00\x00\x2aheader\x00\x07", b"\x5a\xa5\x3c");
+        let mut reader =
+            DecryptReader::new(Cursor::new(encrypted), XorKeystream::new(b"\x5a\xa5\x3c")).unwrap();
+        let mut stream = Stream::new(&mut reader).be();
+        stream.seek(SeekFrom::End(-2)).unwrap();
+        assert_eq!(stream.read_u16().unwrap(), 7);
+        stream.seek(SeekFrom::Start(0)).unwrap();
+        assert_eq!(stream.read_u32().unwrap(), 42);
+        let mut chunk = stream.borrow_chunk(Some(6)).unwrap();
+        let mut text = String::new();
+        chunk.read_to_string(&mut text).unwrap();
+        assert_eq!(text, "header");
+    }
+
+    #[test]
+    fn decrypt_reader_with_base() {
+        let mut data = b"plain header:".to_vec();
+        data.extend(xor(b"secret payload", b"\x13\x37"));
+        let mut cursor = Cursor::new(data);
+        cursor.seek(SeekFrom::Start(13)).unwrap();
+        let keystream = XorKeystream::new(b"\x13\x37");
+        let mut reader = DecryptReader::with_base(cursor, keystream, 13).unwrap();
+        assert_eq!(reader.base(), 13);
+        let mut text = String::new();
+        reader.read_to_string(&mut text).unwrap();
+        assert_eq!(text, "secret payload");
+        reader.seek(SeekFrom::Start(20)).unwrap();
+        let mut word = [0u8; 7];
+        reader.read_exact(&mut word).unwrap();
+        assert_eq!(&word, b"payload");
+        reader.seek(SeekFrom::Start(0)).unwrap();
+        let err = reader.read(&mut word).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[cfg(feature = "aes")]
+    #[test]
+    fn aes_ctr_seek() {
+        let key = *b"\x2b\x7e\x15\x16\x28\xae\xd2\xa6\xab\xf7\x15\x88\x09\xcf\x4f\x3c";
+        let iv = *b"\xf0\xf1\xf2\xf3\xf4\xf5\xf6\xf7\xf8\xf9\xfa\xfb\xfc\xfd\xfe\xff";
+        let encrypted = b"\x87\x4d\x61\x91\xb6\x20\xe3\x26\x1b\xef\x68\x64\x99\x0d\xb6\xce\
+            \x98\x06\xf6\x6b\x79\x70\xfd\xff\x86\x17\x18\x7b\xb9\xff\xfd\xff";
+        let mut reader =
+            DecryptReader::new(Cursor::new(encrypted), AesCtr::aes128(&key, &iv)).unwrap();
+        let mut block = [0u8; 16];
+        reader.seek(SeekFrom::Start(16)).unwrap();
+        reader.read_exact(&mut block).unwrap();
+        assert_eq!(
+            &block,
+            b"\xae\x2d\x8a\x57\x1e\x03\xac\x9c\x9e\xb7\x6f\xac\x45\xaf\x8e\x51"
+        );
+        reader.seek(SeekFrom::Start(3)).unwrap();
+        reader.read_exact(&mut block[..2]).unwrap();
+        assert_eq!(&block[..2], b"\xe2\x2e");
+    }
+}
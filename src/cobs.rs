@@ -0,0 +1,372 @@
+use std::io::{BufRead, Error, ErrorKind, Result, Write};
+
+use crate::{
+    delimited::{ReadDelimited, Terminator},
+    frame::FrameCodec,
+};
+
+fn max_encoded_len(len: usize) -> usize {
+    len.saturating_add(len / 254).saturating_add(2)
+}
+
+pub fn cobs_encode(data: &[u8]) -> Vec<u8> {
+    let mut writer = CobsWriter::new(Vec::with_capacity(max_encoded_len(data.len())));
+    writer
+        .write_frame(data)
+        .expect("writing to a Vec cannot fail");
+    let mut encoded = writer.inner;
+    encoded.pop();
+    encoded
+}
+
+pub fn cobs_decode(encoded: &[u8]) -> Result<Vec<u8>> {
+    let mut data = Vec::with_capacity(encoded.len());
+    let mut index = 0;
+    while index < encoded.len() {
+        let code = encoded[index] as usize;
+        index += 1;
+        let end = index + code.saturating_sub(1);
+        if code == 0 || end > encoded.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid COBS code {:#04x} at offset {}", code, index - 1),
+            ));
+        }
+        if encoded[index..end].contains(&0) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "unexpected zero byte inside a COBS block",
+            ));
+        }
+        data.extend_from_slice(&encoded[index..end]);
+        index = end;
+        if code < 0xff && index < encoded.len() {
+            data.push(0);
+        }
+    }
+    Ok(data)
+}
+
+fn check_frame_len(len: usize, max_len: usize) -> Result<()> {
+    if len > max_len {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("frame of {} bytes exceeds the maximum of {}", len, max_len),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CobsCodec {
+    max_len: usize,
+}
+
+impl CobsCodec {
+    pub fn new() -> CobsCodec {
+        CobsCodec {
+            max_len: usize::MAX,
+        }
+    }
+
+    pub fn with_max_len(mut self, max_len: usize) -> CobsCodec {
+        self.max_len = max_len;
+        self
+    }
+}
+
+impl Default for CobsCodec {
+    fn default() -> CobsCodec {
+        CobsCodec::new()
+    }
+}
+
+impl FrameCodec for CobsCodec {
+    fn decode(&mut self, buf: &mut Vec<u8>) -> Result<Option<Vec<u8>>> {
+        loop {
+            let end = match memchr::memchr(0, buf) {
+                Some(end) => end,
+                None if buf.len() > max_encoded_len(self.max_len) => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "no COBS frame delimiter within {} bytes",
+                            max_encoded_len(self.max_len)
+                        ),
+                    ))
+                }
+                None => return Ok(None),
+            };
+            let encoded = buf.drain(..=end).collect::<Vec<_>>();
+            if end == 0 {
+                continue;
+            }
+            let frame = cobs_decode(&encoded[..end])?;
+            check_frame_len(frame.len(), self.max_len)?;
+            return Ok(Some(frame));
+        }
+    }
+
+    fn encode(&mut self, frame: &[u8], dst: &mut Vec<u8>) -> Result<()> {
+        let mut writer = CobsWriter::new(dst);
+        writer.write_frame(frame)
+    }
+}
+
+pub struct CobsWriter<W>
+where
+    W: Write,
+{
+    inner: W,
+    block: Vec<u8>,
+    in_frame: bool,
+}
+
+impl<W> CobsWriter<W>
+where
+    W: Write,
+{
+    pub fn new(inner: W) -> CobsWriter<W> {
+        CobsWriter {
+            inner,
+            block: Vec::with_capacity(254),
+            in_frame: false,
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    fn emit_block(&mut self, code: u8) -> Result<()> {
+        self.inner.write_all(&[code])?;
+        self.inner.write_all(&self.block)?;
+        self.block.clear();
+        Ok(())
+    }
+
+    pub fn end_frame(&mut self) -> Result<()> {
+        let code = match self.block.len() {
+            254 => 0xff,
+            len => len as u8 + 1,
+        };
+        self.emit_block(code)?;
+        self.inner.write_all(&[0])?;
+        self.in_frame = false;
+        Ok(())
+    }
+
+    pub fn write_frame(&mut self, frame: &[u8]) -> Result<()> {
+        self.write_all(frame)?;
+        self.end_frame()
+    }
+
+    pub fn into_inner(mut self) -> Result<W> {
+        if self.in_frame {
+            self.end_frame()?;
+        }
+        Ok(self.inner)
+    }
+}
+
+impl<W> Write for CobsWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        for byte in buf {
+            self.in_frame = true;
+            if self.block.len() == 254 {
+                self.emit_block(0xff)?;
+            }
+            if *byte == 0 {
+                self.emit_block(self.block.len() as u8 + 1)?;
+            } else {
+                self.block.push(*byte);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub struct CobsReader<R>
+where
+    R: BufRead,
+{
+    inner: R,
+    max_len: usize,
+}
+
+impl<R> CobsReader<R>
+where
+    R: BufRead,
+{
+    pub fn new(inner: R) -> CobsReader<R> {
+        CobsReader {
+            inner,
+            max_len: usize::MAX,
+        }
+    }
+
+    pub fn with_max_len(mut self, max_len: usize) -> CobsReader<R> {
+        self.max_len = max_len;
+        self
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    pub fn read_frame(&mut self) -> Result<Option<Vec<u8>>> {
+        loop {
+            let encoded = self.inner.read_until_delimiter(
+                &[0],
+                max_encoded_len(self.max_len),
+                Terminator::Strip,
+            )?;
+            if !encoded.terminated {
+                if encoded.bytes.is_empty() {
+                    return Ok(None);
+                }
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "COBS frame without a terminating zero",
+                ));
+            }
+            if encoded.bytes.is_empty() {
+                continue;
+            }
+            let frame = cobs_decode(&encoded.bytes)?;
+            check_frame_len(frame.len(), self.max_len)?;
+            return Ok(Some(frame));
+        }
+    }
+}
+
+impl<R> Iterator for CobsReader<R>
+where
+    R: BufRead,
+{
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Result<Vec<u8>>> {
+        self.read_frame().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{read_num::ReadNum, Stream};
+
+    #[test]
+    fn cobs_reference_vectors() {
+        let full: Vec<u8> = (1..=254).collect();
+        let cases: Vec<(Vec<u8>, Vec<u8>)> = vec![
+            (vec![0x00], vec![0x01, 0x01]),
+            (vec![0x00, 0x00], vec![0x01, 0x01, 0x01]),
+            (
+                vec![0x11, 0x22, 0x00, 0x33],
+                vec![0x03, 0x11, 0x22, 0x02, 0x33],
+            ),
+            (
+                vec![0x11, 0x00, 0x00, 0x00],
+                vec![0x02, 0x11, 0x01, 0x01, 0x01],
+            ),
+            (
+                [&[0xff][..], &full].concat(),
+                [&[0xff, 0xff][..], &full[..253], &[0x02, 0xfe]].concat(),
+            ),
+            (full.clone(), [&[0xff][..], &full].concat()),
+            (
+                [&[0x00][..], &full].concat(),
+                [&[0x01, 0xff][..], &full].concat(),
+            ),
+            (
+                [&full[..], &[0x00]].concat(),
+                [&[0xff][..], &full, &[0x01, 0x01]].concat(),
+            ),
+        ];
+        for (data, encoded) in cases {
+            assert_eq!(cobs_encode(&data), encoded);
+            assert_eq!(cobs_decode(&encoded).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn cobs_decode_invalid() {
+        let result = cobs_decode(&[0x05, 0x11, 0x22]);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let result = cobs_decode(&[0x03, 0x11, 0x00]);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn cobs_codec() {
+        let mut codec = CobsCodec::new().with_max_len(4);
+        let mut buf = Vec::new();
+        codec.encode(&[0x11, 0x00, 0x22], &mut buf).unwrap();
+        buf.insert(0, 0);
+        buf.push(0x02);
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(vec![0x11, 0x00, 0x22])
+        );
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        assert_eq!(buf, [0x02]);
+        buf.extend_from_slice(&[0x33, 0x00]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(vec![0x33]));
+        let mut buf = vec![0x01; 8];
+        assert_eq!(
+            codec.decode(&mut buf).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn cobs_frames_round_trip() {
+        let mut writer = CobsWriter::new(Vec::new());
+        writer.write_frame(&[0x00, 0x01, 0x02]).unwrap();
+        writer.write_all(&[0xaa, 0x00]).unwrap();
+        writer.write_all(&[0xbb]).unwrap();
+        let mut data = writer.into_inner().unwrap();
+        data.insert(0, 0x00);
+        assert_eq!(data.iter().filter(|b| **b == 0).count(), 3);
+        let reader = CobsReader::new(Cursor::new(data));
+        let frames: Vec<Vec<u8>> = reader.collect::<Result<_>>().unwrap();
+        assert_eq!(frames, [vec![0x00, 0x01, 0x02], vec![0xaa, 0x00, 0xbb]]);
+    }
+
+    #[test]
+    fn cobs_frame_as_stream() {
+        let mut writer = CobsWriter::new(Vec::new());
+        writer.write_frame(&[0x00, 0x00, 0x01, 0x00]).unwrap();
+        let mut reader = CobsReader::new(Cursor::new(writer.into_inner().unwrap()));
+        let mut frame = Cursor::new(reader.read_frame().unwrap().unwrap());
+        let mut stream = Stream::new(&mut frame).be();
+        assert_eq!(stream.read_u32().unwrap(), 0x100);
+        assert_eq!(reader.read_frame().unwrap(), None);
+    }
+
+    #[test]
+    fn cobs_reader_errors() {
+        let mut reader = CobsReader::new(Cursor::new([0x02, 0x11]));
+        let result = reader.read_frame();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        let encoded = cobs_encode(&[1, 2, 3, 4, 5]);
+        let mut reader =
+            CobsReader::new(Cursor::new([&encoded[..], &[0]].concat())).with_max_len(4);
+        let result = reader.read_frame();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
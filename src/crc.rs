@@ -0,0 +1,302 @@
+use std::{
+    borrow::Cow,
+    io::{Error, ErrorKind, Result},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CrcParams {
+    width: u32,
+    poly: u32,
+    init: u32,
+    reflect: bool,
+    xor_out: u32,
+}
+
+impl CrcParams {
+    pub const CRC16_ARC: CrcParams = CrcParams {
+        width: 16,
+        poly: 0x8005,
+        init: 0,
+        reflect: true,
+        xor_out: 0,
+    };
+    pub const CRC16_CCITT_FALSE: CrcParams = CrcParams {
+        width: 16,
+        poly: 0x1021,
+        init: 0xffff,
+        reflect: false,
+        xor_out: 0,
+    };
+    pub const CRC16_MODBUS: CrcParams = CrcParams {
+        width: 16,
+        poly: 0x8005,
+        init: 0xffff,
+        reflect: true,
+        xor_out: 0,
+    };
+    pub const CRC16_X25: CrcParams = CrcParams {
+        width: 16,
+        poly: 0x1021,
+        init: 0xffff,
+        reflect: true,
+        xor_out: 0xffff,
+    };
+    pub const CRC16_XMODEM: CrcParams = CrcParams {
+        width: 16,
+        poly: 0x1021,
+        init: 0,
+        reflect: false,
+        xor_out: 0,
+    };
+    pub const CRC24_OPENPGP: CrcParams = CrcParams {
+        width: 24,
+        poly: 0x86_4cfb,
+        init: 0xb7_04ce,
+        reflect: false,
+        xor_out: 0,
+    };
+    pub const CRC32: CrcParams = CrcParams {
+        width: 32,
+        poly: 0x04c1_1db7,
+        init: u32::MAX,
+        reflect: true,
+        xor_out: u32::MAX,
+    };
+    pub const CRC32C: CrcParams = CrcParams {
+        width: 32,
+        poly: 0x1edc_6f41,
+        init: u32::MAX,
+        reflect: true,
+        xor_out: u32::MAX,
+    };
+
+    pub fn new(width: u32, poly: u32, init: u32, reflect: bool, xor_out: u32) -> Result<CrcParams> {
+        if !(8..=32).contains(&width) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("unsupported CRC width {}", width),
+            ));
+        }
+        let mask = u32::MAX >> (32 - width);
+        Ok(CrcParams {
+            width,
+            poly: poly & mask,
+            init: init & mask,
+            reflect,
+            xor_out: xor_out & mask,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn poly(&self) -> u32 {
+        self.poly
+    }
+
+    pub fn init(&self) -> u32 {
+        self.init
+    }
+
+    pub fn reflect(&self) -> bool {
+        self.reflect
+    }
+
+    pub fn xor_out(&self) -> u32 {
+        self.xor_out
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.width.div_ceil(8) as usize
+    }
+
+    const fn mask(&self) -> u32 {
+        u32::MAX >> (32 - self.width)
+    }
+
+    const fn reflect_bits(&self, value: u32) -> u32 {
+        value.reverse_bits() >> (32 - self.width)
+    }
+
+    const fn table(&self) -> [u32; 256] {
+        let mut table = [0u32; 256];
+        let mut index = 0;
+        while index < 256 {
+            let mut value;
+            let mut bit = 0;
+            if self.reflect {
+                let poly = self.reflect_bits(self.poly);
+                value = index as u32;
+                while bit < 8 {
+                    value = if value & 1 == 1 {
+                        (value >> 1) ^ poly
+                    } else {
+                        value >> 1
+                    };
+                    bit += 1;
+                }
+            } else {
+                let top = 1 << (self.width - 1);
+                value = (index as u32) << (self.width - 8);
+                while bit < 8 {
+                    value = if value & top != 0 {
+                        (value << 1) ^ self.poly
+                    } else {
+                        value << 1
+                    };
+                    bit += 1;
+                }
+                value &= self.mask();
+            }
+            table[index] = value;
+            index += 1;
+        }
+        table
+    }
+}
+
+static CRC32_TABLE: [u32; 256] = CrcParams::CRC32.table();
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Crc {
+    params: CrcParams,
+    table: Cow<'static, [u32]>,
+    state: u32,
+}
+
+impl Crc {
+    pub fn new(params: CrcParams) -> Crc {
+        let state = if params.reflect {
+            params.reflect_bits(params.init)
+        } else {
+            params.init
+        };
+        let table = if params == CrcParams::CRC32 {
+            Cow::Borrowed(&CRC32_TABLE[..])
+        } else {
+            Cow::Owned(params.table().to_vec())
+        };
+        Crc {
+            params,
+            table,
+            state,
+        }
+    }
+
+    pub fn params(&self) -> &CrcParams {
+        &self.params
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        if self.params.reflect {
+            for byte in bytes {
+                let index = (self.state ^ *byte as u32) as u8;
+                self.state = (self.state >> 8) ^ self.table[index as usize];
+            }
+        } else {
+            let shift = self.params.width - 8;
+            for byte in bytes {
+                let index = ((self.state >> shift) ^ *byte as u32) as u8;
+                self.state = ((self.state << 8) ^ self.table[index as usize]) & self.params.mask();
+            }
+        }
+    }
+
+    pub fn finish(&self) -> u32 {
+        (self.state ^ self.params.xor_out) & self.params.mask()
+    }
+}
+
+pub fn crc(params: CrcParams, bytes: &[u8]) -> u32 {
+    let mut crc = Crc::new(params);
+    crc.update(bytes);
+    crc.finish()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Crc32(Crc);
+
+impl Crc32 {
+    pub fn new() -> Crc32 {
+        Crc32(Crc::new(CrcParams::CRC32))
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes)
+    }
+
+    pub fn finish(&self) -> u32 {
+        self.0.finish()
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Crc32 {
+        Crc32::new()
+    }
+}
+
+pub fn crc32(bytes: &[u8]) -> u32 {
+    crc(CrcParams::CRC32, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn crc32_incremental() {
+        let mut crc = Crc32::default();
+        crc.update(b"1234");
+        crc.update(b"56789");
+        assert_eq!(crc.finish(), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn crc_catalogue_check_values() {
+        let check = b"123456789";
+        assert_eq!(crc(CrcParams::CRC16_ARC, check), 0xbb3d);
+        assert_eq!(crc(CrcParams::CRC16_CCITT_FALSE, check), 0x29b1);
+        assert_eq!(crc(CrcParams::CRC16_MODBUS, check), 0x4b37);
+        assert_eq!(crc(CrcParams::CRC16_X25, check), 0x906e);
+        assert_eq!(crc(CrcParams::CRC16_XMODEM, check), 0x31c3);
+        assert_eq!(crc(CrcParams::CRC24_OPENPGP, check), 0x21_cf02);
+        assert_eq!(crc(CrcParams::CRC32, check), 0xcbf4_3926);
+        assert_eq!(crc(CrcParams::CRC32C, check), 0xe306_9283);
+    }
+
+    #[test]
+    fn crc_incremental() {
+        let mut crc = Crc::new(CrcParams::CRC16_CCITT_FALSE);
+        crc.update(b"12345");
+        crc.update(b"6789");
+        assert_eq!(crc.finish(), 0x29b1);
+        assert_eq!(crc.params().byte_len(), 2);
+    }
+
+    #[test]
+    fn crc_params_validation() {
+        for width in [0, 1, 7, 33] {
+            let err = CrcParams::new(width, 0x07, 0, false, 0).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        }
+        let crc8 = CrcParams::new(8, 0x07, 0, false, 0).unwrap();
+        assert_eq!(crc(crc8, b"123456789"), 0xf4);
+        let crc8_rohc = CrcParams::new(8, 0x07, 0xff, true, 0).unwrap();
+        assert_eq!(crc(crc8_rohc, b"123456789"), 0xd0);
+        let crc24 = CrcParams::new(24, 0x86_4cfb, 0xb7_04ce, false, 0).unwrap();
+        assert_eq!(crc24, CrcParams::CRC24_OPENPGP);
+        assert_eq!(crc24.byte_len(), 3);
+        let crc32 = CrcParams::new(32, 0x04c1_1db7, u32::MAX, true, u32::MAX).unwrap();
+        assert_eq!(crc32, CrcParams::CRC32);
+        let masked = CrcParams::new(16, 0xff_1021, 0, false, 0).unwrap();
+        assert_eq!(masked.poly(), 0x1021);
+    }
+}
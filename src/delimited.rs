@@ -0,0 +1,225 @@
+use std::io::{BufRead, Error, ErrorKind, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Terminator {
+    Include,
+    Strip,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Delimited {
+    pub bytes: Vec<u8>,
+    pub terminated: bool,
+}
+
+pub trait ReadDelimited: BufRead {
+    fn read_line_bounded(&mut self, max_len: usize) -> Result<Option<String>> {
+        let mut line = Vec::new();
+        let mut found = false;
+        while !found {
+            let available = self.fill_buf()?;
+            if available.is_empty() {
+                break;
+            }
+            let (used, end) = match available.iter().position(|b| *b == b'\n') {
+                Some(i) => {
+                    found = true;
+                    (i + 1, i)
+                }
+                None => (available.len(), available.len()),
+            };
+            if line.len() + end > max_len + 1 {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("line exceeds the maximum length of {} bytes", max_len),
+                ));
+            }
+            line.extend_from_slice(&available[..end]);
+            self.consume(used);
+        }
+        if !found && line.is_empty() {
+            return Ok(None);
+        }
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        if line.len() > max_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("line exceeds the maximum length of {} bytes", max_len),
+            ));
+        }
+        String::from_utf8(line)
+            .map(Some)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+
+    fn read_until_delimiter(
+        &mut self,
+        delimiter: &[u8],
+        max_len: usize,
+        terminator: Terminator,
+    ) -> Result<Delimited> {
+        if delimiter.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "empty delimiter"));
+        }
+        let limit = max_len.saturating_add(delimiter.len());
+        let mut bytes = Vec::new();
+        let mut terminated = false;
+        while !terminated {
+            let available = self.fill_buf()?;
+            if available.is_empty() {
+                break;
+            }
+            let mut used = 0;
+            for byte in available {
+                if bytes.len() == limit {
+                    break;
+                }
+                bytes.push(*byte);
+                used += 1;
+                if bytes.ends_with(delimiter) {
+                    terminated = true;
+                    break;
+                }
+            }
+            self.consume(used);
+            if !terminated && bytes.len() == limit {
+                break;
+            }
+        }
+        if !terminated && bytes.len() > max_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("no delimiter found within {} bytes", max_len),
+            ));
+        }
+        if terminated && terminator == Terminator::Strip {
+            bytes.truncate(bytes.len() - delimiter.len());
+        }
+        Ok(Delimited { bytes, terminated })
+    }
+}
+
+impl<R> ReadDelimited for R where R: BufRead + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::*;
+    use crate::Stream;
+
+    #[test]
+    fn read_lines() {
+        let mut reader = Cursor::new(b"ply\r\nformat ascii 1.0\nend_header".to_vec());
+        assert_eq!(reader.read_line_bounded(32).unwrap().unwrap(), "ply");
+        assert_eq!(
+            reader.read_line_bounded(32).unwrap().unwrap(),
+            "format ascii 1.0"
+        );
+        assert_eq!(reader.read_line_bounded(32).unwrap().unwrap(), "end_header");
+        assert_eq!(reader.read_line_bounded(32).unwrap(), None);
+    }
+
+    #[test]
+    fn read_empty_lines() {
+        let mut reader = Cursor::new(b"\n\r\n".to_vec());
+        assert_eq!(reader.read_line_bounded(0).unwrap().unwrap(), "");
+        assert_eq!(reader.read_line_bounded(0).unwrap().unwrap(), "");
+        assert_eq!(reader.read_line_bounded(0).unwrap(), None);
+    }
+
+    #[test]
+    fn read_line_too_long() {
+        let mut reader = Cursor::new(b"abcd\r\n".to_vec());
+        assert!(reader.read_line_bounded(3).is_err());
+        let mut reader = Cursor::new(b"abcd\r\n".to_vec());
+        assert_eq!(reader.read_line_bounded(4).unwrap().unwrap(), "abcd");
+    }
+
+    #[test]
+    fn read_line_stops_at_chunk_limit() {
+        let mut cursor = Cursor::new(b"P5\n640 480\n255\n\x00\x01".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let chunk = stream.borrow_chunk(Some(15)).unwrap();
+        let mut reader = BufReader::with_capacity(4, chunk);
+        assert_eq!(reader.read_line_bounded(70).unwrap().unwrap(), "P5");
+        assert_eq!(reader.read_line_bounded(70).unwrap().unwrap(), "640 480");
+        assert_eq!(reader.read_line_bounded(70).unwrap().unwrap(), "255");
+        assert_eq!(reader.read_line_bounded(70).unwrap(), None);
+    }
+
+    #[test]
+    fn read_until_single_byte_delimiter() {
+        let mut reader = Cursor::new(b"key=value;rest".to_vec());
+        let record = reader
+            .read_until_delimiter(b"=", 16, Terminator::Strip)
+            .unwrap();
+        assert_eq!(
+            record,
+            Delimited {
+                bytes: b"key".to_vec(),
+                terminated: true
+            }
+        );
+        let record = reader
+            .read_until_delimiter(b";", 16, Terminator::Include)
+            .unwrap();
+        assert_eq!(record.bytes, b"value;");
+        let record = reader
+            .read_until_delimiter(b";", 16, Terminator::Include)
+            .unwrap();
+        assert_eq!(
+            record,
+            Delimited {
+                bytes: b"rest".to_vec(),
+                terminated: false
+            }
+        );
+    }
+
+    #[test]
+    fn read_until_pattern_across_buffer_boundaries() {
+        let data = b"first\r\n\r\nsecond".to_vec();
+        let mut reader = BufReader::with_capacity(3, Cursor::new(data));
+        let record = reader
+            .read_until_delimiter(b"\r\n\r\n", 64, Terminator::Strip)
+            .unwrap();
+        assert_eq!(record.bytes, b"first");
+        assert!(record.terminated);
+        let mut rest = String::new();
+        std::io::Read::read_to_string(&mut reader, &mut rest).unwrap();
+        assert_eq!(rest, "second");
+    }
+
+    #[test]
+    fn read_until_limit() {
+        let mut reader = Cursor::new(b"abcdef;".to_vec());
+        let result = reader.read_until_delimiter(b";", 5, Terminator::Strip);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let mut reader = Cursor::new(b"abcdef;".to_vec());
+        let record = reader
+            .read_until_delimiter(b";", 6, Terminator::Strip)
+            .unwrap();
+        assert_eq!(record.bytes, b"abcdef");
+        let mut reader = Cursor::new(b"abcdef\r\n".to_vec());
+        let record = reader
+            .read_until_delimiter(b"\r\n", 6, Terminator::Include)
+            .unwrap();
+        assert_eq!(record.bytes, b"abcdef\r\n");
+        let mut reader = Cursor::new(b"abcdefg".to_vec());
+        let result = reader.read_until_delimiter(b"\r\n", 6, Terminator::Strip);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn line_and_delimiter_limits_agree() {
+        let mut reader = Cursor::new(b"abc\nabc\n".to_vec());
+        assert_eq!(reader.read_line_bounded(3).unwrap().unwrap(), "abc");
+        let record = reader
+            .read_until_delimiter(b"\n", 3, Terminator::Strip)
+            .unwrap();
+        assert_eq!(record.bytes, b"abc");
+    }
+}
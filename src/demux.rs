@@ -0,0 +1,168 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{Error, ErrorKind, Read, Result},
+};
+
+pub struct Demuxer<R, F> {
+    inner: R,
+    next_record: F,
+    queues: HashMap<u64, VecDeque<u8>>,
+    buffered: usize,
+    max_buffered: usize,
+    finished: bool,
+}
+
+impl<R, F> Demuxer<R, F>
+where
+    F: FnMut(&mut R) -> Result<Option<(u64, Vec<u8>)>>,
+{
+    pub fn new(inner: R, next_record: F) -> Demuxer<R, F> {
+        Demuxer {
+            inner,
+            next_record,
+            queues: HashMap::new(),
+            buffered: 0,
+            max_buffered: usize::MAX,
+            finished: false,
+        }
+    }
+
+    pub fn with_max_buffered(mut self, max_buffered: usize) -> Demuxer<R, F> {
+        self.max_buffered = max_buffered;
+        self
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    pub fn buffered_len(&self, channel: u64) -> usize {
+        self.queues.get(&channel).map_or(0, VecDeque::len)
+    }
+
+    pub fn channel(&mut self, channel: u64) -> Channel<'_, R, F> {
+        Channel {
+            demuxer: self,
+            channel,
+        }
+    }
+
+    pub fn read_channel(&mut self, channel: u64, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            if let Some(queue) = self.queues.get_mut(&channel).filter(|q| !q.is_empty()) {
+                let len = queue.read(buf)?;
+                self.buffered -= len;
+                return Ok(len);
+            }
+            if self.finished {
+                return Ok(0);
+            }
+            match (self.next_record)(&mut self.inner)? {
+                None => self.finished = true,
+                Some((id, payload)) => {
+                    if id != channel && self.buffered + payload.len() > self.max_buffered {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!(
+                                "buffering {} bytes for channel {} exceeds the {} byte limit",
+                                payload.len(),
+                                id,
+                                self.max_buffered
+                            ),
+                        ));
+                    }
+                    self.buffered += payload.len();
+                    self.queues.entry(id).or_default().extend(payload);
+                }
+            }
+        }
+    }
+}
+
+pub struct Channel<'d, R, F> {
+    demuxer: &'d mut Demuxer<R, F>,
+    channel: u64,
+}
+
+impl<R, F> Channel<'_, R, F> {
+    pub fn id(&self) -> u64 {
+        self.channel
+    }
+}
+
+impl<R, F> Read for Channel<'_, R, F>
+where
+    F: FnMut(&mut R) -> Result<Option<(u64, Vec<u8>)>>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.demuxer.read_channel(self.channel, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn record(reader: &mut Cursor<Vec<u8>>) -> Result<Option<(u64, Vec<u8>)>> {
+        let mut header = [0u8; 2];
+        if reader.read(&mut header[..1])? == 0 {
+            return Ok(None);
+        }
+        reader.read_exact(&mut header[1..])?;
+        let mut payload = vec![0u8; header[1] as usize];
+        reader.read_exact(&mut payload)?;
+        Ok(Some((header[0].into(), payload)))
+    }
+
+    fn interleaved() -> Cursor<Vec<u8>> {
+        Cursor::new(b"\x01\x02ab\x02\x01X\x01\x01c\x03\x02zz\x02\x02YZ".to_vec())
+    }
+
+    #[test]
+    fn demux_channels() {
+        let mut demuxer = Demuxer::new(interleaved(), record);
+        let mut first = String::new();
+        demuxer.channel(1).read_to_string(&mut first).unwrap();
+        assert_eq!(first, "abc");
+        assert_eq!(demuxer.buffered_len(2), 3);
+        assert_eq!(demuxer.buffered_len(3), 2);
+        let mut second = String::new();
+        demuxer.channel(2).read_to_string(&mut second).unwrap();
+        assert_eq!(second, "XYZ");
+        assert_eq!(demuxer.channel(4).read(&mut [0u8; 4]).unwrap(), 0);
+    }
+
+    #[test]
+    fn demux_buffers_only_what_is_needed() {
+        let mut demuxer = Demuxer::new(interleaved(), record);
+        let mut buf = [0u8; 1];
+        demuxer.channel(2).read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"X");
+        assert_eq!(demuxer.buffered_len(1), 2);
+        assert_eq!(demuxer.get_ref().position(), 7);
+    }
+
+    #[test]
+    fn demux_max_buffered() {
+        let mut demuxer = Demuxer::new(interleaved(), record).with_max_buffered(2);
+        let mut buf = Vec::new();
+        let err = demuxer.channel(3).read_to_end(&mut buf).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "buffering 1 bytes for channel 2 exceeds the 2 byte limit"
+        );
+    }
+}
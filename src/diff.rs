@@ -0,0 +1,215 @@
+use std::io::{self, Read, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiffOptions {
+    block_size: usize,
+    max_ranges: usize,
+}
+
+impl DiffOptions {
+    pub fn new() -> DiffOptions {
+        DiffOptions {
+            block_size: 8 * 1024,
+            max_ranges: 1,
+        }
+    }
+
+    pub fn block_size(mut self, block_size: usize) -> DiffOptions {
+        assert!(block_size > 0, "block size must not be zero");
+        self.block_size = block_size;
+        self
+    }
+
+    pub fn max_ranges(mut self, max_ranges: usize) -> DiffOptions {
+        assert!(max_ranges > 0, "range cap must not be zero");
+        self.max_ranges = max_ranges;
+        self
+    }
+}
+
+impl Default for DiffOptions {
+    fn default() -> DiffOptions {
+        DiffOptions::new()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiffRange {
+    pub offset: u64,
+    pub len: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Diff {
+    pub ranges: Vec<DiffRange>,
+    pub truncated: bool,
+}
+
+impl Diff {
+    pub fn is_equal(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn first_mismatch(&self) -> Option<u64> {
+        self.ranges.first().map(|range| range.offset)
+    }
+}
+
+fn read_block<R: Read + ?Sized>(reader: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..])? {
+            0 => break,
+            n => len += n,
+        }
+    }
+    Ok(len)
+}
+
+pub fn diff<A, B>(a: &mut A, b: &mut B, options: &DiffOptions) -> Result<Diff>
+where
+    A: Read + ?Sized,
+    B: Read + ?Sized,
+{
+    let mut block_a = vec![0u8; options.block_size];
+    let mut block_b = vec![0u8; options.block_size];
+    let mut diff = Diff::default();
+    let mut open = None;
+    let mut offset = 0u64;
+    loop {
+        let len_a = read_block(a, &mut block_a)?;
+        let len_b = read_block(b, &mut block_b)?;
+        let common = std::cmp::min(len_a, len_b);
+        if open.is_some() || block_a[..common] != block_b[..common] {
+            for i in 0..common {
+                match (block_a[i] != block_b[i], open) {
+                    (true, None) if diff.ranges.len() == options.max_ranges => {
+                        diff.truncated = true;
+                        return Ok(diff);
+                    }
+                    (true, None) => open = Some(offset + i as u64),
+                    (false, Some(start)) => {
+                        let end = offset + i as u64;
+                        diff.ranges.push(DiffRange {
+                            offset: start,
+                            len: end - start,
+                        });
+                        open = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        offset += common as u64;
+        if len_a != len_b {
+            if open.is_none() && diff.ranges.len() == options.max_ranges {
+                diff.truncated = true;
+                return Ok(diff);
+            }
+            let start = open.unwrap_or(offset);
+            let tail = if len_a > len_b {
+                (len_a - common) as u64 + io::copy(a, &mut io::sink())?
+            } else {
+                (len_b - common) as u64 + io::copy(b, &mut io::sink())?
+            };
+            diff.ranges.push(DiffRange {
+                offset: start,
+                len: offset + tail - start,
+            });
+            return Ok(diff);
+        }
+        if len_a == 0 {
+            if let Some(start) = open {
+                diff.ranges.push(DiffRange {
+                    offset: start,
+                    len: offset - start,
+                });
+            }
+            return Ok(diff);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Seek, SeekFrom};
+
+    use super::*;
+    use crate::Stream;
+
+    fn range(offset: u64, len: u64) -> DiffRange {
+        DiffRange { offset, len }
+    }
+
+    #[test]
+    fn diff_equal_streams() {
+        let data = (0..10_000u32).map(|i| i as u8).collect::<Vec<_>>();
+        let result = diff(&mut &data[..], &mut &data[..], &DiffOptions::new()).unwrap();
+        assert!(result.is_equal());
+        assert!(!result.truncated);
+        assert_eq!(result.first_mismatch(), None);
+    }
+
+    #[test]
+    fn diff_ranges_across_blocks() {
+        let a = b"0123456789abcdefghij".to_vec();
+        let mut b = a.clone();
+        b[2] = b'x';
+        b[6..10].copy_from_slice(b"XXXX");
+        b[19] = b'!';
+        let options = DiffOptions::new().block_size(4).max_ranges(10);
+        let result = diff(&mut &a[..], &mut &b[..], &options).unwrap();
+        assert_eq!(result.ranges, [range(2, 1), range(6, 4), range(19, 1)]);
+        assert!(!result.truncated);
+        let options = DiffOptions::new().block_size(4).max_ranges(2);
+        let result = diff(&mut &a[..], &mut &b[..], &options).unwrap();
+        assert_eq!(result.ranges, [range(2, 1), range(6, 4)]);
+        assert!(result.truncated);
+        let result = diff(&mut &a[..], &mut &b[..], &DiffOptions::new()).unwrap();
+        assert_eq!(result.first_mismatch(), Some(2));
+    }
+
+    #[test]
+    fn diff_exactly_max_ranges() {
+        let a = b"0123456789".to_vec();
+        let mut b = a.clone();
+        b[4] = b'x';
+        let result = diff(&mut &a[..], &mut &b[..], &DiffOptions::new()).unwrap();
+        assert_eq!(result.ranges, [range(4, 1)]);
+        assert!(!result.truncated);
+        b[7] = b'y';
+        let options = DiffOptions::new().block_size(3).max_ranges(2);
+        let result = diff(&mut &a[..], &mut &b[..], &options).unwrap();
+        assert_eq!(result.ranges, [range(4, 1), range(7, 1)]);
+        assert!(!result.truncated);
+        let result = diff(&mut &a[..], &mut &b[..9], &options).unwrap();
+        assert_eq!(result.ranges, [range(4, 1), range(7, 1)]);
+        assert!(result.truncated);
+        let result = diff(&mut &a[..], &mut &b[..], &DiffOptions::new()).unwrap();
+        assert_eq!(result.ranges, [range(4, 1)]);
+        assert!(result.truncated);
+    }
+
+    #[test]
+    fn diff_length_mismatch() {
+        let options = DiffOptions::new().block_size(3).max_ranges(4);
+        let result = diff(&mut &b"abcdefgh"[..], &mut &b"abcdeXXhijkl"[..], &options).unwrap();
+        assert_eq!(result.ranges, [range(5, 2), range(8, 4)]);
+        let result = diff(&mut &b"abcdefgh"[..], &mut &b"abcd"[..], &options).unwrap();
+        assert_eq!(result.ranges, [range(4, 4)]);
+        let result = diff(&mut &b""[..], &mut &b"a"[..], &options).unwrap();
+        assert_eq!(result.ranges, [range(0, 1)]);
+    }
+
+    #[test]
+    fn diff_stream_chunks() {
+        let mut golden = Cursor::new(b"HDR\x00\x01\x02\x03".to_vec());
+        let mut written = Cursor::new(b"\x00\x01\x02\x04tail".to_vec());
+        let mut golden_stream = Stream::new(&mut golden);
+        golden_stream.seek(SeekFrom::Start(3)).unwrap();
+        let mut written_stream = Stream::new(&mut written);
+        let mut chunk = written_stream.borrow_chunk(Some(4)).unwrap();
+        let result = diff(&mut golden_stream, &mut chunk, &DiffOptions::new()).unwrap();
+        assert_eq!(result.ranges, [range(3, 1)]);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::checksum::Checksum;
+
+pub trait Digest {
+    type Output;
+
+    fn update(&mut self, bytes: &[u8]);
+
+    fn finish(&self) -> Self::Output;
+}
+
+impl<C> Digest for C
+where
+    C: Checksum,
+{
+    type Output = u32;
+
+    fn update(&mut self, bytes: &[u8]) {
+        Checksum::update(self, bytes)
+    }
+
+    fn finish(&self) -> u32 {
+        Checksum::finish(self)
+    }
+}
+
+#[cfg(feature = "sha2")]
+impl Digest for sha2::Sha256 {
+    type Output = [u8; 32];
+
+    fn update(&mut self, bytes: &[u8]) {
+        sha2::Digest::update(self, bytes)
+    }
+
+    fn finish(&self) -> [u8; 32] {
+        sha2::Digest::finalize(self.clone()).into()
+    }
+}
+
+#[cfg(feature = "sha2")]
+impl Digest for sha2::Sha512 {
+    type Output = [u8; 64];
+
+    fn update(&mut self, bytes: &[u8]) {
+        sha2::Digest::update(self, bytes)
+    }
+
+    fn finish(&self) -> [u8; 64] {
+        sha2::Digest::finalize(self.clone()).into()
+    }
+}
+
+#[cfg(feature = "blake3")]
+impl Digest for blake3::Hasher {
+    type Output = [u8; 32];
+
+    fn update(&mut self, bytes: &[u8]) {
+        blake3::Hasher::update(self, bytes);
+    }
+
+    fn finish(&self) -> [u8; 32] {
+        blake3::Hasher::finalize(self).into()
+    }
+}
+
+#[cfg(feature = "xxhash")]
+impl Digest for xxhash_rust::xxh64::Xxh64 {
+    type Output = u64;
+
+    fn update(&mut self, bytes: &[u8]) {
+        xxhash_rust::xxh64::Xxh64::update(self, bytes)
+    }
+
+    fn finish(&self) -> u64 {
+        self.digest()
+    }
+}
+
+#[cfg(feature = "xxhash")]
+impl Digest for xxhash_rust::xxh3::Xxh3 {
+    type Output = u64;
+
+    fn update(&mut self, bytes: &[u8]) {
+        xxhash_rust::xxh3::Xxh3::update(self, bytes)
+    }
+
+    fn finish(&self) -> u64 {
+        self.digest()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor};
+
+    use crate::{checksum::ChecksumReader, crc::Crc32};
+
+    #[test]
+    fn checksum_as_digest() {
+        let mut reader = ChecksumReader::new(Cursor::new(b"123456789"), Crc32::new());
+        io::copy(&mut reader, &mut io::sink()).unwrap();
+        assert_eq!(reader.digest(), 0xcbf4_3926);
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn sha256_writer() {
+        use std::io::Write;
+
+        use crate::checksum::ChecksumWriter;
+
+        let mut writer = ChecksumWriter::new(Vec::new(), sha2::Sha256::default());
+        writer.write_all(b"a").unwrap();
+        writer.write_all(b"bc").unwrap();
+        assert_eq!(writer.digest()[..4], [0xba, 0x78, 0x16, 0xbf]);
+        assert_eq!(writer.into_inner(), b"abc");
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn blake3_reader() {
+        use std::io::Read;
+
+        let mut reader = ChecksumReader::new(Cursor::new(b"abc"), blake3::Hasher::new());
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(reader.digest(), *blake3::hash(b"abc").as_bytes());
+    }
+
+    #[cfg(feature = "xxhash")]
+    #[test]
+    fn xxhash_reader() {
+        use std::io::{Read, Write};
+
+        use crate::checksum::ChecksumWriter;
+
+        let mut reader =
+            ChecksumReader::new(Cursor::new(b"abc"), xxhash_rust::xxh64::Xxh64::new(0));
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(reader.digest(), xxhash_rust::xxh64::xxh64(b"abc", 0));
+        let mut writer = ChecksumWriter::new(Vec::new(), xxhash_rust::xxh3::Xxh3::new());
+        writer.write_all(b"abc").unwrap();
+        assert_eq!(writer.digest(), xxhash_rust::xxh3::xxh3_64(b"abc"));
+    }
+}
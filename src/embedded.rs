@@ -0,0 +1,239 @@
+use alloc::format;
+
+use embedded_io::ErrorType;
+
+use crate::{
+    io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write},
+    Stream,
+};
+
+macro_rules! map_error_kinds {
+    ($($kind: ident),*) => {
+        fn error_kind(kind: embedded_io::ErrorKind) -> ErrorKind {
+            match kind {
+                $(embedded_io::ErrorKind::$kind => ErrorKind::$kind,)*
+                _ => ErrorKind::Other,
+            }
+        }
+
+        #[cfg(not(feature = "std"))]
+        impl embedded_io::Error for Error {
+            fn kind(&self) -> embedded_io::ErrorKind {
+                match Error::kind(self) {
+                    $(ErrorKind::$kind => embedded_io::ErrorKind::$kind,)*
+                    _ => embedded_io::ErrorKind::Other,
+                }
+            }
+        }
+    };
+}
+
+map_error_kinds! {
+    NotFound, PermissionDenied, ConnectionRefused, ConnectionReset, ConnectionAborted,
+    NotConnected, AddrInUse, AddrNotAvailable, BrokenPipe, AlreadyExists, InvalidInput,
+    InvalidData, TimedOut, Interrupted, Unsupported, OutOfMemory, WriteZero
+}
+
+fn to_io_error<E: embedded_io::Error>(e: E) -> Error {
+    Error::new(error_kind(e.kind()), format!("{:?}", e))
+}
+
+fn to_seek_from(pos: embedded_io::SeekFrom) -> SeekFrom {
+    match pos {
+        embedded_io::SeekFrom::Start(n) => SeekFrom::Start(n),
+        embedded_io::SeekFrom::End(n) => SeekFrom::End(n),
+        embedded_io::SeekFrom::Current(n) => SeekFrom::Current(n),
+    }
+}
+
+fn to_embedded_seek_from(pos: SeekFrom) -> embedded_io::SeekFrom {
+    match pos {
+        SeekFrom::Start(n) => embedded_io::SeekFrom::Start(n),
+        SeekFrom::End(n) => embedded_io::SeekFrom::End(n),
+        SeekFrom::Current(n) => embedded_io::SeekFrom::Current(n),
+    }
+}
+
+pub struct EmbeddedIo<T> {
+    inner: T,
+}
+
+impl<T> EmbeddedIo<T> {
+    pub fn new(inner: T) -> EmbeddedIo<T> {
+        EmbeddedIo { inner }
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> Read for EmbeddedIo<T>
+where
+    T: embedded_io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf).map_err(to_io_error)
+    }
+}
+
+impl<T> Write for EmbeddedIo<T>
+where
+    T: embedded_io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf).map_err(to_io_error)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush().map_err(to_io_error)
+    }
+}
+
+impl<T> Seek for EmbeddedIo<T>
+where
+    T: embedded_io::Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.inner
+            .seek(to_embedded_seek_from(pos))
+            .map_err(to_io_error)
+    }
+}
+
+impl<T, E> ErrorType for Stream<'_, T, E>
+where
+    T: Seek,
+{
+    type Error = Error;
+}
+
+impl<T, E> embedded_io::Read for Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        Read::read(self, buf)
+    }
+}
+
+impl<T, E> embedded_io::Write for Stream<'_, T, E>
+where
+    T: Write + Seek,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        Write::write(self, buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Write::flush(self)
+    }
+}
+
+impl<T, E> embedded_io::Seek for Stream<'_, T, E>
+where
+    T: Seek,
+{
+    fn seek(&mut self, pos: embedded_io::SeekFrom) -> Result<u64> {
+        Seek::seek(self, to_seek_from(pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, SeekFrom};
+
+    use embedded_io::ErrorKind;
+
+    use super::*;
+    use crate::read_num::{ReadEndianExt, ReadNum};
+
+    struct Flash {
+        data: Vec<u8>,
+        pos: usize,
+    }
+
+    impl ErrorType for Flash {
+        type Error = ErrorKind;
+    }
+
+    impl embedded_io::Read for Flash {
+        fn read(&mut self, buf: &mut [u8]) -> std::result::Result<usize, ErrorKind> {
+            if self.pos == 3 {
+                self.pos += 1;
+                return Err(ErrorKind::TimedOut);
+            }
+            let len = buf.len().min(self.data.len().saturating_sub(self.pos));
+            buf[..len].copy_from_slice(&self.data[self.pos..self.pos + len]);
+            self.pos += len;
+            Ok(len)
+        }
+    }
+
+    impl embedded_io::Seek for Flash {
+        fn seek(&mut self, pos: embedded_io::SeekFrom) -> std::result::Result<u64, ErrorKind> {
+            let pos = match pos {
+                embedded_io::SeekFrom::Start(n) => Some(n),
+                embedded_io::SeekFrom::End(n) => (self.data.len() as u64).checked_add_signed(n),
+                embedded_io::SeekFrom::Current(n) => (self.pos as u64).checked_add_signed(n),
+            };
+            let pos = pos.ok_or(ErrorKind::InvalidInput)?;
+            self.pos = pos as usize;
+            Ok(pos)
+        }
+    }
+
+    fn flash() -> EmbeddedIo<Flash> {
+        EmbeddedIo::new(Flash {
+            data: b"IMG\x00\x00\x00\x04\x12\x34\x56\x78tail".to_vec(),
+            pos: 0,
+        })
+    }
+
+    #[test]
+    fn read_numbers_from_embedded_reader() {
+        let mut flash = flash();
+        flash.seek(SeekFrom::Start(5)).unwrap();
+        assert_eq!(flash.be().read_u16().unwrap(), 0x0004);
+        assert_eq!(flash.le().read_u32().unwrap(), 0x7856_3412);
+        flash.seek(SeekFrom::Start(3)).unwrap();
+        let err = flash.read_exact(&mut [0u8; 2]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(flash.into_inner().pos, 4);
+    }
+
+    #[test]
+    fn stream_implements_embedded_io() {
+        let mut flash = flash();
+        flash.seek(SeekFrom::Start(6)).unwrap();
+        let mut stream = Stream::new(&mut flash).be();
+        let mut chunk = stream.borrow_chunk(Some(5)).unwrap();
+        assert_eq!(chunk.read_u8().unwrap(), 4);
+        let mut buf = [0u8; 8];
+        let len = embedded_io::Read::read(&mut chunk, &mut buf).unwrap();
+        assert_eq!(&buf[..len], [0x12, 0x34, 0x56, 0x78]);
+        let pos = embedded_io::Seek::seek(&mut chunk, embedded_io::SeekFrom::End(-2)).unwrap();
+        assert_eq!(pos, 3);
+        let err =
+            embedded_io::Seek::seek(&mut chunk, embedded_io::SeekFrom::Current(-9)).unwrap_err();
+        assert_eq!(embedded_io::Error::kind(&err), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn stream_embedded_write() {
+        let mut cursor = io::Cursor::new(vec![0u8; 4]);
+        let mut stream = Stream::new(&mut cursor);
+        embedded_io::Write::write_all(&mut stream, b"ab").unwrap();
+        let len = embedded_io::Write::write(&mut stream, b"cdef").unwrap();
+        assert_eq!(len, 2);
+        assert_eq!(cursor.into_inner(), b"abcd");
+    }
+}
@@ -0,0 +1,130 @@
+use core::{fmt, mem};
+
+use crate::io::{Read, Result, Write};
+
+pub trait EndianValue: Sized {
+    type Native;
+
+    fn read_from<R: Read + ?Sized>(reader: &mut R) -> Result<Self>;
+    fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()>;
+}
+
+macro_rules! endian_type {
+    ($name: ident, $type: ty, $from_bytes: ident, $to_bytes: ident) => {
+        #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+        #[repr(transparent)]
+        pub struct $name([u8; mem::size_of::<$type>()]);
+
+        impl $name {
+            pub fn new(value: $type) -> $name {
+                $name(value.$to_bytes())
+            }
+
+            pub fn get(self) -> $type {
+                <$type>::$from_bytes(self.0)
+            }
+
+            pub fn from_bytes(bytes: [u8; mem::size_of::<$type>()]) -> $name {
+                $name(bytes)
+            }
+
+            pub fn to_bytes(self) -> [u8; mem::size_of::<$type>()] {
+                self.0
+            }
+        }
+
+        impl From<$type> for $name {
+            fn from(value: $type) -> $name {
+                $name::new(value)
+            }
+        }
+
+        impl From<$name> for $type {
+            fn from(value: $name) -> $type {
+                value.get()
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.get()).finish()
+            }
+        }
+
+        impl EndianValue for $name {
+            type Native = $type;
+
+            fn read_from<R: Read + ?Sized>(reader: &mut R) -> Result<$name> {
+                let mut buf = [0u8; mem::size_of::<$type>()];
+                reader.read_exact(&mut buf)?;
+                Ok($name(buf))
+            }
+
+            fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+                writer.write_all(&self.0)
+            }
+        }
+    };
+}
+
+endian_type! {U16Be, u16, from_be_bytes, to_be_bytes}
+endian_type! {U32Be, u32, from_be_bytes, to_be_bytes}
+endian_type! {U64Be, u64, from_be_bytes, to_be_bytes}
+endian_type! {U128Be, u128, from_be_bytes, to_be_bytes}
+endian_type! {I16Be, i16, from_be_bytes, to_be_bytes}
+endian_type! {I32Be, i32, from_be_bytes, to_be_bytes}
+endian_type! {I64Be, i64, from_be_bytes, to_be_bytes}
+endian_type! {I128Be, i128, from_be_bytes, to_be_bytes}
+endian_type! {F32Be, f32, from_be_bytes, to_be_bytes}
+endian_type! {F64Be, f64, from_be_bytes, to_be_bytes}
+
+endian_type! {U16Le, u16, from_le_bytes, to_le_bytes}
+endian_type! {U32Le, u32, from_le_bytes, to_le_bytes}
+endian_type! {U64Le, u64, from_le_bytes, to_le_bytes}
+endian_type! {U128Le, u128, from_le_bytes, to_le_bytes}
+endian_type! {I16Le, i16, from_le_bytes, to_le_bytes}
+endian_type! {I32Le, i32, from_le_bytes, to_le_bytes}
+endian_type! {I64Le, i64, from_le_bytes, to_le_bytes}
+endian_type! {I128Le, i128, from_le_bytes, to_le_bytes}
+endian_type! {F32Le, f32, from_le_bytes, to_le_bytes}
+endian_type! {F64Le, f64, from_le_bytes, to_le_bytes}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    macro_rules! generate_endian_type_test {
+        ($test_name: ident, $name: ident, $value: expr, $to_bytes: ident) => {
+            #[test]
+            fn $test_name() {
+                let data = $value.$to_bytes();
+                let mut cursor = Cursor::new(data);
+                let value = $name::read_from(&mut cursor).unwrap();
+                assert_eq!(value.get(), $value);
+                let mut out = Vec::new();
+                value.write_to(&mut out).unwrap();
+                assert_eq!(out, data);
+            }
+        };
+    }
+
+    generate_endian_type_test! {endian_type_u16_be, U16Be, 0x0102u16, to_be_bytes}
+    generate_endian_type_test! {endian_type_u32_be, U32Be, 0x01020304u32, to_be_bytes}
+    generate_endian_type_test! {endian_type_i64_be, I64Be, i64::MIN, to_be_bytes}
+    generate_endian_type_test! {endian_type_f64_be, F64Be, 1.5f64, to_be_bytes}
+    generate_endian_type_test! {endian_type_u16_le, U16Le, 0x0102u16, to_le_bytes}
+    generate_endian_type_test! {endian_type_u32_le, U32Le, 0x01020304u32, to_le_bytes}
+    generate_endian_type_test! {endian_type_i64_le, I64Le, i64::MIN, to_le_bytes}
+    generate_endian_type_test! {endian_type_f64_le, F64Le, 1.5f64, to_le_bytes}
+
+    #[test]
+    fn endian_type_conversions() {
+        let value = U32Be::from(0x01020304);
+        assert_eq!(value.to_bytes(), [1, 2, 3, 4]);
+        assert_eq!(u32::from(value), 0x01020304);
+        assert_eq!(U32Le::from_bytes([1, 2, 3, 4]).get(), 0x04030201);
+        assert_eq!(format!("{:?}", U16Le::new(7)), "U16Le(7)");
+    }
+}
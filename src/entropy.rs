@@ -0,0 +1,241 @@
+use std::io::{self, Read, Result, Seek, Write};
+
+use crate::Stream;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ByteHistogram {
+    counts: [u64; 256],
+    total: u64,
+}
+
+impl ByteHistogram {
+    pub fn new() -> ByteHistogram {
+        ByteHistogram {
+            counts: [0; 256],
+            total: 0,
+        }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.counts[*byte as usize] += 1;
+        }
+        self.total += bytes.len() as u64;
+    }
+
+    pub fn merge(&mut self, other: &ByteHistogram) {
+        for (count, other) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += other;
+        }
+        self.total += other.total;
+    }
+
+    pub fn count(&self, byte: u8) -> u64 {
+        self.counts[byte as usize]
+    }
+
+    pub fn counts(&self) -> &[u64; 256] {
+        &self.counts
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn distinct(&self) -> usize {
+        self.counts.iter().filter(|count| **count > 0).count()
+    }
+
+    pub fn entropy(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        let total = self.total as f64;
+        self.counts
+            .iter()
+            .filter(|count| **count > 0)
+            .map(|count| {
+                let p = *count as f64 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+}
+
+impl Default for ByteHistogram {
+    fn default() -> ByteHistogram {
+        ByteHistogram::new()
+    }
+}
+
+pub struct EntropyAnalyzer<T> {
+    inner: T,
+    region_size: Option<u64>,
+    current: ByteHistogram,
+    regions: Vec<ByteHistogram>,
+}
+
+impl<T> EntropyAnalyzer<T> {
+    pub fn new(inner: T) -> EntropyAnalyzer<T> {
+        EntropyAnalyzer {
+            inner,
+            region_size: None,
+            current: ByteHistogram::new(),
+            regions: Vec::new(),
+        }
+    }
+
+    pub fn region_size(mut self, region_size: u64) -> EntropyAnalyzer<T> {
+        self.region_size = Some(region_size).filter(|size| *size > 0);
+        self
+    }
+
+    pub fn finish_region(&mut self) -> Option<&ByteHistogram> {
+        if self.current.total() == 0 {
+            return None;
+        }
+        self.regions.push(std::mem::take(&mut self.current));
+        self.regions.last()
+    }
+
+    pub fn current(&self) -> &ByteHistogram {
+        &self.current
+    }
+
+    pub fn regions(&self) -> &[ByteHistogram] {
+        &self.regions
+    }
+
+    pub fn total(&self) -> ByteHistogram {
+        let mut total = self.current.clone();
+        for region in &self.regions {
+            total.merge(region);
+        }
+        total
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn record(&mut self, mut bytes: &[u8]) {
+        while let Some(region_size) = self.region_size {
+            let room = (region_size - self.current.total()) as usize;
+            if bytes.len() < room {
+                break;
+            }
+            self.current.update(&bytes[..room]);
+            self.finish_region();
+            bytes = &bytes[room..];
+        }
+        self.current.update(bytes);
+    }
+}
+
+impl<T> Read for EntropyAnalyzer<T>
+where
+    T: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.record(&buf[..len]);
+        Ok(len)
+    }
+}
+
+impl<T> Write for EntropyAnalyzer<T>
+where
+    T: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.record(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn histogram(&mut self) -> Result<ByteHistogram> {
+        let mut analyzer = EntropyAnalyzer::new(self);
+        io::copy(&mut analyzer, &mut io::sink())?;
+        Ok(analyzer.current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, SeekFrom};
+
+    use super::*;
+
+    #[test]
+    fn histogram_entropy() {
+        let mut histogram = ByteHistogram::new();
+        assert_eq!(histogram.entropy(), 0.0);
+        histogram.update(b"aaaa");
+        assert_eq!(histogram.entropy(), 0.0);
+        histogram.update(b"bbbb");
+        assert_eq!(histogram.entropy(), 1.0);
+        let mut uniform = ByteHistogram::new();
+        uniform.update(&(0..=255).collect::<Vec<u8>>());
+        assert_eq!(uniform.entropy(), 8.0);
+        assert_eq!(uniform.distinct(), 256);
+        histogram.merge(&uniform);
+        assert_eq!(histogram.total(), 264);
+        assert_eq!(histogram.count(b'a'), 5);
+    }
+
+    #[test]
+    fn analyzer_regions() {
+        let mut data = vec![0u8; 6];
+        data.extend(0..10u8);
+        let mut analyzer = EntropyAnalyzer::new(Cursor::new(data)).region_size(4);
+        let mut buf = Vec::new();
+        analyzer.read_to_end(&mut buf).unwrap();
+        let regions = analyzer.regions();
+        assert_eq!(regions.len(), 4);
+        assert_eq!(regions[0].entropy(), 0.0);
+        assert_eq!(regions[1].count(0), 3);
+        assert_eq!(regions[2].entropy(), 2.0);
+        assert_eq!(regions[3].entropy(), 2.0);
+        assert_eq!(analyzer.current().total(), 0);
+        assert_eq!(analyzer.total().total(), 16);
+        assert!(analyzer.finish_region().is_none());
+    }
+
+    #[test]
+    fn analyzer_manual_regions() {
+        let mut analyzer = EntropyAnalyzer::new(Vec::new());
+        analyzer.write_all(b"abab").unwrap();
+        assert_eq!(analyzer.finish_region().unwrap().entropy(), 1.0);
+        analyzer.write_all(b"zzz").unwrap();
+        assert_eq!(analyzer.current().distinct(), 1);
+        assert_eq!(analyzer.into_inner(), b"ababzzz");
+    }
+
+    #[test]
+    fn chunk_histogram() {
+        let mut cursor = Cursor::new(b"header\x00\x01\x02\x03trailer".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(6)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(4)).unwrap();
+        let histogram = chunk.histogram().unwrap();
+        assert_eq!(histogram.total(), 4);
+        assert_eq!(histogram.entropy(), 2.0);
+    }
+}
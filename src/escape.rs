@@ -0,0 +1,309 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EscapeCodec {
+    escape: u8,
+    escaped: [Option<u8>; 256],
+    unescaped: [Option<u8>; 256],
+}
+
+impl EscapeCodec {
+    pub fn new(escape: u8, escape_code: u8) -> EscapeCodec {
+        EscapeCodec {
+            escape,
+            escaped: [None; 256],
+            unescaped: [None; 256],
+        }
+        .reserve(escape, escape_code)
+    }
+
+    pub fn reserve(mut self, raw: u8, code: u8) -> EscapeCodec {
+        if let Some(previous) = self.escaped[raw as usize].take() {
+            self.unescaped[previous as usize] = None;
+        }
+        self.escaped[raw as usize] = Some(code);
+        self.unescaped[code as usize] = Some(raw);
+        self
+    }
+
+    pub fn reserve_xor(self, raw: u8, mask: u8) -> EscapeCodec {
+        self.reserve(raw, raw ^ mask)
+    }
+
+    pub fn hdlc() -> EscapeCodec {
+        EscapeCodec::new(0x7d, 0x5d).reserve_xor(0x7e, 0x20)
+    }
+
+    pub fn slip() -> EscapeCodec {
+        EscapeCodec::new(0xdb, 0xdd).reserve(0xc0, 0xdc)
+    }
+
+    pub fn escape(&self) -> u8 {
+        self.escape
+    }
+
+    pub fn is_reserved(&self, byte: u8) -> bool {
+        self.escaped[byte as usize].is_some()
+    }
+
+    pub fn encode_into(&self, data: &[u8], out: &mut Vec<u8>) {
+        for byte in data {
+            match self.escaped[*byte as usize] {
+                Some(code) => out.extend([self.escape, code]),
+                None => out.push(*byte),
+            }
+        }
+    }
+
+    fn unescape(&self, code: u8) -> Result<u8> {
+        self.unescaped[code as usize].ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid escape sequence {:#04x} {:#04x}", self.escape, code),
+            )
+        })
+    }
+}
+
+pub struct EscapeReader<R>
+where
+    R: Read,
+{
+    inner: R,
+    codec: EscapeCodec,
+    delimiter: Option<u8>,
+    pending: Vec<u8>,
+    frame_done: bool,
+}
+
+impl<R> EscapeReader<R>
+where
+    R: Read,
+{
+    pub fn new(inner: R, codec: EscapeCodec) -> EscapeReader<R> {
+        EscapeReader {
+            inner,
+            codec,
+            delimiter: None,
+            pending: Vec::new(),
+            frame_done: false,
+        }
+    }
+
+    pub fn delimiter(mut self, delimiter: u8) -> EscapeReader<R> {
+        assert!(
+            delimiter != self.codec.escape,
+            "the frame delimiter cannot be the escape byte"
+        );
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    pub fn is_frame_done(&self) -> bool {
+        self.frame_done
+    }
+
+    pub fn next_frame(&mut self) {
+        self.frame_done = false;
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn read_raw(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.pending.is_empty() {
+            return self.inner.read(buf);
+        }
+        let len = std::cmp::min(buf.len(), self.pending.len());
+        buf[..len].copy_from_slice(&self.pending[..len]);
+        self.pending.drain(..len);
+        Ok(len)
+    }
+
+    fn read_raw_byte(&mut self) -> Result<u8> {
+        if self.pending.is_empty() {
+            let mut byte = [0u8; 1];
+            self.inner.read_exact(&mut byte)?;
+            Ok(byte[0])
+        } else {
+            Ok(self.pending.remove(0))
+        }
+    }
+}
+
+impl<R> Read for EscapeReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.frame_done {
+            return Ok(0);
+        }
+        let n = self.read_raw(buf)?;
+        let mut len = 0;
+        let mut index = 0;
+        while index < n {
+            let byte = buf[index];
+            index += 1;
+            buf[len] = if Some(byte) == self.delimiter {
+                self.frame_done = true;
+                self.pending.splice(..0, buf[index..n].iter().copied());
+                break;
+            } else if byte == self.codec.escape {
+                let code = if index < n {
+                    index += 1;
+                    buf[index - 1]
+                } else {
+                    self.read_raw_byte()?
+                };
+                self.codec.unescape(code)?
+            } else if self.codec.is_reserved(byte) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("unescaped reserved byte {:#04x}", byte),
+                ));
+            } else {
+                byte
+            };
+            len += 1;
+        }
+        Ok(len)
+    }
+}
+
+pub struct EscapeWriter<W>
+where
+    W: Write,
+{
+    inner: W,
+    codec: EscapeCodec,
+}
+
+impl<W> EscapeWriter<W>
+where
+    W: Write,
+{
+    pub fn new(inner: W, codec: EscapeCodec) -> EscapeWriter<W> {
+        EscapeWriter { inner, codec }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W> Write for EscapeWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let mut encoded = Vec::with_capacity(buf.len() + buf.len() / 8);
+        self.codec.encode_into(buf, &mut encoded);
+        self.inner.write_all(&encoded)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::*;
+    use crate::read_num::{ReadEndianExt, ReadNum};
+
+    #[test]
+    fn hdlc_escaping() {
+        let mut writer = EscapeWriter::new(Vec::new(), EscapeCodec::hdlc());
+        writer.write_all(&[0x01, 0x7e, 0x7d, 0x02]).unwrap();
+        assert_eq!(writer.get_ref(), &[0x01, 0x7d, 0x5e, 0x7d, 0x5d, 0x02]);
+        let mut reader = EscapeReader::new(Cursor::new(writer.into_inner()), EscapeCodec::hdlc());
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, [0x01, 0x7e, 0x7d, 0x02]);
+    }
+
+    #[test]
+    fn slip_escaping_with_numeric_reads() {
+        let mut writer = EscapeWriter::new(Vec::new(), EscapeCodec::slip());
+        writer.write_all(&0xc0db_00c0u32.to_be_bytes()).unwrap();
+        let encoded = writer.into_inner();
+        assert_eq!(encoded, [0xdb, 0xdc, 0xdb, 0xdd, 0x00, 0xdb, 0xdc]);
+        let mut reader = EscapeReader::new(
+            BufReader::with_capacity(1, Cursor::new(encoded)),
+            EscapeCodec::slip(),
+        );
+        assert_eq!(reader.be().read_u32().unwrap(), 0xc0db_00c0);
+    }
+
+    #[test]
+    fn custom_codec() {
+        let codec = EscapeCodec::new(b'\\', b'\\')
+            .reserve(b'\n', b'n')
+            .reserve(b'\0', b'0');
+        let mut out = Vec::new();
+        codec.encode_into(b"a\nb\\\0", &mut out);
+        assert_eq!(out, b"a\\nb\\\\\\0");
+        let mut reader = EscapeReader::new(Cursor::new(out), codec);
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"a\nb\\\0");
+    }
+
+    #[test]
+    fn invalid_escapes() {
+        let mut data = Vec::new();
+        let mut reader = EscapeReader::new(Cursor::new([0x7d, 0x00]), EscapeCodec::hdlc());
+        let result = reader.read_to_end(&mut data);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let mut reader = EscapeReader::new(Cursor::new([0x01, 0x7d]), EscapeCodec::hdlc());
+        let result = reader.read_to_end(&mut data);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        let mut reader = EscapeReader::new(Cursor::new([0x01, 0x7e]), EscapeCodec::hdlc());
+        let result = reader.read_to_end(&mut data);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn slip_frames_back_to_back() {
+        let mut encoded = Vec::new();
+        EscapeCodec::slip().encode_into(&[0x01, 0xc0, 0x02], &mut encoded);
+        encoded.push(0xc0);
+        EscapeCodec::slip().encode_into(&[0xdb, 0x03], &mut encoded);
+        encoded.push(0xc0);
+        let mut reader = EscapeReader::new(
+            BufReader::with_capacity(64, Cursor::new(encoded)),
+            EscapeCodec::slip(),
+        )
+        .delimiter(0xc0);
+        let mut frame = Vec::new();
+        reader.read_to_end(&mut frame).unwrap();
+        assert_eq!(frame, [0x01, 0xc0, 0x02]);
+        assert!(reader.is_frame_done());
+        reader.next_frame();
+        frame.clear();
+        reader.read_to_end(&mut frame).unwrap();
+        assert_eq!(frame, [0xdb, 0x03]);
+        reader.next_frame();
+        frame.clear();
+        reader.read_to_end(&mut frame).unwrap();
+        assert!(frame.is_empty());
+        assert!(!reader.is_frame_done());
+    }
+}
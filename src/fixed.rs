@@ -0,0 +1,235 @@
+use std::io::{Error, ErrorKind, Result};
+
+use crate::{read_num::ReadNum, write_num::WriteNum};
+
+pub trait FixedPoint: Copy {
+    const BITS: u32;
+
+    fn read_from<R: ReadNum + ?Sized>(reader: &mut R) -> Result<Self>;
+    fn write_to<W: WriteNum + ?Sized>(self, writer: &mut W) -> Result<()>;
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Option<Self>;
+}
+
+macro_rules! impl_fixed_point {
+    ($type: ty, $read: ident, $write: ident) => {
+        impl FixedPoint for $type {
+            const BITS: u32 = <$type>::BITS;
+
+            fn read_from<R: ReadNum + ?Sized>(reader: &mut R) -> Result<$type> {
+                reader.$read()
+            }
+
+            fn write_to<W: WriteNum + ?Sized>(self, writer: &mut W) -> Result<()> {
+                writer.$write(self)
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(value: f64) -> Option<$type> {
+                let value_bits = if <$type>::MIN == 0 {
+                    <$type>::BITS
+                } else {
+                    <$type>::BITS - 1
+                };
+                if value.is_finite()
+                    && value >= <$type>::MIN as f64
+                    && value < (value_bits as f64).exp2()
+                {
+                    Some(value as $type)
+                } else {
+                    None
+                }
+            }
+        }
+    };
+}
+
+impl_fixed_point! {u8, read_u8, write_u8}
+impl_fixed_point! {u16, read_u16, write_u16}
+impl_fixed_point! {u32, read_u32, write_u32}
+impl_fixed_point! {u64, read_u64, write_u64}
+impl_fixed_point! {i8, read_i8, write_i8}
+impl_fixed_point! {i16, read_i16, write_i16}
+impl_fixed_point! {i32, read_i32, write_i32}
+impl_fixed_point! {i64, read_i64, write_i64}
+
+fn check_format<T: FixedPoint>(int_bits: u32, frac_bits: u32) -> Result<()> {
+    match int_bits.checked_add(frac_bits) {
+        Some(bits) if bits == T::BITS => Ok(()),
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "invalid Q{}.{} format for a {}-bit value",
+                int_bits,
+                frac_bits,
+                T::BITS
+            ),
+        )),
+    }
+}
+
+pub trait ReadFixed: ReadNum {
+    fn read_fixed<T: FixedPoint>(&mut self, int_bits: u32, frac_bits: u32) -> Result<f64> {
+        check_format::<T>(int_bits, frac_bits)?;
+        let raw = T::read_from(self)?;
+        Ok(raw.to_f64() / (frac_bits as f64).exp2())
+    }
+
+    fn read_fixed_u16_8_8(&mut self) -> Result<f64> {
+        self.read_fixed::<u16>(8, 8)
+    }
+
+    fn read_fixed_i16_8_8(&mut self) -> Result<f64> {
+        self.read_fixed::<i16>(8, 8)
+    }
+
+    fn read_fixed_i16_2_14(&mut self) -> Result<f64> {
+        self.read_fixed::<i16>(2, 14)
+    }
+
+    fn read_fixed_u32_16_16(&mut self) -> Result<f64> {
+        self.read_fixed::<u32>(16, 16)
+    }
+
+    fn read_fixed_i32_16_16(&mut self) -> Result<f64> {
+        self.read_fixed::<i32>(16, 16)
+    }
+
+    fn read_fixed_i32_2_30(&mut self) -> Result<f64> {
+        self.read_fixed::<i32>(2, 30)
+    }
+}
+
+impl<R> ReadFixed for R where R: ReadNum + ?Sized {}
+
+pub trait WriteFixed: WriteNum {
+    fn write_fixed<T: FixedPoint>(
+        &mut self,
+        value: f64,
+        int_bits: u32,
+        frac_bits: u32,
+    ) -> Result<()> {
+        check_format::<T>(int_bits, frac_bits)?;
+        match T::from_f64((value * (frac_bits as f64).exp2()).round()) {
+            Some(raw) => raw.write_to(self),
+            None => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "value {} out of range for Q{}.{} format",
+                    value, int_bits, frac_bits
+                ),
+            )),
+        }
+    }
+
+    fn write_fixed_u16_8_8(&mut self, value: f64) -> Result<()> {
+        self.write_fixed::<u16>(value, 8, 8)
+    }
+
+    fn write_fixed_i16_8_8(&mut self, value: f64) -> Result<()> {
+        self.write_fixed::<i16>(value, 8, 8)
+    }
+
+    fn write_fixed_i16_2_14(&mut self, value: f64) -> Result<()> {
+        self.write_fixed::<i16>(value, 2, 14)
+    }
+
+    fn write_fixed_u32_16_16(&mut self, value: f64) -> Result<()> {
+        self.write_fixed::<u32>(value, 16, 16)
+    }
+
+    fn write_fixed_i32_16_16(&mut self, value: f64) -> Result<()> {
+        self.write_fixed::<i32>(value, 16, 16)
+    }
+
+    fn write_fixed_i32_2_30(&mut self, value: f64) -> Result<()> {
+        self.write_fixed::<i32>(value, 2, 30)
+    }
+}
+
+impl<W> WriteFixed for W where W: WriteNum + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Seek, SeekFrom};
+
+    use super::*;
+    use crate::Stream;
+
+    #[test]
+    fn read_fixed_16_16() {
+        let mut cursor = Cursor::new([0x00, 0x01, 0x80, 0x00, 0xff, 0xff, 0x80, 0x00]);
+        let mut stream = Stream::new(&mut cursor).be();
+        assert_eq!(stream.read_fixed_u32_16_16().unwrap(), 1.5);
+        assert_eq!(stream.read_fixed_i32_16_16().unwrap(), -0.5);
+    }
+
+    #[test]
+    fn read_fixed_2_30_and_2_14() {
+        let mut cursor = Cursor::new([0x40, 0x00, 0x00, 0x00, 0xe0, 0x00]);
+        let mut stream = Stream::new(&mut cursor).be();
+        assert_eq!(stream.read_fixed_i32_2_30().unwrap(), 1.0);
+        assert_eq!(stream.read_fixed_i16_2_14().unwrap(), -0.5);
+    }
+
+    #[test]
+    fn read_fixed_generic() {
+        let mut cursor = Cursor::new([0x03, 0x01]);
+        let mut stream = Stream::new(&mut cursor).le();
+        assert_eq!(stream.read_fixed::<u16>(8, 8).unwrap(), 1.0 + 3.0 / 256.0);
+    }
+
+    #[test]
+    fn read_fixed_with_invalid_format() {
+        let mut cursor = Cursor::new([0u8; 4]);
+        let mut stream = Stream::new(&mut cursor);
+        let result = stream.read_fixed::<u32>(16, 8);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn write_fixed_round_trip() {
+        let mut cursor = Cursor::new(vec![0u8; 10]);
+        let mut stream = Stream::new(&mut cursor).be();
+        stream.write_fixed_i32_16_16(-1.25).unwrap();
+        stream.write_fixed_i16_8_8(2.5).unwrap();
+        stream.write_fixed_i32_2_30(0.75).unwrap();
+        let _ = stream.seek(SeekFrom::Start(0));
+        assert_eq!(stream.read_fixed_i32_16_16().unwrap(), -1.25);
+        assert_eq!(stream.read_fixed_i16_8_8().unwrap(), 2.5);
+        assert_eq!(stream.read_fixed_i32_2_30().unwrap(), 0.75);
+    }
+
+    #[test]
+    fn write_fixed_out_of_range() {
+        let mut cursor = Cursor::new(vec![0u8; 2]);
+        let mut stream = Stream::new(&mut cursor).be();
+        let result = stream.write_fixed_u16_8_8(256.0);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+        let result = stream.write_fixed_i16_8_8(f64::NAN);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn from_f64_boundaries() {
+        assert_eq!(u64::from_f64(18_446_744_073_709_551_616.0), None);
+        assert_eq!(
+            u64::from_f64(18_446_744_073_709_549_568.0),
+            Some(u64::MAX - 2047)
+        );
+        assert_eq!(u64::from_f64(-1.0), None);
+        assert_eq!(i64::from_f64(9_223_372_036_854_775_808.0), None);
+        assert_eq!(i64::from_f64(-9_223_372_036_854_775_808.0), Some(i64::MIN));
+        assert_eq!(
+            i64::from_f64(9_223_372_036_854_774_784.0),
+            Some(i64::MAX - 1023)
+        );
+        assert_eq!(u8::from_f64(255.0), Some(u8::MAX));
+        assert_eq!(u8::from_f64(256.0), None);
+        assert_eq!(i8::from_f64(-128.0), Some(i8::MIN));
+        assert_eq!(i8::from_f64(128.0), None);
+    }
+}
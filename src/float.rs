@@ -0,0 +1,171 @@
+use std::io::{Error, ErrorKind, Result, Seek};
+
+use crate::{read_num::ReadNum, write_num::WriteNum};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bf16(pub u16);
+
+impl Bf16 {
+    pub fn from_bits(bits: u16) -> Bf16 {
+        Bf16(bits)
+    }
+
+    pub fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    pub fn from_f32(value: f32) -> Bf16 {
+        let bits = value.to_bits();
+        if value.is_nan() {
+            return Bf16(((bits >> 16) as u16) | 0x0040);
+        }
+        let rounding_bias = 0x7fff + ((bits >> 16) & 1);
+        Bf16((bits.wrapping_add(rounding_bias) >> 16) as u16)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        f32::from_bits((self.0 as u32) << 16)
+    }
+}
+
+fn non_finite_error(value: f64, type_name: &str, offset: u64) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "non-finite {} value {} at offset {}",
+            type_name, value, offset
+        ),
+    )
+}
+
+pub trait ReadFloat: ReadNum {
+    fn read_f32_finite(&mut self) -> Result<f32>
+    where
+        Self: Seek,
+    {
+        let offset = self.stream_position()?;
+        let value = self.read_f32()?;
+        match value.is_finite() {
+            true => Ok(value),
+            false => Err(non_finite_error(value as f64, "f32", offset)),
+        }
+    }
+
+    fn read_f64_finite(&mut self) -> Result<f64>
+    where
+        Self: Seek,
+    {
+        let offset = self.stream_position()?;
+        let value = self.read_f64()?;
+        match value.is_finite() {
+            true => Ok(value),
+            false => Err(non_finite_error(value, "f64", offset)),
+        }
+    }
+
+    fn read_f32_canonical(&mut self) -> Result<f32> {
+        let value = self.read_f32()?;
+        Ok(if value.is_nan() { f32::NAN } else { value })
+    }
+
+    fn read_f64_canonical(&mut self) -> Result<f64> {
+        let value = self.read_f64()?;
+        Ok(if value.is_nan() { f64::NAN } else { value })
+    }
+
+    fn read_bf16(&mut self) -> Result<Bf16> {
+        Ok(Bf16(self.read_u16()?))
+    }
+
+    fn read_bf16_as_f32(&mut self) -> Result<f32> {
+        Ok(self.read_bf16()?.to_f32())
+    }
+}
+
+impl<R> ReadFloat for R where R: ReadNum + ?Sized {}
+
+pub trait WriteFloat: WriteNum {
+    fn write_bf16(&mut self, value: Bf16) -> Result<()> {
+        self.write_u16(value.0)
+    }
+
+    fn write_bf16_from_f32(&mut self, value: f32) -> Result<()> {
+        self.write_bf16(Bf16::from_f32(value))
+    }
+}
+
+impl<W> WriteFloat for W where W: WriteNum + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Seek, SeekFrom};
+
+    use super::*;
+    use crate::{read_num::ReadEndianExt, Stream};
+
+    #[test]
+    fn read_finite_floats() {
+        let mut data = 1.5f32.to_be_bytes().to_vec();
+        data.extend((-2.25f64).to_be_bytes());
+        let mut cursor = Cursor::new(data);
+        let mut reader = cursor.be();
+        assert_eq!(reader.read_f32_finite().unwrap(), 1.5);
+        assert_eq!(reader.read_f64_finite().unwrap(), -2.25);
+    }
+
+    #[test]
+    fn read_non_finite_floats() {
+        let mut data = 0u32.to_be_bytes().to_vec();
+        data.extend(f32::INFINITY.to_be_bytes());
+        data.extend(f64::NAN.to_be_bytes());
+        let mut cursor = Cursor::new(data);
+        let mut reader = cursor.be();
+        let _ = reader.read_u32();
+        let err = reader.read_f32_finite().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "non-finite f32 value inf at offset 4");
+        assert!(reader.read_f64_finite().is_err());
+    }
+
+    #[test]
+    fn read_canonical_nan() {
+        let payload_nan = f32::from_bits(0x7fc0_1234);
+        let mut data = payload_nan.to_le_bytes().to_vec();
+        data.extend(f64::NEG_INFINITY.to_le_bytes());
+        let mut cursor = Cursor::new(data);
+        let mut reader = cursor.le();
+        assert_eq!(
+            reader.read_f32_canonical().unwrap().to_bits(),
+            f32::NAN.to_bits()
+        );
+        assert_eq!(reader.read_f64_canonical().unwrap(), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn bf16_conversions() {
+        assert_eq!(Bf16::from_f32(1.0).to_bits(), 0x3f80);
+        assert_eq!(Bf16::from_bits(0xc040).to_f32(), -3.0);
+        assert_eq!(
+            Bf16::from_f32(f32::from_bits(0x3f80_8000)).to_bits(),
+            0x3f80
+        );
+        assert_eq!(
+            Bf16::from_f32(f32::from_bits(0x3f81_8000)).to_bits(),
+            0x3f82
+        );
+        assert!(Bf16::from_f32(f32::NAN).to_f32().is_nan());
+        assert_eq!(Bf16::from_f32(f32::INFINITY).to_f32(), f32::INFINITY);
+    }
+
+    #[test]
+    fn read_write_bf16() {
+        let mut cursor = Cursor::new(vec![0u8; 4]);
+        let mut stream = Stream::new(&mut cursor).be();
+        stream.write_bf16(Bf16::from_bits(0x3fc0)).unwrap();
+        stream.write_bf16_from_f32(-2.0).unwrap();
+        let _ = stream.seek(SeekFrom::Start(0));
+        assert_eq!(stream.read_bf16_as_f32().unwrap(), 1.5);
+        assert_eq!(stream.read_bf16().unwrap(), Bf16::from_f32(-2.0));
+        assert_eq!(cursor.get_ref(), &[0x3f, 0xc0, 0xc0, 0x00]);
+    }
+}
@@ -0,0 +1,588 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+use crate::{
+    crc::{Crc, CrcParams},
+    read_num::Endianness,
+    tlv::FieldEncoding,
+    Stream,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BadFrame {
+    Fail,
+    Skip,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CrcTrailer {
+    params: CrcParams,
+    endianness: Endianness,
+}
+
+impl CrcTrailer {
+    fn read<R: Read + ?Sized>(&self, reader: &mut R) -> Result<u64> {
+        let len = self.params.byte_len();
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf[..len])?;
+        let value = match self.endianness {
+            Endianness::Big => buf[..len]
+                .iter()
+                .fold(0, |value, byte| value << 8 | *byte as u64),
+            Endianness::Little => buf[..len]
+                .iter()
+                .rev()
+                .fold(0, |value, byte| value << 8 | *byte as u64),
+        };
+        Ok(value)
+    }
+
+    fn write<W: Write + ?Sized>(&self, writer: &mut W, value: u32) -> Result<()> {
+        let len = self.params.byte_len();
+        match self.endianness {
+            Endianness::Big => writer.write_all(&value.to_be_bytes()[4 - len..]),
+            Endianness::Little => writer.write_all(&value.to_le_bytes()[..len]),
+        }
+    }
+
+    fn check(&self, header: &[u8], payload: &[u8], stored: u64) -> Result<()> {
+        let mut crc = Crc::new(self.params);
+        crc.update(header);
+        crc.update(payload);
+        let computed = crc.finish() as u64;
+        if computed != stored {
+            return Err(checksum_mismatch(stored, computed));
+        }
+        Ok(())
+    }
+}
+
+fn checksum_mismatch(stored: u64, computed: u64) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "frame checksum mismatch: stored {:#x}, computed {:#x}",
+            stored, computed
+        ),
+    )
+}
+
+struct Tee<'r, R: ?Sized> {
+    inner: &'r mut R,
+    bytes: Vec<u8>,
+}
+
+impl<R> Read for Tee<'_, R>
+where
+    R: Read + ?Sized,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameFormat {
+    length: FieldEncoding,
+    max_len: u64,
+    includes_header: bool,
+    crc: Option<CrcTrailer>,
+    bad_frames: BadFrame,
+}
+
+impl FrameFormat {
+    pub fn new(length: FieldEncoding) -> FrameFormat {
+        FrameFormat {
+            length,
+            max_len: u64::MAX,
+            includes_header: false,
+            crc: None,
+            bad_frames: BadFrame::Fail,
+        }
+    }
+
+    pub fn crc_trailer(mut self, params: CrcParams, endianness: Endianness) -> FrameFormat {
+        self.crc = Some(CrcTrailer { params, endianness });
+        self
+    }
+
+    pub fn bad_frames(mut self, bad_frames: BadFrame) -> FrameFormat {
+        self.bad_frames = bad_frames;
+        self
+    }
+
+    fn trailer_len(&self) -> u64 {
+        self.crc.map_or(0, |crc| crc.params.byte_len() as u64)
+    }
+
+    fn read_header<R: Read + ?Sized>(&self, reader: &mut R) -> Result<(u64, Vec<u8>)> {
+        let mut tee = Tee {
+            inner: reader,
+            bytes: Vec::new(),
+        };
+        let len = self.length.read(&mut tee)?;
+        Ok((self.payload_len(len)?, tee.bytes))
+    }
+
+    pub fn max_len(mut self, max_len: u64) -> FrameFormat {
+        self.max_len = max_len;
+        self
+    }
+
+    pub fn includes_header(mut self, includes_header: bool) -> FrameFormat {
+        self.includes_header = includes_header;
+        self
+    }
+
+    fn payload_len(&self, len: u64) -> Result<u64> {
+        let payload_len = if self.includes_header {
+            let header_len = self.length.encoded_len(len) as u64;
+            len.checked_sub(header_len).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "frame length {} is shorter than its {}-byte header",
+                        len, header_len
+                    ),
+                )
+            })?
+        } else {
+            len
+        };
+        if payload_len > self.max_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "frame of {} bytes exceeds the maximum of {}",
+                    payload_len, self.max_len
+                ),
+            ));
+        }
+        Ok(payload_len)
+    }
+
+    fn length_value(&self, payload_len: u64) -> Result<u64> {
+        if payload_len > self.max_len {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "frame of {} bytes exceeds the maximum of {}",
+                    payload_len, self.max_len
+                ),
+            ));
+        }
+        if !self.includes_header {
+            return Ok(payload_len);
+        }
+        let mut header_len = self.length.encoded_len(payload_len) as u64;
+        while self.length.encoded_len(payload_len + header_len) as u64 != header_len {
+            header_len = self.length.encoded_len(payload_len + header_len) as u64;
+        }
+        Ok(payload_len + header_len)
+    }
+
+    pub fn read_frame_with<T, E, F, R>(&self, stream: &mut Stream<'_, T, E>, f: F) -> Result<R>
+    where
+        T: Read + Seek,
+        F: FnOnce(&mut Stream<'_, T, E>) -> Result<R>,
+    {
+        loop {
+            let (len, header) = self.read_header(stream)?;
+            let remainder_len = stream.remainder_len()?;
+            if len.saturating_add(self.trailer_len()) > remainder_len {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "frame of {} bytes exceeds the remaining {} bytes",
+                        len, remainder_len
+                    ),
+                ));
+            }
+            if let Some(crc) = self.crc {
+                let data_pos = stream.stream_position()?;
+                let mut payload = Vec::new();
+                stream.borrow_chunk(Some(len))?.read_to_end(&mut payload)?;
+                let stored = crc.read(stream)?;
+                if let Err(e) = crc.check(&header, &payload, stored) {
+                    match self.bad_frames {
+                        BadFrame::Fail => return Err(e),
+                        BadFrame::Skip if stream.remainder_len()? == 0 => {
+                            return Err(Error::new(
+                                ErrorKind::UnexpectedEof,
+                                "no intact frame before the end of the stream",
+                            ))
+                        }
+                        BadFrame::Skip => continue,
+                    }
+                }
+                stream.seek(SeekFrom::Start(data_pos))?;
+            }
+            let mut chunk = stream.borrow_chunk(Some(len))?;
+            let result = f(&mut chunk)?;
+            chunk.seek(SeekFrom::End(0))?;
+            stream.seek(SeekFrom::Current(self.trailer_len() as i64))?;
+            return Ok(result);
+        }
+    }
+}
+
+pub trait FrameCodec {
+    fn decode(&mut self, buf: &mut Vec<u8>) -> Result<Option<Vec<u8>>>;
+    fn encode(&mut self, frame: &[u8], dst: &mut Vec<u8>) -> Result<()>;
+}
+
+impl FrameCodec for FrameFormat {
+    fn decode(&mut self, buf: &mut Vec<u8>) -> Result<Option<Vec<u8>>> {
+        loop {
+            let mut header = &buf[..];
+            let len = match self.read_header(&mut header) {
+                Ok((len, _)) => len,
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+                Err(e) => return Err(e),
+            };
+            let frame_len = ((buf.len() - header.len()) as u64)
+                .saturating_add(len)
+                .saturating_add(self.trailer_len());
+            if frame_len > buf.len() as u64 {
+                return Ok(None);
+            }
+            let frame_len = frame_len as usize;
+            let frame = FrameReader::new(&buf[..frame_len], *self).read_frame()?;
+            buf.drain(..frame_len);
+            if frame.is_some() {
+                return Ok(frame);
+            }
+        }
+    }
+
+    fn encode(&mut self, frame: &[u8], dst: &mut Vec<u8>) -> Result<()> {
+        FrameWriter::new(dst, *self).write_frame(frame)
+    }
+}
+
+pub struct FrameReader<R>
+where
+    R: Read,
+{
+    inner: R,
+    format: FrameFormat,
+    skipped_frames: u64,
+}
+
+impl<R> FrameReader<R>
+where
+    R: Read,
+{
+    pub fn new(inner: R, format: FrameFormat) -> FrameReader<R> {
+        FrameReader {
+            inner,
+            format,
+            skipped_frames: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    pub fn skipped_frames(&self) -> u64 {
+        self.skipped_frames
+    }
+
+    pub fn read_frame(&mut self) -> Result<Option<Vec<u8>>> {
+        loop {
+            let mut first = [0u8; 1];
+            loop {
+                match self.inner.read(&mut first) {
+                    Ok(0) => return Ok(None),
+                    Ok(_) => break,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+            let (len, header) = self
+                .format
+                .read_header(&mut (&first[..]).chain(&mut self.inner))?;
+            let mut frame = Vec::new();
+            (&mut self.inner).take(len).read_to_end(&mut frame)?;
+            if (frame.len() as u64) < len {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!("frame truncated after {} of {} bytes", frame.len(), len),
+                ));
+            }
+            if let Some(crc) = self.format.crc {
+                let stored = crc.read(&mut self.inner)?;
+                if let Err(e) = crc.check(&header, &frame, stored) {
+                    match self.format.bad_frames {
+                        BadFrame::Fail => return Err(e),
+                        BadFrame::Skip => {
+                            self.skipped_frames += 1;
+                            continue;
+                        }
+                    }
+                }
+            }
+            return Ok(Some(frame));
+        }
+    }
+}
+
+impl<R> Iterator for FrameReader<R>
+where
+    R: Read,
+{
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Result<Vec<u8>>> {
+        self.read_frame().transpose()
+    }
+}
+
+pub struct FrameWriter<W>
+where
+    W: Write,
+{
+    inner: W,
+    format: FrameFormat,
+}
+
+impl<W> FrameWriter<W>
+where
+    W: Write,
+{
+    pub fn new(inner: W, format: FrameFormat) -> FrameWriter<W> {
+        FrameWriter { inner, format }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    pub fn write_frame(&mut self, frame: &[u8]) -> Result<()> {
+        let len = self.format.length_value(frame.len() as u64)?;
+        let mut header = Vec::new();
+        self.format.length.write(&mut header, len)?;
+        self.inner.write_all(&header)?;
+        self.inner.write_all(frame)?;
+        if let Some(crc) = self.format.crc {
+            let mut computed = Crc::new(crc.params);
+            computed.update(&header);
+            computed.update(frame);
+            crc.write(&mut self.inner, computed.finish())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::read_num::ReadNum;
+
+    #[test]
+    fn frames_round_trip() {
+        let format = FrameFormat::new(FieldEncoding::U16(Endianness::Big));
+        let mut writer = FrameWriter::new(Vec::new(), format);
+        writer.write_frame(b"hello").unwrap();
+        writer.write_frame(b"").unwrap();
+        writer.write_frame(b"world!").unwrap();
+        let data = writer.into_inner();
+        assert_eq!(&data[..7], b"\x00\x05hello");
+        let reader = FrameReader::new(Cursor::new(data), format);
+        let frames: Vec<Vec<u8>> = reader.collect::<Result<_>>().unwrap();
+        assert_eq!(frames, [b"hello".to_vec(), vec![], b"world!".to_vec()]);
+    }
+
+    #[test]
+    fn length_including_header() {
+        let format = FrameFormat::new(FieldEncoding::U32(Endianness::Little)).includes_header(true);
+        let mut writer = FrameWriter::new(Vec::new(), format);
+        writer.write_frame(b"abc").unwrap();
+        assert_eq!(writer.get_ref(), b"\x07\x00\x00\x00abc");
+        let mut reader = FrameReader::new(Cursor::new(writer.into_inner()), format);
+        assert_eq!(reader.read_frame().unwrap().unwrap(), b"abc");
+        assert_eq!(reader.read_frame().unwrap(), None);
+        let mut reader = FrameReader::new(Cursor::new(b"\x03\x00\x00\x00"), format);
+        let result = reader.read_frame();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn varint_length_including_header() {
+        let format = FrameFormat::new(FieldEncoding::Varint).includes_header(true);
+        let mut writer = FrameWriter::new(Vec::new(), format);
+        writer.write_frame(&[0xaa; 126]).unwrap();
+        writer.write_frame(&[0xbb; 127]).unwrap();
+        let data = writer.into_inner();
+        assert_eq!(data[0], 127);
+        assert_eq!(&data[127..129], [0x81, 0x01]);
+        let mut reader = FrameReader::new(Cursor::new(data), format);
+        assert_eq!(reader.read_frame().unwrap().unwrap().len(), 126);
+        assert_eq!(reader.read_frame().unwrap().unwrap().len(), 127);
+    }
+
+    #[test]
+    fn max_frame_len() {
+        let format = FrameFormat::new(FieldEncoding::U8).max_len(4);
+        let result = FrameWriter::new(Vec::new(), format).write_frame(b"hello");
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+        let mut reader = FrameReader::new(Cursor::new(b"\x05hello"), format);
+        let result = reader.read_frame();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn truncated_frame() {
+        let format = FrameFormat::new(FieldEncoding::U8);
+        let mut reader = FrameReader::new(Cursor::new(b"\x05hel"), format);
+        let result = reader.read_frame();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        let mut reader = FrameReader::new(
+            Cursor::new(b"\x00"),
+            FrameFormat::new(FieldEncoding::U16(Endianness::Big)),
+        );
+        let result = reader.read_frame();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_frame_as_chunk() {
+        let mut cursor = Cursor::new(b"\x00\x04\x12\x34\x56\x78\x00\x01\xff".to_vec());
+        let mut stream = Stream::new(&mut cursor).be();
+        let format = FrameFormat::new(FieldEncoding::U16(Endianness::Big));
+        let value = format
+            .read_frame_with(&mut stream, |frame| frame.read_u16())
+            .unwrap();
+        assert_eq!(value, 0x1234);
+        let len = format
+            .read_frame_with(&mut stream, |frame| frame.remainder_len())
+            .unwrap();
+        assert_eq!(len, 1);
+        assert_eq!(stream.remainder_len().unwrap(), 0);
+    }
+
+    fn crc_format() -> FrameFormat {
+        FrameFormat::new(FieldEncoding::U8).crc_trailer(CrcParams::CRC16_MODBUS, Endianness::Little)
+    }
+
+    fn crc_frames() -> Vec<u8> {
+        let mut writer = FrameWriter::new(Vec::new(), crc_format());
+        writer.write_frame(b"\x01\x03").unwrap();
+        writer.write_frame(b"\x02").unwrap();
+        writer.write_frame(b"\x03\x04").unwrap();
+        writer.into_inner()
+    }
+
+    #[test]
+    fn crc_trailer_round_trip() {
+        let data = crc_frames();
+        let crc = crate::crc::crc(CrcParams::CRC16_MODBUS, b"\x02\x01\x03");
+        assert_eq!(&data[..5], [0x02, 0x01, 0x03, crc as u8, (crc >> 8) as u8]);
+        let reader = FrameReader::new(Cursor::new(data), crc_format());
+        let frames: Vec<Vec<u8>> = reader.collect::<Result<_>>().unwrap();
+        assert_eq!(frames, [vec![1, 3], vec![2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn crc24_trailer_round_trip() {
+        for endianness in [Endianness::Big, Endianness::Little] {
+            let format = FrameFormat::new(FieldEncoding::U8)
+                .crc_trailer(CrcParams::CRC24_OPENPGP, endianness);
+            let mut writer = FrameWriter::new(Vec::new(), format);
+            writer.write_frame(b"abc").unwrap();
+            writer.write_frame(b"de").unwrap();
+            let data = writer.into_inner();
+            assert_eq!(data.len(), 2 * (1 + 3) + 5);
+            let crc = crate::crc::crc(CrcParams::CRC24_OPENPGP, b"\x03abc");
+            let trailer = match endianness {
+                Endianness::Big => crc.to_be_bytes()[1..].to_vec(),
+                Endianness::Little => crc.to_le_bytes()[..3].to_vec(),
+            };
+            assert_eq!(data[4..7], trailer);
+            let reader = FrameReader::new(Cursor::new(data.clone()), format);
+            let frames: Vec<Vec<u8>> = reader.collect::<Result<_>>().unwrap();
+            assert_eq!(frames, [b"abc".to_vec(), b"de".to_vec()]);
+            let mut stream_data = Cursor::new(data);
+            let mut stream = Stream::new(&mut stream_data);
+            let first = format
+                .read_frame_with(&mut stream, |frame| frame.remainder_len())
+                .unwrap();
+            assert_eq!(first, 3);
+            assert_eq!(stream.stream_position().unwrap(), 7);
+        }
+    }
+
+    #[test]
+    fn crc_trailer_mismatch() {
+        let mut data = crc_frames();
+        data[6] ^= 0xff;
+        let mut reader = FrameReader::new(Cursor::new(data.clone()), crc_format());
+        assert_eq!(reader.read_frame().unwrap().unwrap(), [1, 3]);
+        let err = reader.read_frame().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("frame checksum mismatch"));
+        let format = crc_format().bad_frames(BadFrame::Skip);
+        let mut reader = FrameReader::new(Cursor::new(data), format);
+        let frames: Vec<Vec<u8>> = (&mut reader).collect::<Result<_>>().unwrap();
+        assert_eq!(frames, [vec![1, 3], vec![3, 4]]);
+        assert_eq!(reader.skipped_frames(), 1);
+    }
+
+    #[test]
+    fn frame_codec_partial_input() {
+        let mut data = crc_frames();
+        data[6] ^= 0xff;
+        let mut format = crc_format().bad_frames(BadFrame::Skip);
+        let mut buf = data[..3].to_vec();
+        assert_eq!(format.decode(&mut buf).unwrap(), None);
+        buf.extend_from_slice(&data[3..8]);
+        assert_eq!(format.decode(&mut buf).unwrap(), Some(vec![1, 3]));
+        assert_eq!(format.decode(&mut buf).unwrap(), None);
+        assert_eq!(buf, data[5..8]);
+        buf.extend_from_slice(&data[8..]);
+        assert_eq!(format.decode(&mut buf).unwrap(), Some(vec![3, 4]));
+        assert!(buf.is_empty());
+        let mut encoded = Vec::new();
+        format.encode(b"\x02", &mut encoded).unwrap();
+        assert_eq!(encoded, crc_frames()[5..9]);
+    }
+
+    #[test]
+    fn crc_trailer_with_chunks() {
+        let mut data = crc_frames();
+        data[6] ^= 0xff;
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let format = crc_format().bad_frames(BadFrame::Skip);
+        let first = format
+            .read_frame_with(&mut stream, |f| f.read_u8())
+            .unwrap();
+        let second = format
+            .read_frame_with(&mut stream, |f| f.read_u8())
+            .unwrap();
+        assert_eq!((first, second), (1, 3));
+        assert_eq!(stream.remainder_len().unwrap(), 0);
+        let result = format.read_frame_with(&mut stream, |f| f.read_u8());
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}
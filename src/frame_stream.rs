@@ -0,0 +1,282 @@
+use std::{
+    io::{Error, ErrorKind, Result},
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use futures_core::Stream;
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_sink::Sink;
+
+use crate::frame::FrameCodec;
+
+const READ_SIZE: usize = 8 * 1024;
+
+pub struct FrameStream<R, C> {
+    inner: R,
+    codec: C,
+    buf: Vec<u8>,
+    eof: bool,
+    done: bool,
+}
+
+impl<R, C> FrameStream<R, C>
+where
+    R: AsyncRead + Unpin,
+    C: FrameCodec + Unpin,
+{
+    pub fn new(inner: R, codec: C) -> FrameStream<R, C> {
+        FrameStream {
+            inner,
+            codec,
+            buf: Vec::new(),
+            eof: false,
+            done: false,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn buffered(&self) -> &[u8] {
+        &self.buf
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn poll_frame(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Vec<u8>>>> {
+        loop {
+            if let Some(frame) = self.codec.decode(&mut self.buf)? {
+                return Poll::Ready(Ok(Some(frame)));
+            }
+            if self.eof {
+                if self.buf.is_empty() {
+                    return Poll::Ready(Ok(None));
+                }
+                return Poll::Ready(Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!(
+                        "{} trailing bytes do not form a complete frame",
+                        self.buf.len()
+                    ),
+                )));
+            }
+            let len = self.buf.len();
+            self.buf.resize(len + READ_SIZE, 0);
+            let result = Pin::new(&mut self.inner).poll_read(cx, &mut self.buf[len..]);
+            let read = match result {
+                Poll::Ready(Ok(read)) => read,
+                _ => 0,
+            };
+            self.buf.truncate(len + read);
+            match result {
+                Poll::Ready(Ok(0)) => self.eof = true,
+                Poll::Ready(Ok(_)) => {}
+                Poll::Ready(Err(e)) if e.kind() == ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<R, C> Stream for FrameStream<R, C>
+where
+    R: AsyncRead + Unpin,
+    C: FrameCodec + Unpin,
+{
+    type Item = Result<Vec<u8>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Vec<u8>>>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        let result = ready!(this.poll_frame(cx)).transpose();
+        if !matches!(result, Some(Ok(_))) {
+            this.done = true;
+        }
+        Poll::Ready(result)
+    }
+}
+
+pub struct FrameSink<W, C> {
+    inner: W,
+    codec: C,
+    buf: Vec<u8>,
+    written: usize,
+}
+
+impl<W, C> FrameSink<W, C>
+where
+    W: AsyncWrite + Unpin,
+    C: FrameCodec + Unpin,
+{
+    pub fn new(inner: W, codec: C) -> FrameSink<W, C> {
+        FrameSink {
+            inner,
+            codec,
+            buf: Vec::new(),
+            written: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn poll_write_buf(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        while self.written < self.buf.len() {
+            let len = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.buf[self.written..]))?;
+            if len == 0 {
+                return Poll::Ready(Err(Error::new(
+                    ErrorKind::WriteZero,
+                    "failed to write the buffered frames",
+                )));
+            }
+            self.written += len;
+        }
+        self.buf.clear();
+        self.written = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W, C> Sink<Vec<u8>> for FrameSink<W, C>
+where
+    W: AsyncWrite + Unpin,
+    C: FrameCodec + Unpin,
+{
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        if this.buf.len() >= READ_SIZE {
+            ready!(this.poll_write_buf(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Vec<u8>) -> Result<()> {
+        let this = self.get_mut();
+        let len = this.buf.len();
+        let result = this.codec.encode(&item, &mut this.buf);
+        if result.is_err() {
+            this.buf.truncate(len);
+        }
+        result
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buf(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buf(cx))?;
+        Pin::new(&mut this.inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{executor::block_on, io::Cursor, SinkExt, StreamExt, TryStreamExt};
+
+    use super::*;
+    use crate::{
+        cobs::CobsCodec,
+        crc::CrcParams,
+        frame::{BadFrame, FrameFormat},
+        read_num::Endianness,
+        tlv::FieldEncoding,
+    };
+
+    fn crc_format() -> FrameFormat {
+        FrameFormat::new(FieldEncoding::U16(Endianness::Big))
+            .crc_trailer(CrcParams::CRC16_MODBUS, Endianness::Little)
+    }
+
+    #[test]
+    fn frame_sink_and_stream_round_trip() {
+        block_on(async {
+            let mut sink = FrameSink::new(Cursor::new(Vec::new()), crc_format());
+            sink.send(b"hello".to_vec()).await.unwrap();
+            sink.send(Vec::new()).await.unwrap();
+            sink.send(vec![0xab; 20_000]).await.unwrap();
+            sink.close().await.unwrap();
+            let data = sink.into_inner().into_inner();
+            let frames = FrameStream::new(&data[..], crc_format())
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            assert_eq!(frames.len(), 3);
+            assert_eq!(frames[0], b"hello");
+            assert!(frames[1].is_empty());
+            assert_eq!(frames[2], vec![0xab; 20_000]);
+        });
+    }
+
+    #[test]
+    fn cobs_frame_stream() {
+        block_on(async {
+            let mut sink = FrameSink::new(Vec::new(), CobsCodec::new());
+            sink.send(vec![0x00, 0x11]).await.unwrap();
+            sink.send(vec![0x22]).await.unwrap();
+            sink.flush().await.unwrap();
+            let data = sink.into_inner();
+            assert_eq!(data, [0x01, 0x02, 0x11, 0x00, 0x02, 0x22, 0x00]);
+            let mut stream = FrameStream::new(&data[..], CobsCodec::new());
+            assert_eq!(stream.next().await.unwrap().unwrap(), [0x00, 0x11]);
+            assert_eq!(stream.next().await.unwrap().unwrap(), [0x22]);
+            assert!(stream.next().await.is_none());
+        });
+    }
+
+    #[test]
+    fn frame_stream_errors() {
+        block_on(async {
+            let mut data = Vec::new();
+            crc_format().encode(b"abc", &mut data).unwrap();
+            crc_format().encode(b"def", &mut data).unwrap();
+            data[3] ^= 0xff;
+            let format = crc_format().bad_frames(BadFrame::Skip);
+            let frames = FrameStream::new(&data[..], format)
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            assert_eq!(frames, [b"def"]);
+
+            let mut stream = FrameStream::new(&data[..], crc_format());
+            let err = stream.next().await.unwrap().unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+            assert!(stream.next().await.is_none());
+
+            let mut stream = FrameStream::new(&b"\x02\x11"[..], CobsCodec::new());
+            let err = stream.next().await.unwrap().unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+            let mut sink = FrameSink::new(Vec::new(), FrameFormat::new(FieldEncoding::U8));
+            let err = sink.send(vec![0u8; 300]).await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+            sink.send(vec![1u8]).await.unwrap();
+            assert_eq!(sink.into_inner(), [1, 1]);
+        });
+    }
+}
@@ -0,0 +1,77 @@
+use std::io::{Read, Result, Seek};
+
+use zerocopy::{FromBytes, IntoBytes};
+
+use crate::Stream;
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn read_into_frombytes<F>(&mut self, dst: &mut F) -> Result<()>
+    where
+        F: FromBytes + IntoBytes + ?Sized,
+    {
+        self.read_exact(dst.as_mut_bytes())
+    }
+
+    pub fn read_frombytes<F>(&mut self) -> Result<F>
+    where
+        F: FromBytes + IntoBytes,
+    {
+        let mut value = F::new_zeroed();
+        self.read_into_frombytes(&mut value)?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use zerocopy::{
+        byteorder::{BigEndian, LittleEndian, U16, U32},
+        FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    };
+
+    use super::*;
+
+    #[derive(FromBytes, IntoBytes, KnownLayout, Immutable, Unaligned, Debug, PartialEq)]
+    #[repr(C)]
+    struct Header {
+        magic: [u8; 2],
+        len: U16<BigEndian>,
+        offset: U32<LittleEndian>,
+    }
+
+    #[test]
+    fn read_frombytes_struct() {
+        let data = [b'O', b'K', 0x01, 0x02, 0x04, 0x03, 0x02, 0x01];
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let header: Header = stream.read_frombytes().unwrap();
+        assert_eq!(header.magic, *b"OK");
+        assert_eq!(header.len.get(), 0x0102);
+        assert_eq!(header.offset.get(), 0x01020304);
+    }
+
+    #[test]
+    fn read_into_frombytes_slice() {
+        let data = [0x00, 0x01, 0x00, 0x02, 0x00, 0x03];
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let mut values = [U16::<BigEndian>::ZERO; 3];
+        stream.read_into_frombytes(&mut values[..]).unwrap();
+        assert_eq!(values.map(|v| v.get()), [1, 2, 3]);
+    }
+
+    #[test]
+    fn read_frombytes_over_limit() {
+        let data = [0u8; 10];
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let mut chunk = stream.borrow_chunk(Some(7)).unwrap();
+        let result = chunk.read_frombytes::<Header>();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}
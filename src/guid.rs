@@ -0,0 +1,70 @@
+use std::io::{Read, Result};
+
+pub fn guid_to_uuid_bytes(guid: [u8; 16]) -> [u8; 16] {
+    let mut bytes = guid;
+    bytes[..4].reverse();
+    bytes[4..6].reverse();
+    bytes[6..8].reverse();
+    bytes
+}
+
+pub trait ReadGuid: Read {
+    fn read_uuid_be(&mut self) -> Result<[u8; 16]> {
+        let mut buf = [0u8; 16];
+        self.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn read_guid_le(&mut self) -> Result<[u8; 16]> {
+        Ok(guid_to_uuid_bytes(self.read_uuid_be()?))
+    }
+
+    #[cfg(feature = "uuid")]
+    fn read_uuid(&mut self) -> Result<uuid::Uuid> {
+        Ok(uuid::Uuid::from_bytes(self.read_uuid_be()?))
+    }
+
+    #[cfg(feature = "uuid")]
+    fn read_guid(&mut self) -> Result<uuid::Uuid> {
+        Ok(uuid::Uuid::from_bytes(self.read_guid_le()?))
+    }
+}
+
+impl<R> ReadGuid for R where R: Read + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const UUID: [u8; 16] = [
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee,
+        0xff,
+    ];
+
+    const GUID: [u8; 16] = [
+        0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee,
+        0xff,
+    ];
+
+    #[test]
+    fn read_uuid_be() {
+        let mut cursor = Cursor::new(UUID);
+        assert_eq!(cursor.read_uuid_be().unwrap(), UUID);
+    }
+
+    #[test]
+    fn read_guid_le() {
+        let mut cursor = Cursor::new(GUID);
+        assert_eq!(cursor.read_guid_le().unwrap(), UUID);
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn read_uuid_typed() {
+        let expected = uuid::Uuid::parse_str("00112233-4455-6677-8899-aabbccddeeff").unwrap();
+        assert_eq!(Cursor::new(UUID).read_uuid().unwrap(), expected);
+        assert_eq!(Cursor::new(GUID).read_guid().unwrap(), expected);
+    }
+}
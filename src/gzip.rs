@@ -0,0 +1,319 @@
+use std::io::{self, Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use crate::{
+    checksum::Crc32Reader,
+    crc::Crc32,
+    inflate::{DeflateFormat, InflateReader},
+    Stream,
+};
+
+const FTEXT: u8 = 0x01;
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GzipHeader {
+    pub text: bool,
+    pub mtime: u32,
+    pub extra_flags: u8,
+    pub os: u8,
+    pub extra: Option<Vec<u8>>,
+    pub name: Option<Vec<u8>>,
+    pub comment: Option<Vec<u8>>,
+}
+
+impl GzipHeader {
+    pub fn read<R>(reader: &mut R) -> Result<GzipHeader>
+    where
+        R: Read,
+    {
+        let mut reader = Crc32Reader::new(reader, Crc32::new());
+        let mut fixed = [0u8; 10];
+        reader.read_exact(&mut fixed)?;
+        if fixed[..2] != [0x1f, 0x8b] {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid gzip magic {:02x?}", &fixed[..2]),
+            ));
+        }
+        if fixed[2] != 8 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unsupported gzip compression method {}", fixed[2]),
+            ));
+        }
+        let flags = fixed[3];
+        if flags & 0xe0 != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("reserved gzip flags set in {:#04x}", flags),
+            ));
+        }
+        let mut header = GzipHeader {
+            text: flags & FTEXT != 0,
+            mtime: u32::from_le_bytes(fixed[4..8].try_into().unwrap()),
+            extra_flags: fixed[8],
+            os: fixed[9],
+            ..GzipHeader::default()
+        };
+        if flags & FEXTRA != 0 {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            let mut extra = vec![0u8; u16::from_le_bytes(len) as usize];
+            reader.read_exact(&mut extra)?;
+            header.extra = Some(extra);
+        }
+        if flags & FNAME != 0 {
+            header.name = Some(read_zero_terminated(&mut reader)?);
+        }
+        if flags & FCOMMENT != 0 {
+            header.comment = Some(read_zero_terminated(&mut reader)?);
+        }
+        if flags & FHCRC != 0 {
+            let expected = reader.digest() as u16;
+            let mut crc = [0u8; 2];
+            reader.read_exact(&mut crc)?;
+            let found = u16::from_le_bytes(crc);
+            if found != expected {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "gzip header crc {:#06x} does not match computed {:#06x}",
+                        found, expected
+                    ),
+                ));
+            }
+        }
+        Ok(header)
+    }
+}
+
+fn read_zero_terminated<R>(reader: &mut R) -> Result<Vec<u8>>
+where
+    R: Read,
+{
+    let mut field = Vec::new();
+    let mut byte = [0u8; 1];
+    loop {
+        reader.read_exact(&mut byte)?;
+        if byte[0] == 0 {
+            return Ok(field);
+        }
+        field.push(byte[0]);
+    }
+}
+
+pub struct GzipMembers<'a, T, E>
+where
+    T: Seek,
+{
+    chunk: Stream<'a, T, E>,
+    unfinished: Option<u64>,
+}
+
+impl<T, E> GzipMembers<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn next_member(&mut self) -> Result<Option<GzipMember<'_, T, E>>> {
+        if let Some(offset) = self.unfinished.take() {
+            self.chunk.seek(SeekFrom::Start(offset))?;
+            let mut member = self.open_member(offset)?;
+            io::copy(&mut member, &mut io::sink())?;
+        }
+        if self.chunk.remainder_len()? == 0 {
+            return Ok(None);
+        }
+        let offset = self.chunk.stream_position()?;
+        self.open_member(offset).map(Some)
+    }
+
+    fn open_member(&mut self, offset: u64) -> Result<GzipMember<'_, T, E>> {
+        let header = GzipHeader::read(&mut self.chunk)?;
+        self.unfinished = Some(offset);
+        Ok(GzipMember {
+            offset,
+            header,
+            inflate: self.chunk.inflate(DeflateFormat::Raw)?.allow_trailing(true),
+            crc: Crc32::new(),
+            len: 0,
+            unfinished: &mut self.unfinished,
+        })
+    }
+}
+
+pub struct GzipMember<'m, T, E>
+where
+    T: Seek,
+{
+    offset: u64,
+    header: GzipHeader,
+    inflate: InflateReader<'m, T, E>,
+    crc: Crc32,
+    len: u32,
+    unfinished: &'m mut Option<u64>,
+}
+
+impl<T, E> GzipMember<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn header(&self) -> &GzipHeader {
+        &self.header
+    }
+
+    fn read_trailer(&mut self) -> Result<()> {
+        let mut trailer = [0u8; 8];
+        self.inflate.get_mut().read_exact(&mut trailer)?;
+        let crc = u32::from_le_bytes(trailer[..4].try_into().unwrap());
+        let len = u32::from_le_bytes(trailer[4..].try_into().unwrap());
+        if crc != self.crc.finish() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "gzip member at offset {} has crc {:#010x}, computed {:#010x}",
+                    self.offset,
+                    crc,
+                    self.crc.finish()
+                ),
+            ));
+        }
+        if len != self.len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "gzip member at offset {} records {} bytes, inflated {}",
+                    self.offset, len, self.len
+                ),
+            ));
+        }
+        *self.unfinished = None;
+        Ok(())
+    }
+}
+
+impl<T, E> Read for GzipMember<'_, T, E>
+where
+    T: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.unfinished.is_none() {
+            return Ok(0);
+        }
+        let len = self.inflate.read(buf)?;
+        self.crc.update(&buf[..len]);
+        self.len = self.len.wrapping_add(len as u32);
+        if len == 0 && !buf.is_empty() {
+            self.read_trailer()?;
+        }
+        Ok(len)
+    }
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn gzip_members(&mut self) -> Result<GzipMembers<'_, T, E>> {
+        let chunk_len = self.remainder_len()?;
+        Ok(GzipMembers {
+            chunk: self.borrow_chunk(Some(chunk_len))?,
+            unfinished: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use flate2::{write::GzEncoder, Compression, GzBuilder};
+
+    use super::*;
+
+    fn member(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn archive() -> Vec<u8> {
+        let mut data = member(b"first line\n");
+        let mut encoder = GzBuilder::new()
+            .filename("log.txt")
+            .comment("rotated")
+            .extra(b"ab".to_vec())
+            .mtime(1_700_000_000)
+            .write(Vec::new(), Compression::fast());
+        encoder.write_all(b"second line\n").unwrap();
+        data.extend(encoder.finish().unwrap());
+        data.extend(member(b"third line\n"));
+        data
+    }
+
+    #[test]
+    fn gzip_members() {
+        let mut cursor = Cursor::new(archive());
+        let mut stream = Stream::new(&mut cursor);
+        let mut members = stream.gzip_members().unwrap();
+        let mut lines = Vec::new();
+        while let Some(mut member) = members.next_member().unwrap() {
+            let mut line = String::new();
+            member.read_to_string(&mut line).unwrap();
+            lines.push((member.offset(), member.header().clone(), line));
+        }
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].0, 0);
+        assert_eq!(lines[1].2, "second line\n");
+        assert_eq!(lines[1].1.name.as_deref(), Some(&b"log.txt"[..]));
+        assert_eq!(lines[1].1.comment.as_deref(), Some(&b"rotated"[..]));
+        assert_eq!(lines[1].1.extra.as_deref(), Some(&b"ab"[..]));
+        assert_eq!(lines[1].1.mtime, 1_700_000_000);
+        assert_eq!(lines[2].2, "third line\n");
+    }
+
+    #[test]
+    fn gzip_skips_unread_members() {
+        let mut cursor = Cursor::new(archive());
+        let mut stream = Stream::new(&mut cursor);
+        let mut members = stream.gzip_members().unwrap();
+        let mut first = members.next_member().unwrap().unwrap();
+        first.read_exact(&mut [0u8; 3]).unwrap();
+        members.next_member().unwrap().unwrap();
+        let mut third = members.next_member().unwrap().unwrap();
+        let mut line = String::new();
+        third.read_to_string(&mut line).unwrap();
+        assert_eq!(line, "third line\n");
+        assert!(members.next_member().unwrap().is_none());
+    }
+
+    #[test]
+    fn gzip_errors() {
+        let mut data = archive();
+        data.extend_from_slice(b"garbage!!!");
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let mut members = stream.gzip_members().unwrap();
+        for _ in 0..3 {
+            members.next_member().unwrap().unwrap();
+        }
+        let err = members.next_member().err().unwrap();
+        assert_eq!(err.to_string(), "invalid gzip magic [67, 61]");
+
+        let mut data = member(b"payload");
+        let len = data.len();
+        data[len - 8] ^= 1;
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let mut members = stream.gzip_members().unwrap();
+        let mut member = members.next_member().unwrap().unwrap();
+        let err = member.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,233 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexCase {
+    Lower,
+    Upper,
+}
+
+fn hex_digit(symbol: u8) -> Option<u8> {
+    match symbol {
+        b'0'..=b'9' => Some(symbol - b'0'),
+        b'a'..=b'f' => Some(symbol - b'a' + 10),
+        b'A'..=b'F' => Some(symbol - b'A' + 10),
+        _ => None,
+    }
+}
+
+pub struct HexReader<R>
+where
+    R: Read,
+{
+    inner: R,
+    separator: Option<u8>,
+    high: Option<u8>,
+}
+
+impl<R> HexReader<R>
+where
+    R: Read,
+{
+    pub fn new(inner: R) -> HexReader<R> {
+        HexReader {
+            inner,
+            separator: None,
+            high: None,
+        }
+    }
+
+    pub fn with_separator(mut self, separator: u8) -> HexReader<R> {
+        self.separator = Some(separator);
+        self
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Read for HexReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let mut input = vec![0u8; buf.len() * 2];
+        loop {
+            let n = self.inner.read(&mut input)?;
+            if n == 0 {
+                if let Some(high) = self.high {
+                    return Err(Error::new(
+                        ErrorKind::UnexpectedEof,
+                        format!("dangling hex digit {:x}", high),
+                    ));
+                }
+                return Ok(0);
+            }
+            let mut len = 0;
+            for symbol in &input[..n] {
+                if symbol.is_ascii_whitespace() || Some(*symbol) == self.separator {
+                    if self.high.is_some() {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            "separator inside a hex byte",
+                        ));
+                    }
+                    continue;
+                }
+                let digit = hex_digit(*symbol).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("invalid hex digit {:?}", *symbol as char),
+                    )
+                })?;
+                match self.high.take() {
+                    Some(high) => {
+                        buf[len] = high << 4 | digit;
+                        len += 1;
+                    }
+                    None => self.high = Some(digit),
+                }
+            }
+            if len > 0 {
+                return Ok(len);
+            }
+        }
+    }
+}
+
+pub struct HexWriter<W>
+where
+    W: Write,
+{
+    inner: W,
+    case: HexCase,
+    group_len: usize,
+    separator: Vec<u8>,
+    written: u64,
+}
+
+impl<W> HexWriter<W>
+where
+    W: Write,
+{
+    pub fn new(inner: W, case: HexCase) -> HexWriter<W> {
+        HexWriter {
+            inner,
+            case,
+            group_len: 0,
+            separator: Vec::new(),
+            written: 0,
+        }
+    }
+
+    pub fn with_grouping(mut self, group_len: usize, separator: &[u8]) -> HexWriter<W> {
+        self.group_len = group_len;
+        self.separator = separator.to_vec();
+        self
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W> Write for HexWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let digits = match self.case {
+            HexCase::Lower => b"0123456789abcdef",
+            HexCase::Upper => b"0123456789ABCDEF",
+        };
+        let mut out = Vec::with_capacity(buf.len() * (2 + self.separator.len()));
+        for byte in buf {
+            if self.group_len > 0
+                && self.written > 0
+                && self.written.is_multiple_of(self.group_len as u64)
+            {
+                out.extend_from_slice(&self.separator);
+            }
+            out.extend([digits[(byte >> 4) as usize], digits[(byte & 0x0f) as usize]]);
+            self.written += 1;
+        }
+        self.inner.write_all(&out)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::*;
+    use crate::read_num::{ReadEndianExt, ReadNum};
+
+    #[test]
+    fn hex_writer() {
+        let mut writer = HexWriter::new(Vec::new(), HexCase::Lower);
+        writer.write_all(&[0xde, 0xad]).unwrap();
+        writer.write_all(&[0xbe, 0xef]).unwrap();
+        assert_eq!(writer.into_inner(), b"deadbeef");
+        let mut writer = HexWriter::new(Vec::new(), HexCase::Upper).with_grouping(1, b":");
+        writer.write_all(&[0x00, 0x1b]).unwrap();
+        writer.write_all(&[0x63]).unwrap();
+        assert_eq!(writer.into_inner(), b"00:1B:63");
+        let mut writer = HexWriter::new(Vec::new(), HexCase::Lower).with_grouping(2, b" ");
+        writer.write_all(&[1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(writer.into_inner(), b"0102 0304 05");
+    }
+
+    #[test]
+    fn hex_reader_with_numeric_reads() {
+        let inner = BufReader::with_capacity(3, Cursor::new(b"00:1B:63\n84 45 e6".to_vec()));
+        let mut reader = HexReader::new(inner).with_separator(b':');
+        let mut reader = reader.be();
+        assert_eq!(reader.read_u16().unwrap(), 0x001b);
+        assert_eq!(reader.read_u32().unwrap(), 0x6384_45e6);
+        let result = reader.read_u8();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn hex_reader_invalid() {
+        let mut data = Vec::new();
+        let result = HexReader::new(Cursor::new("abc")).read_to_end(&mut data);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        let result = HexReader::new(Cursor::new("0g")).read_to_end(&mut data);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let result = HexReader::new(Cursor::new("0 1")).read_to_end(&mut data);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let data: Vec<u8> = (0..=255).collect();
+        let mut writer = HexWriter::new(Vec::new(), HexCase::Upper).with_grouping(16, b"\n");
+        writer.write_all(&data).unwrap();
+        let mut decoded = Vec::new();
+        HexReader::new(Cursor::new(writer.into_inner()))
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
+}
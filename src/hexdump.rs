@@ -0,0 +1,244 @@
+use std::{
+    fmt,
+    io::{Error, Read, Result, Seek, Write},
+};
+
+use crate::{hex::HexCase, Stream};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HexdumpConfig {
+    width: usize,
+    group: usize,
+    case: HexCase,
+    absolute: bool,
+}
+
+impl HexdumpConfig {
+    pub fn new() -> HexdumpConfig {
+        HexdumpConfig {
+            width: 16,
+            group: 8,
+            case: HexCase::Lower,
+            absolute: false,
+        }
+    }
+
+    pub fn width(mut self, width: usize) -> HexdumpConfig {
+        assert!(width > 0, "hexdump width must not be zero");
+        self.width = width;
+        self
+    }
+
+    pub fn group(mut self, group: usize) -> HexdumpConfig {
+        self.group = group;
+        self
+    }
+
+    pub fn case(mut self, case: HexCase) -> HexdumpConfig {
+        self.case = case;
+        self
+    }
+
+    pub fn absolute_offsets(mut self, absolute: bool) -> HexdumpConfig {
+        self.absolute = absolute;
+        self
+    }
+
+    fn write_row<W: fmt::Write>(&self, out: &mut W, offset: u64, row: &[u8]) -> fmt::Result {
+        write!(out, "{:08x} ", offset)?;
+        for i in 0..self.width {
+            out.write_char(' ')?;
+            if self.group != 0 && i != 0 && i % self.group == 0 {
+                out.write_char(' ')?;
+            }
+            match (row.get(i), self.case) {
+                (Some(byte), HexCase::Lower) => write!(out, "{:02x}", byte)?,
+                (Some(byte), HexCase::Upper) => write!(out, "{:02X}", byte)?,
+                (None, _) => out.write_str("  ")?,
+            }
+        }
+        out.write_str("  |")?;
+        for &byte in row {
+            let symbol = if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            };
+            out.write_char(symbol)?;
+        }
+        out.write_str("|\n")
+    }
+}
+
+impl Default for HexdumpConfig {
+    fn default() -> HexdumpConfig {
+        HexdumpConfig::new()
+    }
+}
+
+pub struct Hexdump<'a> {
+    data: &'a [u8],
+    config: HexdumpConfig,
+    base_offset: u64,
+}
+
+impl<'a> Hexdump<'a> {
+    pub fn new(data: &'a [u8]) -> Hexdump<'a> {
+        Hexdump {
+            data,
+            config: HexdumpConfig::new(),
+            base_offset: 0,
+        }
+    }
+
+    pub fn config(mut self, config: HexdumpConfig) -> Hexdump<'a> {
+        self.config = config;
+        self
+    }
+
+    pub fn base_offset(mut self, base_offset: u64) -> Hexdump<'a> {
+        self.base_offset = base_offset;
+        self
+    }
+
+    pub fn write_to<W: Write>(&self, out: &mut W) -> Result<()> {
+        let mut line = String::new();
+        for (offset, row) in self.rows() {
+            line.clear();
+            self.config
+                .write_row(&mut line, offset, row)
+                .map_err(Error::other)?;
+            out.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn rows(&self) -> impl Iterator<Item = (u64, &'a [u8])> + '_ {
+        let width = self.config.width;
+        self.data
+            .chunks(width)
+            .enumerate()
+            .map(move |(i, row)| (self.base_offset + (i * width) as u64, row))
+    }
+}
+
+impl fmt::Display for Hexdump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (offset, row) in self.rows() {
+            self.config.write_row(f, offset, row)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn hexdump<W: Write>(&mut self, out: &mut W, config: &HexdumpConfig) -> Result<()> {
+        let mut line = String::new();
+        self.hexdump_rows(config, |offset, row| {
+            line.clear();
+            config
+                .write_row(&mut line, offset, row)
+                .map_err(Error::other)?;
+            out.write_all(line.as_bytes())
+        })
+    }
+
+    pub fn hexdump_fmt<W: fmt::Write>(
+        &mut self,
+        out: &mut W,
+        config: &HexdumpConfig,
+    ) -> Result<()> {
+        self.hexdump_rows(config, |offset, row| {
+            config.write_row(out, offset, row).map_err(Error::other)
+        })
+    }
+
+    fn hexdump_rows<F>(&mut self, config: &HexdumpConfig, mut emit: F) -> Result<()>
+    where
+        F: FnMut(u64, &[u8]) -> Result<()>,
+    {
+        let mut offset = match config.absolute {
+            true => self.inner.stream_position()?,
+            false => self.stream_position()?,
+        };
+        let mut row = vec![0u8; config.width];
+        loop {
+            let mut len = 0;
+            while len < row.len() {
+                match self.read(&mut row[len..])? {
+                    0 => break,
+                    n => len += n,
+                }
+            }
+            if len == 0 {
+                return Ok(());
+            }
+            emit(offset, &row[..len])?;
+            offset += len as u64;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, SeekFrom};
+
+    use super::*;
+
+    #[test]
+    fn hexdump_display() {
+        let dump = Hexdump::new(b"Hello, hexdump!\n\x00\x01\xff").to_string();
+        assert_eq!(
+            dump,
+            "00000000  48 65 6c 6c 6f 2c 20 68  65 78 64 75 6d 70 21 0a  |Hello, hexdump!.|\n\
+             00000010  00 01 ff                                          |...|\n"
+        );
+        assert_eq!(Hexdump::new(&[]).to_string(), "");
+    }
+
+    #[test]
+    fn hexdump_config() {
+        let config = HexdumpConfig::new().width(6).group(2).case(HexCase::Upper);
+        let dump = Hexdump::new(b"\xab\xcdefghij")
+            .config(config)
+            .base_offset(0x1000)
+            .to_string();
+        assert_eq!(
+            dump,
+            "00001000  AB CD  65 66  67 68  |..efgh|\n\
+             00001006  69 6A                |ij|\n"
+        );
+        let mut out = Vec::new();
+        Hexdump::new(b"ab")
+            .config(HexdumpConfig::new().group(0).width(4))
+            .write_to(&mut out)
+            .unwrap();
+        assert_eq!(out, b"00000000  61 62        |ab|\n");
+    }
+
+    #[test]
+    fn hexdump_stream_chunk() {
+        let mut cursor = Cursor::new(b"header:0123456789abcdef:trailer".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(7)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(16)).unwrap();
+        let config = HexdumpConfig::new().width(8).group(4);
+        let mut out = Vec::new();
+        chunk.hexdump(&mut out, &config).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "00000000  30 31 32 33  34 35 36 37  |01234567|\n\
+             00000008  38 39 61 62  63 64 65 66  |89abcdef|\n"
+        );
+        chunk.seek(SeekFrom::Start(12)).unwrap();
+        let mut out = String::new();
+        chunk
+            .hexdump_fmt(&mut out, &config.absolute_offsets(true))
+            .unwrap();
+        assert_eq!(out, "00000013  63 64 65 66               |cdef|\n");
+        assert_eq!(chunk.remainder_len().unwrap(), 0);
+    }
+}
@@ -0,0 +1,240 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use flate2::{Decompress, FlushDecompress, Status};
+
+use crate::Stream;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeflateFormat {
+    Raw,
+    Zlib,
+}
+
+pub struct InflateReader<'a, T, E>
+where
+    T: Seek,
+{
+    chunk: Stream<'a, T, E>,
+    chunk_len: u64,
+    decompress: Decompress,
+    input: Vec<u8>,
+    start: usize,
+    end: usize,
+    finished: bool,
+    allow_trailing: bool,
+}
+
+impl<'a, T, E> InflateReader<'a, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn allow_trailing(mut self, allow_trailing: bool) -> InflateReader<'a, T, E> {
+        self.allow_trailing = allow_trailing;
+        self
+    }
+
+    pub fn get_mut(&mut self) -> &mut Stream<'a, T, E> {
+        &mut self.chunk
+    }
+
+    pub fn total_in(&self) -> u64 {
+        self.decompress.total_in()
+    }
+
+    pub fn total_out(&self) -> u64 {
+        self.decompress.total_out()
+    }
+
+    pub fn trailing_len(&self) -> Option<u64> {
+        self.finished
+            .then(|| self.chunk_len - self.decompress.total_in())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.finished = true;
+        let unread = (self.end - self.start) as i64;
+        self.chunk.seek(SeekFrom::Current(-unread))?;
+        self.start = self.end;
+        Ok(())
+    }
+}
+
+impl<T, E> Read for InflateReader<'_, T, E>
+where
+    T: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.finished {
+            let trailing_len = self.chunk_len - self.decompress.total_in();
+            if trailing_len > 0 && !self.allow_trailing {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "{} bytes of trailing data after the deflate stream",
+                        trailing_len
+                    ),
+                ));
+            }
+            return Ok(0);
+        }
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            if self.start == self.end {
+                self.start = 0;
+                self.end = self.chunk.read(&mut self.input)?;
+            }
+            let eof = self.start == self.end;
+            let flush = if eof {
+                FlushDecompress::Finish
+            } else {
+                FlushDecompress::None
+            };
+            let total_in = self.decompress.total_in();
+            let total_out = self.decompress.total_out();
+            let status = self
+                .decompress
+                .decompress(&self.input[self.start..self.end], buf, flush)
+                .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+            let consumed = (self.decompress.total_in() - total_in) as usize;
+            let produced = (self.decompress.total_out() - total_out) as usize;
+            self.start += consumed;
+            if status == Status::StreamEnd {
+                self.finish()?;
+                if produced == 0 {
+                    return self.read(buf);
+                }
+                return Ok(produced);
+            }
+            if produced > 0 {
+                return Ok(produced);
+            }
+            if eof && consumed == 0 {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "deflate stream is truncated",
+                ));
+            }
+        }
+    }
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn inflate(&mut self, format: DeflateFormat) -> Result<InflateReader<'_, T, E>> {
+        let chunk_len = self.remainder_len()?;
+        Ok(InflateReader {
+            chunk: self.borrow_chunk(Some(chunk_len))?,
+            chunk_len,
+            decompress: Decompress::new(format == DeflateFormat::Zlib),
+            input: vec![0u8; 8192],
+            start: 0,
+            end: 0,
+            finished: false,
+            allow_trailing: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use flate2::{
+        write::{DeflateEncoder, ZlibEncoder},
+        Compression,
+    };
+
+    use super::*;
+
+    fn deflate(data: &[u8]) -> Vec<u8> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn inflate_chunk() {
+        let text = "once upon a time ".repeat(100);
+        let compressed = deflate(text.as_bytes());
+        let mut data = b"xx".to_vec();
+        data.extend_from_slice(&compressed);
+        data.extend_from_slice(b"next");
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(2)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(compressed.len() as u64)).unwrap();
+        let mut inflated = String::new();
+        chunk
+            .inflate(DeflateFormat::Raw)
+            .unwrap()
+            .read_to_string(&mut inflated)
+            .unwrap();
+        assert_eq!(inflated, text);
+        assert_eq!(cursor.position(), 2 + compressed.len() as u64);
+    }
+
+    #[test]
+    fn inflate_zlib() {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(b"hello zlib").unwrap();
+        let mut cursor = Cursor::new(encoder.finish().unwrap());
+        let mut stream = Stream::new(&mut cursor);
+        let mut reader = stream.inflate(DeflateFormat::Zlib).unwrap();
+        let mut inflated = Vec::new();
+        reader.read_to_end(&mut inflated).unwrap();
+        assert_eq!(inflated, b"hello zlib");
+        assert_eq!(reader.trailing_len(), Some(0));
+    }
+
+    #[test]
+    fn inflate_trailing_data() {
+        let mut data = deflate(b"payload");
+        let compressed_len = data.len() as u64;
+        data.extend_from_slice(b"junk");
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let mut inflated = Vec::new();
+        let err = stream
+            .inflate(DeflateFormat::Raw)
+            .unwrap()
+            .read_to_end(&mut inflated)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "4 bytes of trailing data after the deflate stream"
+        );
+        assert_eq!(inflated, b"payload");
+        stream.seek(SeekFrom::Start(0)).unwrap();
+        let mut reader = stream
+            .inflate(DeflateFormat::Raw)
+            .unwrap()
+            .allow_trailing(true);
+        inflated.clear();
+        reader.read_to_end(&mut inflated).unwrap();
+        assert_eq!(reader.total_in(), compressed_len);
+        assert_eq!(reader.trailing_len(), Some(4));
+        drop(reader);
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"junk");
+    }
+
+    #[test]
+    fn inflate_truncated() {
+        let data = deflate(&[7u8; 1000]);
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let mut chunk = stream.borrow_chunk(Some(4)).unwrap();
+        let mut inflated = Vec::new();
+        let err = chunk
+            .inflate(DeflateFormat::Raw)
+            .unwrap()
+            .read_to_end(&mut inflated)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
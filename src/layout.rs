@@ -0,0 +1,730 @@
+use std::{
+    io::{Error, ErrorKind, Read, Result, Write},
+    mem,
+};
+
+use crate::{
+    magic::MagicMismatch,
+    read_num::{Endianness, NumReader, NumReaderEndianness, Numeric, ReadNum},
+    write_num::{NumWriter, NumWriterEndianness, WriteNum},
+};
+
+pub trait LayoutField: Sized {
+    fn read_field<R>(reader: &mut R) -> Result<Self>
+    where
+        R: ReadNum + Read + ?Sized;
+
+    fn write_field<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: WriteNum + Write + ?Sized;
+}
+
+macro_rules! impl_layout_field {
+    ($($type: ty),*) => {
+        $(
+            impl LayoutField for $type {
+                fn read_field<R>(reader: &mut R) -> Result<$type>
+                where
+                    R: ReadNum + Read + ?Sized,
+                {
+                    reader.read_num()
+                }
+
+                fn write_field<W>(&self, writer: &mut W) -> Result<()>
+                where
+                    W: WriteNum + Write + ?Sized,
+                {
+                    writer.write_num(*self)
+                }
+            }
+        )*
+    };
+}
+
+impl_layout_field! {u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64}
+
+impl<const N: usize> LayoutField for [u8; N] {
+    fn read_field<R>(reader: &mut R) -> Result<[u8; N]>
+    where
+        R: ReadNum + Read + ?Sized,
+    {
+        let mut buf = [0u8; N];
+        reader.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn write_field<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: WriteNum + Write + ?Sized,
+    {
+        writer.write_all(self)
+    }
+}
+
+pub trait ReadBinary: Sized {
+    fn read_binary<R>(reader: &mut R) -> Result<Self>
+    where
+        R: ReadNum + Read + ?Sized;
+}
+
+pub trait WriteBinary {
+    fn write_binary<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: WriteNum + Write + ?Sized;
+}
+
+impl<T> ReadBinary for T
+where
+    T: LayoutField,
+{
+    fn read_binary<R>(reader: &mut R) -> Result<T>
+    where
+        R: ReadNum + Read + ?Sized,
+    {
+        T::read_field(reader)
+    }
+}
+
+impl<T> WriteBinary for T
+where
+    T: LayoutField,
+{
+    fn write_binary<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: WriteNum + Write + ?Sized,
+    {
+        self.write_field(writer)
+    }
+}
+
+pub trait ReadSequence: Sized {
+    fn read_sequence<R>(reader: &mut R, len: usize) -> Result<Self>
+    where
+        R: ReadNum + Read + ?Sized;
+}
+
+pub trait WriteSequence {
+    fn sequence_len(&self) -> usize;
+
+    fn write_sequence<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: WriteNum + Write + ?Sized;
+}
+
+impl<T> ReadSequence for Vec<T>
+where
+    T: ReadBinary,
+{
+    fn read_sequence<R>(reader: &mut R, len: usize) -> Result<Vec<T>>
+    where
+        R: ReadNum + Read + ?Sized,
+    {
+        let mut items = Vec::with_capacity(len.min(4096));
+        for _ in 0..len {
+            items.push(T::read_binary(reader)?);
+        }
+        Ok(items)
+    }
+}
+
+impl ReadSequence for String {
+    fn read_sequence<R>(reader: &mut R, len: usize) -> Result<String>
+    where
+        R: ReadNum + Read + ?Sized,
+    {
+        let mut bytes = Vec::new();
+        reader.take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() < len {
+            return Err(Error::from(ErrorKind::UnexpectedEof));
+        }
+        String::from_utf8(bytes).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+}
+
+impl<T> WriteSequence for [T]
+where
+    T: WriteBinary,
+{
+    fn sequence_len(&self) -> usize {
+        self.len()
+    }
+
+    fn write_sequence<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: WriteNum + Write + ?Sized,
+    {
+        self.iter().try_for_each(|item| item.write_binary(writer))
+    }
+}
+
+impl<T> WriteSequence for Vec<T>
+where
+    T: WriteBinary,
+{
+    fn sequence_len(&self) -> usize {
+        self.len()
+    }
+
+    fn write_sequence<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: WriteNum + Write + ?Sized,
+    {
+        self.as_slice().write_sequence(writer)
+    }
+}
+
+impl WriteSequence for str {
+    fn sequence_len(&self) -> usize {
+        self.len()
+    }
+
+    fn write_sequence<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: WriteNum + Write + ?Sized,
+    {
+        writer.write_all(self.as_bytes())
+    }
+}
+
+impl WriteSequence for String {
+    fn sequence_len(&self) -> usize {
+        self.len()
+    }
+
+    fn write_sequence<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: WriteNum + Write + ?Sized,
+    {
+        self.as_str().write_sequence(writer)
+    }
+}
+
+pub fn read_len_prefixed<L, S, R>(reader: &mut R) -> Result<S>
+where
+    L: Numeric + TryInto<usize> + Copy + std::fmt::Display,
+    S: ReadSequence,
+    R: ReadNum + Read + ?Sized,
+{
+    let prefix: L = reader.read_num()?;
+    let len = prefix.try_into().map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("invalid length prefix {}", prefix),
+        )
+    })?;
+    S::read_sequence(reader, len)
+}
+
+pub fn write_len_prefixed<L, S, W>(sequence: &S, writer: &mut W) -> Result<()>
+where
+    L: Numeric + TryFrom<usize>,
+    S: WriteSequence + ?Sized,
+    W: WriteNum + Write + ?Sized,
+{
+    let len = sequence.sequence_len();
+    let prefix = L::try_from(len).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "{} items do not fit in a {} length prefix",
+                len,
+                std::any::type_name::<L>()
+            ),
+        )
+    })?;
+    writer.write_num(prefix)?;
+    sequence.write_sequence(writer)
+}
+
+pub struct Counted<'a, T: ?Sized> {
+    inner: &'a mut T,
+    count: u64,
+}
+
+impl<'a, T> Counted<'a, T>
+where
+    T: ?Sized,
+{
+    pub fn new(inner: &'a mut T) -> Counted<'a, T> {
+        Counted { inner, count: 0 }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn get_ref(&self) -> &T {
+        self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner
+    }
+}
+
+impl<T> Counted<'_, T>
+where
+    T: Read + ?Sized,
+{
+    pub fn read_magic(&mut self, magic: &[u8]) -> Result<()> {
+        let offset = self.count;
+        let mut found = Vec::with_capacity(magic.len());
+        self.take(magic.len() as u64).read_to_end(&mut found)?;
+        if found == magic {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidData,
+                MagicMismatch {
+                    offset,
+                    expected: magic.to_vec(),
+                    found,
+                },
+            ))
+        }
+    }
+
+    pub fn read_align(&mut self, align: u64) -> Result<()> {
+        let padding = self.count.next_multiple_of(align) - self.count;
+        let copied = std::io::copy(&mut self.take(padding), &mut std::io::sink())?;
+        if copied < padding {
+            return Err(Error::from(ErrorKind::UnexpectedEof));
+        }
+        Ok(())
+    }
+}
+
+impl<T> Counted<'_, T>
+where
+    T: Write + ?Sized,
+{
+    pub fn write_align(&mut self, align: u64) -> Result<()> {
+        let padding = self.count.next_multiple_of(align) - self.count;
+        std::io::copy(&mut std::io::repeat(0).take(padding), self)?;
+        Ok(())
+    }
+}
+
+impl<T> Read for Counted<'_, T>
+where
+    T: Read + ?Sized,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read as u64;
+        Ok(read)
+    }
+}
+
+impl<T> Write for Counted<'_, T>
+where
+    T: Write + ?Sized,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub struct CountedNum;
+
+macro_rules! impl_counted_reader {
+    ($($type: ty, $method: ident);*) => {
+        $(
+            fn $method(counted: &mut Counted<'_, T>) -> Result<$type> {
+                let value = counted.inner.$method()?;
+                counted.count += mem::size_of::<$type>() as u64;
+                Ok(value)
+            }
+        )*
+    };
+}
+
+macro_rules! impl_counted_writer {
+    ($($type: ty, $method: ident);*) => {
+        $(
+            fn $method(counted: &mut Counted<'_, T>, value: $type) -> Result<()> {
+                counted.inner.$method(value)?;
+                counted.count += mem::size_of::<$type>() as u64;
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl<T> NumReader<Counted<'_, T>> for CountedNum
+where
+    T: ReadNum + ?Sized,
+{
+    impl_counted_reader! {
+        u8, read_u8; u16, read_u16; u32, read_u32; u64, read_u64; u128, read_u128;
+        i8, read_i8; i16, read_i16; i32, read_i32; i64, read_i64; i128, read_i128;
+        usize, read_usize; isize, read_isize; f32, read_f32; f64, read_f64
+    }
+}
+
+impl<T> NumReaderEndianness<Counted<'_, T>> for CountedNum
+where
+    T: ReadNum + ?Sized,
+    T::Reader: NumReaderEndianness<T>,
+{
+    fn endianness(counted: &Counted<'_, T>) -> Endianness {
+        counted.inner.endianness()
+    }
+}
+
+impl<T> NumWriter<Counted<'_, T>> for CountedNum
+where
+    T: WriteNum + ?Sized,
+{
+    impl_counted_writer! {
+        u8, write_u8; u16, write_u16; u32, write_u32; u64, write_u64; u128, write_u128;
+        i8, write_i8; i16, write_i16; i32, write_i32; i64, write_i64; i128, write_i128;
+        usize, write_usize; isize, write_isize; f32, write_f32; f64, write_f64
+    }
+}
+
+impl<T> NumWriterEndianness<Counted<'_, T>> for CountedNum
+where
+    T: WriteNum + ?Sized,
+    T::Writer: NumWriterEndianness<T>,
+{
+    fn endianness(counted: &Counted<'_, T>) -> Endianness {
+        T::Writer::endianness(counted.inner)
+    }
+}
+
+impl<T> ReadNum for Counted<'_, T>
+where
+    T: ReadNum + ?Sized,
+{
+    type Reader = CountedNum;
+}
+
+impl<T> WriteNum for Counted<'_, T>
+where
+    T: WriteNum + ?Sized,
+{
+    type Writer = CountedNum;
+}
+
+#[macro_export]
+macro_rules! binary_layout {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident { $($body:tt)* }) => {
+        $crate::binary_layout! {
+            @munch (reader, writer, self)
+            [$(#[$meta])* $vis struct $name] [] [] [] []
+            $($body)*
+        }
+    };
+    (
+        @munch ($r:ident, $w:ident, $s:tt) $head:tt [$($fields:tt)*] [$($names:tt)*]
+        [$($reads:tt)*] [$($writes:tt)*]
+        pad($len:expr) $(, $($rest:tt)*)?
+    ) => {
+        $crate::binary_layout! {
+            @munch ($r, $w, $s) $head [$($fields)*] [$($names)*]
+            [$($reads)* {
+                let mut padding = [0u8; $len];
+                ::std::io::Read::read_exact($r, &mut padding)?;
+            }]
+            [$($writes)* ::std::io::Write::write_all($w, &[0u8; $len])?;]
+            $($($rest)*)?
+        }
+    };
+    (
+        @munch ($r:ident, $w:ident, $s:tt) $head:tt [$($fields:tt)*] [$($names:tt)*]
+        [$($reads:tt)*] [$($writes:tt)*]
+        const $field:ident : $ty:ty $(as $endian:ident)? = $value:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::binary_layout! {
+            @munch ($r, $w, $s) $head [$($fields)*] [$($names)*]
+            [$($reads)* {
+                let expected: $ty = $value;
+                let found: $ty = $crate::binary_layout!(@read $r, $ty $(, $endian)?);
+                if found != expected {
+                    return Err(::std::io::Error::new(
+                        ::std::io::ErrorKind::InvalidData,
+                        format!(
+                            "{}: expected {:?}, found {:?}",
+                            stringify!($field),
+                            expected,
+                            found
+                        ),
+                    ));
+                }
+            }]
+            [$($writes)* {
+                let value: $ty = $value;
+                $crate::binary_layout!(@write $w, value $(, $endian)?);
+            }]
+            $($($rest)*)?
+        }
+    };
+    (
+        @munch ($r:ident, $w:ident, $s:tt) $head:tt [$($fields:tt)*] [$($names:tt)*]
+        [$($reads:tt)*] [$($writes:tt)*]
+        $(#[$field_meta:meta])* $field_vis:vis $field:ident : $ty:ty $(as $endian:ident)?
+        $(, $($rest:tt)*)?
+    ) => {
+        $crate::binary_layout! {
+            @munch ($r, $w, $s) $head
+            [$($fields)* $(#[$field_meta])* $field_vis $field: $ty,]
+            [$($names)* $field]
+            [$($reads)* let $field: $ty = $crate::binary_layout!(@read $r, $ty $(, $endian)?);]
+            [$($writes)* $crate::binary_layout!(@write $w, $s.$field $(, $endian)?);]
+            $($($rest)*)?
+        }
+    };
+    (
+        @munch ($r:ident, $w:ident, $s:tt) [$(#[$meta:meta])* $vis:vis struct $name:ident]
+        [$($fields:tt)*] [$($names:ident)*] [$($reads:tt)*] [$($writes:tt)*]
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($fields)*
+        }
+
+        impl $crate::layout::LayoutField for $name {
+            fn read_field<R>($r: &mut R) -> ::std::io::Result<$name>
+            where
+                R: $crate::read_num::ReadNum + ::std::io::Read + ?Sized,
+            {
+                $($reads)*
+                Ok($name { $($names),* })
+            }
+
+            fn write_field<W>(&$s, $w: &mut W) -> ::std::io::Result<()>
+            where
+                W: $crate::write_num::WriteNum + ::std::io::Write + ?Sized,
+            {
+                $($writes)*
+                Ok(())
+            }
+        }
+
+        impl $name {
+            $vis fn read_from<R>(reader: &mut R) -> ::std::io::Result<$name>
+            where
+                R: $crate::read_num::ReadNum + ::std::io::Read + ?Sized,
+            {
+                <$name as $crate::layout::LayoutField>::read_field(reader)
+            }
+
+            $vis fn write_to<W>(&self, writer: &mut W) -> ::std::io::Result<()>
+            where
+                W: $crate::write_num::WriteNum + ::std::io::Write + ?Sized,
+            {
+                $crate::layout::LayoutField::write_field(self, writer)
+            }
+        }
+    };
+    (@read $r:ident, $ty:ty) => {
+        <$ty as $crate::layout::LayoutField>::read_field($r)?
+    };
+    (@read $r:ident, $ty:ty, be) => {
+        <$ty as $crate::layout::LayoutField>::read_field(
+            &mut $crate::read_num::ReadEndianExt::be($r),
+        )?
+    };
+    (@read $r:ident, $ty:ty, le) => {
+        <$ty as $crate::layout::LayoutField>::read_field(
+            &mut $crate::read_num::ReadEndianExt::le($r),
+        )?
+    };
+    (@write $w:ident, $value:expr) => {
+        $crate::layout::LayoutField::write_field(&$value, $w)?
+    };
+    (@write $w:ident, $value:expr, be) => {
+        $crate::layout::LayoutField::write_field(
+            &$value,
+            &mut $crate::write_num::WriteEndianExt::be_writer($w),
+        )?
+    };
+    (@write $w:ident, $value:expr, le) => {
+        $crate::layout::LayoutField::write_field(
+            &$value,
+            &mut $crate::write_num::WriteEndianExt::le_writer($w),
+        )?
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind, Seek, SeekFrom};
+
+    use crate::{write_num::WriteEndianExt, Stream};
+
+    crate::binary_layout! {
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct Version {
+            pub major: u8,
+            pub minor: u8,
+        }
+    }
+
+    crate::binary_layout! {
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct Header {
+            const magic: [u8; 4] = *b"LYT0",
+            pub version: Version,
+            pad(2),
+            pub count: u32,
+            pub checksum: u16 as le,
+            pub tag: [u8; 3],
+            const terminator: u16 as be = 0xfeed,
+        }
+    }
+
+    fn header() -> Header {
+        Header {
+            version: Version { major: 1, minor: 2 },
+            count: 0x0102_0304,
+            checksum: 0xabcd,
+            tag: *b"xyz",
+        }
+    }
+
+    const ENCODED: &[u8] = b"LYT0\x01\x02\x00\x00\x04\x03\x02\x01\xcd\xabxyz\xfe\xed";
+
+    #[test]
+    fn binary_layout_write() {
+        let mut cursor = Cursor::new(vec![0u8; ENCODED.len()]);
+        let mut stream = Stream::new(&mut cursor).le();
+        header().write_to(&mut stream).unwrap();
+        assert_eq!(cursor.get_ref(), ENCODED);
+    }
+
+    #[test]
+    fn binary_layout_read() {
+        let mut cursor = Cursor::new(ENCODED.to_vec());
+        let mut stream = Stream::new(&mut cursor).le();
+        assert_eq!(Header::read_from(&mut stream).unwrap(), header());
+        let mut big = ENCODED.to_vec();
+        big[8..12].reverse();
+        let mut cursor = Cursor::new(big);
+        let mut stream = Stream::new(&mut cursor).be();
+        assert_eq!(Header::read_from(&mut stream).unwrap(), header());
+    }
+
+    #[test]
+    fn binary_layout_constant_mismatch() {
+        let mut data = ENCODED.to_vec();
+        data[0] = b'X';
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor).le();
+        let err = Header::read_from(&mut stream).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "magic: expected [76, 89, 84, 48], found [88, 89, 84, 48]"
+        );
+        let mut data = ENCODED.to_vec();
+        *data.last_mut().unwrap() = 0;
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor).le();
+        let err = Header::read_from(&mut stream).unwrap_err();
+        assert_eq!(err.to_string(), "terminator: expected 65261, found 65024");
+        stream.seek(SeekFrom::Start(4)).unwrap();
+        assert_eq!(
+            Version::read_from(&mut stream).unwrap(),
+            Version { major: 1, minor: 2 }
+        );
+    }
+
+    #[test]
+    fn binary_layout_nested_write() {
+        let mut out = Vec::new();
+        Version { major: 3, minor: 4 }
+            .write_to(&mut out.be_writer())
+            .unwrap();
+        assert_eq!(out, [3, 4]);
+    }
+
+    #[cfg(feature = "derive")]
+    mod derive {
+        use std::io::{Cursor, ErrorKind};
+
+        use crate::{
+            layout::{ReadBinary, WriteBinary},
+            magic::MagicMismatch,
+            read_num::ReadEndianExt,
+            write_num::WriteEndianExt,
+        };
+
+        #[derive(Debug, PartialEq, crate::ReadBinary, crate::WriteBinary)]
+        struct Entry(u8, #[endian(le)] u16);
+
+        #[derive(Debug, PartialEq, crate::ReadBinary, crate::WriteBinary)]
+        #[magic(b"ABCD")]
+        #[endian(be)]
+        #[pad_to(8)]
+        struct Record {
+            version: u16,
+            #[len_prefix(u8)]
+            #[pad_to(4)]
+            name: String,
+            #[endian(le)]
+            flags: u32,
+            #[magic(b"E")]
+            #[len_prefix(u16)]
+            entries: Vec<Entry>,
+        }
+
+        fn record() -> Record {
+            Record {
+                version: 0x0102,
+                name: "abc".to_string(),
+                flags: 0x0a0b0c0d,
+                entries: vec![Entry(1, 0x0302), Entry(4, 0x0605)],
+            }
+        }
+
+        const ENCODED: &[u8] =
+            b"ABCD\x01\x02\x03abc\x00\x00\x0d\x0c\x0b\x0aE\x00\x02\x01\x02\x03\x04\x05\x06\0\0\0\0\0\0\0";
+
+        #[test]
+        fn derive_write_binary() {
+            let mut out = Vec::new();
+            record().write_binary(&mut out.be_writer()).unwrap();
+            assert_eq!(out, ENCODED);
+            let mut out = Vec::new();
+            record().write_binary(&mut out.le_writer()).unwrap();
+            assert_eq!(out, ENCODED);
+        }
+
+        #[test]
+        fn derive_read_binary() {
+            let mut cursor = Cursor::new(ENCODED);
+            let mut reader = cursor.le();
+            assert_eq!(Record::read_binary(&mut reader).unwrap(), record());
+            assert_eq!(cursor.position(), ENCODED.len() as u64);
+        }
+
+        #[test]
+        fn derive_read_binary_errors() {
+            let mut data = ENCODED.to_vec();
+            data[16] = b'X';
+            let err = Record::read_binary(&mut Cursor::new(data).be()).unwrap_err();
+            let mismatch = err.get_ref().unwrap().downcast_ref::<MagicMismatch>();
+            assert_eq!(mismatch.unwrap().offset, 16);
+            let err = Record::read_binary(&mut Cursor::new(&ENCODED[..24]).be()).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        }
+
+        #[test]
+        fn derive_len_prefix_overflow() {
+            let mut record = record();
+            record.name = "x".repeat(256);
+            let err = record
+                .write_binary(&mut Vec::new().be_writer())
+                .unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        }
+    }
+}
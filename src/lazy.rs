@@ -0,0 +1,165 @@
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result, Seek, SeekFrom},
+    marker::PhantomData,
+};
+
+use crate::{read_num::NativeEndianReader, Stream};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChunkSpec {
+    pub offset: u64,
+    pub len: u64,
+}
+
+impl ChunkSpec {
+    pub fn open<'a, T: Seek>(&self, inner: &'a mut T) -> Result<Stream<'a, T>> {
+        let end = self.offset.checked_add(self.len).ok_or_else(|| {
+            Error::new(ErrorKind::InvalidInput, "chunk spec overflows the stream")
+        })?;
+        inner.seek(SeekFrom::Start(self.offset))?;
+        Ok(Stream {
+            inner,
+            origin_pos: self.offset,
+            limit_pos: end,
+            endianness: PhantomData,
+        })
+    }
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Seek,
+{
+    pub fn chunk_spec(&mut self, len: u64) -> Result<ChunkSpec> {
+        let remainder_len = self.remainder_len()?;
+        if len > remainder_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "chunk of {} bytes exceeds the remaining {} bytes",
+                    len, remainder_len
+                ),
+            ));
+        }
+        Ok(ChunkSpec {
+            offset: self.inner.stream_position()?,
+            len,
+        })
+    }
+
+    pub fn lazy<R>(
+        &mut self,
+        len: u64,
+        parse: fn(&mut Stream<'_, T, E>) -> Result<R>,
+    ) -> Result<Lazy<R, T, E>> {
+        let spec = self.chunk_spec(len)?;
+        self.inner.seek(SeekFrom::Start(spec.offset + len))?;
+        Ok(Lazy { spec, parse })
+    }
+}
+
+pub struct Lazy<R, T, E = NativeEndianReader>
+where
+    T: Seek,
+{
+    spec: ChunkSpec,
+    parse: fn(&mut Stream<'_, T, E>) -> Result<R>,
+}
+
+impl<R, T, E> Lazy<R, T, E>
+where
+    T: Seek,
+{
+    pub fn new(spec: ChunkSpec, parse: fn(&mut Stream<'_, T, E>) -> Result<R>) -> Lazy<R, T, E> {
+        Lazy { spec, parse }
+    }
+
+    pub fn spec(&self) -> ChunkSpec {
+        self.spec
+    }
+
+    pub fn resolve(&self, inner: &mut T) -> Result<R> {
+        let mut chunk = self.spec.open(inner)?.with_endianness::<E>();
+        (self.parse)(&mut chunk)
+    }
+}
+
+impl<R, T, E> Clone for Lazy<R, T, E>
+where
+    T: Seek,
+{
+    fn clone(&self) -> Lazy<R, T, E> {
+        *self
+    }
+}
+
+impl<R, T, E> Copy for Lazy<R, T, E> where T: Seek {}
+
+impl<R, T, E> fmt::Debug for Lazy<R, T, E>
+where
+    T: Seek,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lazy").field("spec", &self.spec).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, thread};
+
+    use super::*;
+    use crate::read_num::{BigEndianReader, ReadNum};
+
+    fn read_entry(entry: &mut Stream<'_, Cursor<Vec<u8>>, BigEndianReader>) -> Result<(u16, u64)> {
+        Ok((entry.read_u16()?, entry.remainder_len()?))
+    }
+
+    fn archive() -> Vec<u8> {
+        vec![
+            0x00, 0x03, 0x12, 0x34, 0xff, 0x00, 0x02, 0xab, 0xcd, 0x00, 0x01, 0x07,
+        ]
+    }
+
+    #[test]
+    fn index_then_resolve() {
+        let mut cursor = Cursor::new(archive());
+        let mut stream = Stream::new(&mut cursor).be();
+        let mut index = Vec::new();
+        while stream.remainder_len().unwrap() > 0 {
+            let len = stream.read_u16().unwrap() as u64;
+            index.push(stream.lazy(len, read_entry).unwrap());
+        }
+        assert_eq!(index[1].spec(), ChunkSpec { offset: 7, len: 2 });
+        let mut backing = Cursor::new(archive());
+        assert_eq!(index[1].resolve(&mut backing).unwrap(), (0xabcd, 0));
+        assert_eq!(index[0].resolve(&mut backing).unwrap(), (0x1234, 1));
+        let result = index[2].resolve(&mut backing);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn resolve_on_another_thread() {
+        let mut cursor = Cursor::new(archive());
+        let mut stream = Stream::new(&mut cursor).be();
+        stream.seek(SeekFrom::Start(2)).unwrap();
+        let lazy = stream.lazy(3, read_entry).unwrap();
+        assert_eq!(stream.stream_position().unwrap(), 5);
+        let handle = thread::spawn(move || lazy.resolve(&mut Cursor::new(archive())));
+        assert_eq!(handle.join().unwrap().unwrap(), (0x1234, 1));
+    }
+
+    #[test]
+    fn chunk_spec_over_remainder() {
+        let mut cursor = Cursor::new(archive());
+        let mut stream = Stream::new(&mut cursor);
+        let mut chunk = stream.borrow_chunk(Some(4)).unwrap();
+        let result = chunk.chunk_spec(5);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let spec = chunk.chunk_spec(4).unwrap();
+        let mut backing = Cursor::new(archive());
+        let mut reopened = spec.open(&mut backing).unwrap();
+        assert_eq!(reopened.remainder_len().unwrap(), 4);
+    }
+}
@@ -1,15 +1,164 @@
-#![feature(seek_stream_len)]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "std", feature(seek_stream_len))]
+extern crate alloc;
+extern crate self as once_io;
+
+#[cfg(feature = "std")]
+pub mod adler;
+#[cfg(feature = "std")]
+pub mod align;
+#[cfg(feature = "std")]
+pub mod ascii_num;
+#[cfg(feature = "tokio")]
+pub mod async_chunk;
+#[cfg(feature = "tokio")]
+pub mod async_read_num;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub mod async_stream;
+#[cfg(feature = "tokio")]
+pub mod async_write_num;
+#[cfg(feature = "std")]
+pub mod base64;
+#[cfg(feature = "std")]
+pub mod bcd;
+#[cfg(feature = "std")]
+pub mod bitfield;
+pub mod bits;
+#[cfg(feature = "bytes")]
+pub mod buf;
+#[cfg(feature = "std")]
+pub mod bulk;
+#[cfg(feature = "std")]
+pub mod bytes;
+#[cfg(feature = "std")]
+pub mod cached;
+#[cfg(feature = "std")]
+pub mod cdc;
+#[cfg(feature = "std")]
+pub mod checksum;
+#[cfg(feature = "std")]
+pub mod chunk;
+#[cfg(feature = "std")]
+pub mod cipher;
+#[cfg(feature = "std")]
+pub mod cobs;
+#[cfg(feature = "std")]
+pub mod crc;
+#[cfg(feature = "std")]
+pub mod delimited;
+#[cfg(feature = "std")]
+pub mod demux;
+#[cfg(feature = "std")]
+pub mod diff;
+#[cfg(feature = "std")]
+pub mod digest;
+#[cfg(feature = "embedded-io")]
+pub mod embedded;
+pub mod endian;
+#[cfg(feature = "std")]
+pub mod entropy;
+#[cfg(feature = "std")]
+pub mod escape;
+#[cfg(feature = "std")]
+pub mod fixed;
+#[cfg(feature = "std")]
+pub mod float;
+#[cfg(feature = "std")]
+pub mod frame;
+#[cfg(feature = "futures")]
+pub mod frame_stream;
+#[cfg(feature = "zerocopy")]
+mod from_bytes;
+#[cfg(feature = "std")]
+pub mod guid;
+#[cfg(feature = "flate2")]
+pub mod gzip;
+#[cfg(feature = "std")]
+pub mod hex;
+#[cfg(feature = "std")]
+pub mod hexdump;
+#[cfg(feature = "flate2")]
+pub mod inflate;
+pub mod io;
+#[cfg(feature = "std")]
+pub mod layout;
+#[cfg(feature = "std")]
+pub mod lazy;
+#[cfg(feature = "std")]
+pub mod magic;
+#[cfg(feature = "std")]
+pub mod merkle;
+#[cfg(feature = "std")]
+pub mod msgpack;
+#[cfg(feature = "std")]
+pub mod narrow;
+#[cfg(feature = "std")]
+pub mod net;
+#[cfg(feature = "std")]
+pub mod offset;
+#[cfg(feature = "rayon")]
+pub mod par;
+#[cfg(feature = "std")]
+pub mod peek;
+#[cfg(feature = "std")]
+pub mod png;
+#[cfg(feature = "std")]
+pub mod prefetch;
+#[cfg(feature = "std")]
+mod prefixed;
+#[cfg(feature = "std")]
+pub mod protobuf;
+#[cfg(feature = "std")]
+pub mod range_fetch;
+#[cfg(feature = "std")]
+pub mod read_at;
 pub mod read_num;
+#[cfg(feature = "std")]
+pub mod records;
+#[cfg(feature = "std")]
+pub mod riff;
+#[cfg(feature = "std")]
+mod scan;
+#[cfg(feature = "std")]
+pub mod schema;
+#[cfg(feature = "std")]
+pub mod sentinel;
+#[cfg(feature = "std")]
+pub mod sequence;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod string;
+#[cfg(feature = "std")]
+pub mod time;
+#[cfg(feature = "std")]
+pub mod tlv;
+#[cfg(feature = "std")]
+pub mod verify;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+pub mod write_behind;
+pub mod write_num;
+#[cfg(feature = "zstd")]
+pub mod zstd_seekable;
+
+#[cfg(feature = "derive")]
+pub use once_io_derive::{ReadBinary, WriteBinary};
+
+use core::marker::PhantomData;
 
-use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+use io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+use read_num::{BigEndianReader, LittleEndianReader, NativeEndianReader, NumReader, ReadNum};
+use write_num::{BigEndianWriter, LittleEndianWriter, NativeEndianWriter, WriteNum};
 
-pub struct Stream<'a, T>
+pub struct Stream<'a, T, E = NativeEndianReader>
 where
     T: Seek,
 {
     inner: &'a mut T,
     origin_pos: u64,
     limit_pos: u64,
+    endianness: PhantomData<E>,
 }
 
 impl<'a, T> Stream<'a, T>
@@ -21,44 +170,64 @@ where
             inner,
             origin_pos: 0,
             limit_pos: u64::MAX,
+            endianness: PhantomData,
         }
     }
 }
 
-impl<'a, T> Stream<'a, T>
+impl<'a, T, E> Stream<'a, T, E>
 where
     T: Seek,
 {
-    pub fn borrow_chunk(&mut self, limit: Option<u64>) -> Result<Stream<'_, T>> {
+    pub fn borrow_chunk(&mut self, limit: Option<u64>) -> Result<Stream<'_, T, E>> {
         let origin_pos = self.inner.stream_position()?;
         let limit_pos = match limit {
             None => u64::MAX,
-            Some(l) => std::cmp::min(origin_pos.saturating_add(l), self.limit_pos),
+            Some(l) => core::cmp::min(origin_pos.saturating_add(l), self.limit_pos),
         };
-        Ok(Stream::<'_, T> {
+        Ok(Stream::<'_, T, E> {
             inner: self.inner,
             origin_pos,
             limit_pos,
+            endianness: PhantomData,
         })
     }
 
     pub fn remainder_len(&mut self) -> Result<u64> {
         let current_position = self.inner.stream_position()?;
-        let end_position = std::cmp::min(self.inner.stream_len()?, self.limit_pos);
-        Ok(match end_position.checked_sub(current_position) {
-            Some(n) => n,
-            None => 0,
-        })
+        let end_position = core::cmp::min(self.inner.stream_len()?, self.limit_pos);
+        Ok(end_position.saturating_sub(current_position))
+    }
+
+    pub fn with_endianness<R>(self) -> Stream<'a, T, R> {
+        Stream::<'a, T, R> {
+            inner: self.inner,
+            origin_pos: self.origin_pos,
+            limit_pos: self.limit_pos,
+            endianness: PhantomData,
+        }
+    }
+
+    pub fn be(self) -> Stream<'a, T, BigEndianReader> {
+        self.with_endianness()
+    }
+
+    pub fn le(self) -> Stream<'a, T, LittleEndianReader> {
+        self.with_endianness()
+    }
+
+    pub fn native(self) -> Stream<'a, T, NativeEndianReader> {
+        self.with_endianness()
     }
 }
 
-impl<T> Seek for Stream<'_, T>
+impl<T, E> Seek for Stream<'_, T, E>
 where
     T: Seek,
 {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
         let start_position = self.origin_pos;
-        let end_position = std::cmp::min(self.inner.stream_len()?, self.limit_pos);
+        let end_position = core::cmp::min(self.inner.stream_len()?, self.limit_pos);
         let final_position = match pos {
             SeekFrom::Current(n) => self.inner.stream_position()?.checked_add_signed(n),
             SeekFrom::End(n) => end_position.checked_add_signed(n),
@@ -81,23 +250,23 @@ where
     }
 }
 
-impl<T> Read for Stream<'_, T>
+impl<T, E> Read for Stream<'_, T, E>
 where
     T: Read + Seek,
 {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        let len = std::cmp::min(buf.len(), self.remainder_len()? as usize);
-        Ok(self.inner.read(&mut buf[..len])?)
+        let len = core::cmp::min(buf.len(), self.remainder_len()? as usize);
+        self.inner.read(&mut buf[..len])
     }
 }
 
-impl<T> Write for Stream<'_, T>
+impl<T, E> Write for Stream<'_, T, E>
 where
     T: Write + Seek,
 {
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
-        let len = std::cmp::min(buf.len(), self.remainder_len()? as usize);
-        Ok(self.inner.write(&buf[..len])?)
+        let len = core::cmp::min(buf.len(), self.remainder_len()? as usize);
+        self.inner.write(&buf[..len])
     }
 
     fn flush(&mut self) -> Result<()> {
@@ -105,6 +274,29 @@ where
     }
 }
 
+impl<'a, T, E> ReadNum for Stream<'a, T, E>
+where
+    T: Read + Seek,
+    E: NumReader<Stream<'a, T, E>>,
+{
+    type Reader = E;
+}
+
+macro_rules! impl_write_num_for_stream {
+    ($reader: ty, $writer: ty) => {
+        impl<T> WriteNum for Stream<'_, T, $reader>
+        where
+            T: Write + Seek,
+        {
+            type Writer = $writer;
+        }
+    };
+}
+
+impl_write_num_for_stream! {BigEndianReader, BigEndianWriter}
+impl_write_num_for_stream! {LittleEndianReader, LittleEndianWriter}
+impl_write_num_for_stream! {NativeEndianReader, NativeEndianWriter}
+
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
@@ -306,4 +498,46 @@ mod tests {
         let seek_result = chunk.seek(SeekFrom::Start(10));
         assert!(seek_result.is_err());
     }
+
+    #[test]
+    fn read_num_with_default_endianness() {
+        let data = 0x01020304u32.to_ne_bytes();
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        assert_eq!(stream.read_u32().unwrap(), 0x01020304);
+    }
+
+    #[test]
+    fn read_num_with_selected_endianness() {
+        let data = [0x01, 0x02, 0x03, 0x04];
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor).be();
+        assert_eq!(stream.read_u16().unwrap(), 0x0102);
+        let mut chunk = stream.borrow_chunk(Some(2)).unwrap().le();
+        assert_eq!(chunk.read_u16().unwrap(), 0x0403);
+        assert!(chunk.read_u8().is_err());
+    }
+
+    #[test]
+    fn with_endianness_keeps_bounds() {
+        let data = [0u8; 10];
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let _ = stream.seek(SeekFrom::Start(1));
+        let chunk = stream.borrow_chunk(Some(4)).unwrap();
+        let chunk = chunk.le();
+        assert_eq!(chunk.origin_pos, 1);
+        assert_eq!(chunk.limit_pos, 5);
+    }
+
+    #[test]
+    fn write_num_with_selected_endianness() {
+        let mut cursor = Cursor::new(vec![0u8; 4]);
+        let mut stream = Stream::new(&mut cursor).be();
+        stream.write_u16(0x0102).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(1)).unwrap().le();
+        chunk.write_u8(0x03).unwrap();
+        assert!(chunk.write_u16(0x0405).is_err());
+        assert_eq!(cursor.into_inner(), [0x01, 0x02, 0x03, 0x00]);
+    }
 }
@@ -0,0 +1,106 @@
+use std::{
+    error, fmt,
+    io::{Error, ErrorKind, Read, Result, Seek, SeekFrom},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MagicMismatch {
+    pub offset: u64,
+    pub expected: Vec<u8>,
+    pub found: Vec<u8>,
+}
+
+impl fmt::Display for MagicMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "magic mismatch at offset {}: expected {:02x?}, found {:02x?}",
+            self.offset, self.expected, self.found
+        )
+    }
+}
+
+impl error::Error for MagicMismatch {}
+
+pub trait ReadMagic: Read + Seek {
+    fn expect_magic(&mut self, magic: &[u8]) -> Result<()> {
+        let offset = self.stream_position()?;
+        let mut found = Vec::with_capacity(magic.len());
+        self.take(magic.len() as u64).read_to_end(&mut found)?;
+        if found == magic {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidData,
+                MagicMismatch {
+                    offset,
+                    expected: magic.to_vec(),
+                    found,
+                },
+            ))
+        }
+    }
+
+    fn expect_magic_or_rewind(&mut self, magic: &[u8]) -> Result<()> {
+        let offset = self.stream_position()?;
+        let result = self.expect_magic(magic);
+        if result.is_err() {
+            self.seek(SeekFrom::Start(offset))?;
+        }
+        result
+    }
+}
+
+impl<R> ReadMagic for R where R: Read + Seek + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::Stream;
+
+    const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    #[test]
+    fn expect_magic() {
+        let mut cursor = Cursor::new(b"\x89PNG\r\n\x1a\n\x00".to_vec());
+        cursor.expect_magic(PNG_MAGIC).unwrap();
+        assert_eq!(cursor.position(), 8);
+    }
+
+    #[test]
+    fn expect_magic_mismatch() {
+        let mut cursor = Cursor::new(b"xGIF89a".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let _ = stream.seek(SeekFrom::Start(1));
+        let err = stream.expect_magic(b"GIF87a").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let mismatch = err.get_ref().unwrap().downcast_ref::<MagicMismatch>();
+        assert_eq!(
+            mismatch,
+            Some(&MagicMismatch {
+                offset: 1,
+                expected: b"GIF87a".to_vec(),
+                found: b"GIF89a".to_vec(),
+            })
+        );
+    }
+
+    #[test]
+    fn expect_magic_when_truncated() {
+        let mut cursor = Cursor::new(b"\x89PN".to_vec());
+        let err = cursor.expect_magic(PNG_MAGIC).unwrap_err();
+        let mismatch = err.get_ref().unwrap().downcast_ref::<MagicMismatch>();
+        assert_eq!(mismatch.unwrap().found, b"\x89PN");
+    }
+
+    #[test]
+    fn expect_magic_or_rewind() {
+        let mut cursor = Cursor::new(b"GIF89a".to_vec());
+        assert!(cursor.expect_magic_or_rewind(PNG_MAGIC).is_err());
+        assert_eq!(cursor.position(), 0);
+        cursor.expect_magic_or_rewind(b"GIF89a").unwrap();
+        assert_eq!(cursor.position(), 6);
+    }
+}
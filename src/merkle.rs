@@ -0,0 +1,257 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use crate::{digest::Digest, lazy::ChunkSpec, Stream};
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofStep<O> {
+    Left(O),
+    Right(O),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof<O> {
+    pub index: usize,
+    pub steps: Vec<ProofStep<O>>,
+}
+
+impl<O> MerkleProof<O>
+where
+    O: AsRef<[u8]> + PartialEq,
+{
+    pub fn verify<D>(&self, leaf: &O, root: &O, digest: &D) -> bool
+    where
+        D: Digest<Output = O> + Clone,
+    {
+        let mut hash = None;
+        for step in &self.steps {
+            let current = hash.as_ref().unwrap_or(leaf);
+            hash = Some(match step {
+                ProofStep::Left(sibling) => node_hash(digest, sibling, current),
+                ProofStep::Right(sibling) => node_hash(digest, current, sibling),
+            });
+        }
+        hash.as_ref().unwrap_or(leaf) == root
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTree<O> {
+    levels: Vec<Vec<O>>,
+}
+
+pub fn leaf_hash<D>(digest: &D, data: &[u8]) -> D::Output
+where
+    D: Digest + Clone,
+{
+    let mut digest = digest.clone();
+    digest.update(&[LEAF_PREFIX]);
+    digest.update(data);
+    digest.finish()
+}
+
+fn node_hash<D>(digest: &D, left: &D::Output, right: &D::Output) -> D::Output
+where
+    D: Digest + Clone,
+    D::Output: AsRef<[u8]>,
+{
+    let mut digest = digest.clone();
+    digest.update(&[NODE_PREFIX]);
+    digest.update(left.as_ref());
+    digest.update(right.as_ref());
+    digest.finish()
+}
+
+impl<O> MerkleTree<O>
+where
+    O: AsRef<[u8]> + Clone,
+{
+    pub fn from_leaves<D>(leaves: Vec<O>, digest: &D) -> MerkleTree<O>
+    where
+        D: Digest<Output = O> + Clone,
+    {
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let level = levels.last().unwrap();
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(digest, left, right),
+                    [single] => single.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree { levels }
+    }
+
+    pub fn build<T, E, D, I>(
+        stream: &mut Stream<'_, T, E>,
+        chunks: I,
+        digest: &D,
+    ) -> Result<MerkleTree<O>>
+    where
+        T: Read + Seek,
+        D: Digest<Output = O> + Clone,
+        I: IntoIterator<Item = ChunkSpec>,
+    {
+        let mut leaves = Vec::new();
+        for spec in chunks {
+            let offset = spec.offset.checked_sub(stream.origin_pos).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("chunk at {} starts before the stream", spec.offset),
+                )
+            })?;
+            stream.seek(SeekFrom::Start(offset))?;
+            let remainder_len = stream.remainder_len()?;
+            if spec.len > remainder_len {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!(
+                        "chunk of {} bytes exceeds the remaining {} bytes",
+                        spec.len, remainder_len
+                    ),
+                ));
+            }
+            let mut chunk = stream.borrow_chunk(Some(spec.len))?;
+            let mut leaf = digest.clone();
+            leaf.update(&[LEAF_PREFIX]);
+            leaves.push(chunk.digest_remainder(leaf)?);
+        }
+        Ok(MerkleTree::from_leaves(leaves, digest))
+    }
+
+    pub fn root(&self) -> Option<&O> {
+        self.levels.last().unwrap().first()
+    }
+
+    pub fn leaves(&self) -> &[O] {
+        &self.levels[0]
+    }
+
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    pub fn proof(&self, index: usize) -> Option<MerkleProof<O>> {
+        if index >= self.levels[0].len() {
+            return None;
+        }
+        let mut steps = Vec::new();
+        let mut position = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = position ^ 1;
+            if let Some(hash) = level.get(sibling) {
+                steps.push(if sibling < position {
+                    ProofStep::Left(hash.clone())
+                } else {
+                    ProofStep::Right(hash.clone())
+                });
+            }
+            position /= 2;
+        }
+        Some(MerkleProof { index, steps })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, SeekFrom};
+
+    use super::*;
+    use crate::cdc::CdcConfig;
+
+    #[derive(Clone)]
+    struct Fnv(u64);
+
+    impl Digest for Fnv {
+        type Output = [u8; 8];
+
+        fn update(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+
+        fn finish(&self) -> [u8; 8] {
+            self.0.to_be_bytes()
+        }
+    }
+
+    const FNV: Fnv = Fnv(0xcbf2_9ce4_8422_2325);
+
+    #[test]
+    fn merkle_tree_shape() {
+        let leaves = (0u8..5).map(|i| leaf_hash(&FNV, &[i])).collect::<Vec<_>>();
+        let tree = MerkleTree::from_leaves(leaves.clone(), &FNV);
+        assert_eq!(tree.depth(), 3);
+        let left = node_hash(
+            &FNV,
+            &node_hash(&FNV, &leaves[0], &leaves[1]),
+            &node_hash(&FNV, &leaves[2], &leaves[3]),
+        );
+        assert_eq!(tree.root(), Some(&node_hash(&FNV, &left, &leaves[4])));
+        let empty = MerkleTree::<[u8; 8]>::from_leaves(Vec::new(), &FNV);
+        assert_eq!(empty.root(), None);
+        assert!(empty.proof(0).is_none());
+    }
+
+    #[test]
+    fn merkle_proofs() {
+        let leaves = (0u8..7).map(|i| leaf_hash(&FNV, &[i])).collect::<Vec<_>>();
+        let tree = MerkleTree::from_leaves(leaves.clone(), &FNV);
+        let root = tree.root().unwrap();
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = tree.proof(index).unwrap();
+            assert!(proof.verify(leaf, root, &FNV));
+            assert!(!proof.verify(&leaves[(index + 1) % 7], root, &FNV));
+        }
+        assert_eq!(tree.proof(6).unwrap().steps.len(), 2);
+    }
+
+    #[test]
+    fn merkle_over_cdc_chunks() {
+        let data = (0..20_000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect::<Vec<_>>();
+        let mut cursor = Cursor::new(data.clone());
+        let mut stream = Stream::new(&mut cursor);
+        let specs = stream
+            .cdc_chunks(CdcConfig::new(256, 1024, 4096))
+            .map(|chunk| chunk.map(|chunk| chunk.spec))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        let tree = MerkleTree::build(&mut stream, specs.iter().copied(), &FNV).unwrap();
+        assert_eq!(tree.leaves().len(), specs.len());
+        let spec = specs[1];
+        let chunk = &data[spec.offset as usize..(spec.offset + spec.len) as usize];
+        let leaf = leaf_hash(&FNV, chunk);
+        assert_eq!(tree.leaves()[1], leaf);
+        assert!(tree
+            .proof(1)
+            .unwrap()
+            .verify(&leaf, tree.root().unwrap(), &FNV));
+    }
+
+    #[test]
+    fn merkle_build_in_sub_chunk() {
+        let mut cursor = Cursor::new(b"headerabcdefgh".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(6)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(6)).unwrap();
+        let spec = |offset, len| ChunkSpec { offset, len };
+        let tree = MerkleTree::build(&mut chunk, [spec(6, 3), spec(9, 3)], &FNV).unwrap();
+        assert_eq!(
+            tree.leaves(),
+            [leaf_hash(&FNV, b"abc"), leaf_hash(&FNV, b"def")]
+        );
+        let err = MerkleTree::build(&mut chunk, [spec(9, 4)], &FNV).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let err = MerkleTree::build(&mut chunk, [spec(2, 1)], &FNV).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,387 @@
+use std::io::{self, Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+use crate::{
+    read_num::{ReadEndianExt, ReadNum},
+    write_num::{WriteEndianExt, WriteNum},
+    Stream,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MsgpackHeader {
+    Nil,
+    Bool(bool),
+    Uint(u64),
+    Int(i64),
+    F32(f32),
+    F64(f64),
+    Str(u32),
+    Bin(u32),
+    Array(u32),
+    Map(u32),
+    Ext(i8, u32),
+}
+
+impl MsgpackHeader {
+    pub fn payload_len(&self) -> u32 {
+        match *self {
+            MsgpackHeader::Str(len) | MsgpackHeader::Bin(len) | MsgpackHeader::Ext(_, len) => len,
+            _ => 0,
+        }
+    }
+
+    pub fn child_count(&self) -> u64 {
+        match *self {
+            MsgpackHeader::Array(len) => len.into(),
+            MsgpackHeader::Map(len) => u64::from(len) * 2,
+            _ => 0,
+        }
+    }
+}
+
+pub trait ReadMsgpack: Read {
+    fn read_msgpack_header(&mut self) -> Result<MsgpackHeader> {
+        let mut reader = self.be();
+        let marker = reader.read_u8()?;
+        Ok(match marker {
+            0x00..=0x7f => MsgpackHeader::Uint(marker.into()),
+            0x80..=0x8f => MsgpackHeader::Map((marker & 0x0f).into()),
+            0x90..=0x9f => MsgpackHeader::Array((marker & 0x0f).into()),
+            0xa0..=0xbf => MsgpackHeader::Str((marker & 0x1f).into()),
+            0xc0 => MsgpackHeader::Nil,
+            0xc2 => MsgpackHeader::Bool(false),
+            0xc3 => MsgpackHeader::Bool(true),
+            0xc4 => MsgpackHeader::Bin(reader.read_u8()?.into()),
+            0xc5 => MsgpackHeader::Bin(reader.read_u16()?.into()),
+            0xc6 => MsgpackHeader::Bin(reader.read_u32()?),
+            0xc7 => {
+                let len = reader.read_u8()?.into();
+                MsgpackHeader::Ext(reader.read_i8()?, len)
+            }
+            0xc8 => {
+                let len = reader.read_u16()?.into();
+                MsgpackHeader::Ext(reader.read_i8()?, len)
+            }
+            0xc9 => {
+                let len = reader.read_u32()?;
+                MsgpackHeader::Ext(reader.read_i8()?, len)
+            }
+            0xca => MsgpackHeader::F32(reader.read_f32()?),
+            0xcb => MsgpackHeader::F64(reader.read_f64()?),
+            0xcc => MsgpackHeader::Uint(reader.read_u8()?.into()),
+            0xcd => MsgpackHeader::Uint(reader.read_u16()?.into()),
+            0xce => MsgpackHeader::Uint(reader.read_u32()?.into()),
+            0xcf => MsgpackHeader::Uint(reader.read_u64()?),
+            0xd0 => MsgpackHeader::Int(reader.read_i8()?.into()),
+            0xd1 => MsgpackHeader::Int(reader.read_i16()?.into()),
+            0xd2 => MsgpackHeader::Int(reader.read_i32()?.into()),
+            0xd3 => MsgpackHeader::Int(reader.read_i64()?),
+            0xd4 => MsgpackHeader::Ext(reader.read_i8()?, 1),
+            0xd5 => MsgpackHeader::Ext(reader.read_i8()?, 2),
+            0xd6 => MsgpackHeader::Ext(reader.read_i8()?, 4),
+            0xd7 => MsgpackHeader::Ext(reader.read_i8()?, 8),
+            0xd8 => MsgpackHeader::Ext(reader.read_i8()?, 16),
+            0xd9 => MsgpackHeader::Str(reader.read_u8()?.into()),
+            0xda => MsgpackHeader::Str(reader.read_u16()?.into()),
+            0xdb => MsgpackHeader::Str(reader.read_u32()?),
+            0xdc => MsgpackHeader::Array(reader.read_u16()?.into()),
+            0xdd => MsgpackHeader::Array(reader.read_u32()?),
+            0xde => MsgpackHeader::Map(reader.read_u16()?.into()),
+            0xdf => MsgpackHeader::Map(reader.read_u32()?),
+            0xe0..=0xff => MsgpackHeader::Int((marker as i8).into()),
+            0xc1 => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "reserved msgpack marker 0xc1",
+                ))
+            }
+        })
+    }
+
+    fn read_msgpack_bytes(&mut self) -> Result<Vec<u8>> {
+        let len = match self.read_msgpack_header()? {
+            MsgpackHeader::Str(len) | MsgpackHeader::Bin(len) => len,
+            header => return Err(unexpected_header("str or bin", header)),
+        };
+        let mut bytes = Vec::new();
+        self.take(len.into()).read_to_end(&mut bytes)?;
+        if bytes.len() < len as usize {
+            return Err(Error::from(ErrorKind::UnexpectedEof));
+        }
+        Ok(bytes)
+    }
+
+    fn read_msgpack_str(&mut self) -> Result<String> {
+        String::from_utf8(self.read_msgpack_bytes()?)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+
+    fn skip_msgpack_value(&mut self) -> Result<()> {
+        let mut pending = 1u64;
+        while pending > 0 {
+            pending -= 1;
+            let header = self.read_msgpack_header()?;
+            let len = header.payload_len().into();
+            if io::copy(&mut self.take(len), &mut io::sink())? < len {
+                return Err(Error::from(ErrorKind::UnexpectedEof));
+            }
+            pending += header.child_count();
+        }
+        Ok(())
+    }
+}
+
+impl<R> ReadMsgpack for R where R: Read + ?Sized {}
+
+fn unexpected_header(expected: &str, header: MsgpackHeader) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("expected msgpack {}, found {:?}", expected, header),
+    )
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn read_msgpack_payload<F, R>(&mut self, f: F) -> Result<(MsgpackHeader, R)>
+    where
+        F: FnOnce(MsgpackHeader, &mut Stream<'_, T, E>) -> Result<R>,
+    {
+        let header = self.read_msgpack_header()?;
+        let len = u64::from(header.payload_len());
+        let remainder_len = self.remainder_len()?;
+        if len > remainder_len {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!(
+                    "msgpack payload of {} bytes exceeds the remaining {} bytes",
+                    len, remainder_len
+                ),
+            ));
+        }
+        let mut chunk = self.borrow_chunk(Some(len))?;
+        let result = f(header, &mut chunk)?;
+        chunk.seek(SeekFrom::End(0))?;
+        Ok((header, result))
+    }
+}
+
+fn write_sized<W: Write + ?Sized>(writer: &mut W, markers: [u8; 3], len: u32) -> Result<()> {
+    let mut writer = writer.be_writer();
+    if let Ok(len) = u8::try_from(len) {
+        writer.write_u8(markers[0])?;
+        writer.write_u8(len)
+    } else if let Ok(len) = u16::try_from(len) {
+        writer.write_u8(markers[1])?;
+        writer.write_u16(len)
+    } else {
+        writer.write_u8(markers[2])?;
+        writer.write_u32(len)
+    }
+}
+
+fn payload_len(len: usize) -> Result<u32> {
+    u32::try_from(len).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} bytes exceed the msgpack length limit", len),
+        )
+    })
+}
+
+pub trait WriteMsgpack: Write {
+    fn write_msgpack_header(&mut self, header: MsgpackHeader) -> Result<()> {
+        let header = match header {
+            MsgpackHeader::Int(value) if value >= 0 => MsgpackHeader::Uint(value as u64),
+            header => header,
+        };
+        let mut writer = self.be_writer();
+        match header {
+            MsgpackHeader::Nil => writer.write_u8(0xc0),
+            MsgpackHeader::Bool(value) => writer.write_u8(0xc2 | u8::from(value)),
+            MsgpackHeader::Uint(value) => match value {
+                0..=0x7f => writer.write_u8(value as u8),
+                0x80..=0xff => writer.write_all(&[0xcc, value as u8]),
+                0x100..=0xffff => {
+                    writer.write_u8(0xcd)?;
+                    writer.write_u16(value as u16)
+                }
+                0x1_0000..=0xffff_ffff => {
+                    writer.write_u8(0xce)?;
+                    writer.write_u32(value as u32)
+                }
+                _ => {
+                    writer.write_u8(0xcf)?;
+                    writer.write_u64(value)
+                }
+            },
+            MsgpackHeader::Int(value) => {
+                if value >= -32 {
+                    writer.write_i8(value as i8)
+                } else if let Ok(value) = i8::try_from(value) {
+                    writer.write_u8(0xd0)?;
+                    writer.write_i8(value)
+                } else if let Ok(value) = i16::try_from(value) {
+                    writer.write_u8(0xd1)?;
+                    writer.write_i16(value)
+                } else if let Ok(value) = i32::try_from(value) {
+                    writer.write_u8(0xd2)?;
+                    writer.write_i32(value)
+                } else {
+                    writer.write_u8(0xd3)?;
+                    writer.write_i64(value)
+                }
+            }
+            MsgpackHeader::F32(value) => {
+                writer.write_u8(0xca)?;
+                writer.write_f32(value)
+            }
+            MsgpackHeader::F64(value) => {
+                writer.write_u8(0xcb)?;
+                writer.write_f64(value)
+            }
+            MsgpackHeader::Str(len) if len < 32 => writer.write_u8(0xa0 | len as u8),
+            MsgpackHeader::Str(len) => write_sized(&mut writer, [0xd9, 0xda, 0xdb], len),
+            MsgpackHeader::Bin(len) => write_sized(&mut writer, [0xc4, 0xc5, 0xc6], len),
+            MsgpackHeader::Array(len) if len < 16 => writer.write_u8(0x90 | len as u8),
+            MsgpackHeader::Array(len) if len <= 0xffff => {
+                writer.write_u8(0xdc)?;
+                writer.write_u16(len as u16)
+            }
+            MsgpackHeader::Array(len) => {
+                writer.write_u8(0xdd)?;
+                writer.write_u32(len)
+            }
+            MsgpackHeader::Map(len) if len < 16 => writer.write_u8(0x80 | len as u8),
+            MsgpackHeader::Map(len) if len <= 0xffff => {
+                writer.write_u8(0xde)?;
+                writer.write_u16(len as u16)
+            }
+            MsgpackHeader::Map(len) => {
+                writer.write_u8(0xdf)?;
+                writer.write_u32(len)
+            }
+            MsgpackHeader::Ext(type_id, len) => {
+                match len {
+                    1 => writer.write_u8(0xd4)?,
+                    2 => writer.write_u8(0xd5)?,
+                    4 => writer.write_u8(0xd6)?,
+                    8 => writer.write_u8(0xd7)?,
+                    16 => writer.write_u8(0xd8)?,
+                    _ => write_sized(&mut writer, [0xc7, 0xc8, 0xc9], len)?,
+                }
+                writer.write_i8(type_id)
+            }
+        }
+    }
+
+    fn write_msgpack_str(&mut self, value: &str) -> Result<()> {
+        self.write_msgpack_header(MsgpackHeader::Str(payload_len(value.len())?))?;
+        self.write_all(value.as_bytes())
+    }
+
+    fn write_msgpack_bin(&mut self, value: &[u8]) -> Result<()> {
+        self.write_msgpack_header(MsgpackHeader::Bin(payload_len(value.len())?))?;
+        self.write_all(value)
+    }
+}
+
+impl<W> WriteMsgpack for W where W: Write + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn msgpack_header_round_trip() {
+        let headers = [
+            MsgpackHeader::Nil,
+            MsgpackHeader::Bool(true),
+            MsgpackHeader::Bool(false),
+            MsgpackHeader::Uint(5),
+            MsgpackHeader::Uint(200),
+            MsgpackHeader::Uint(70_000),
+            MsgpackHeader::Uint(u64::MAX),
+            MsgpackHeader::Int(-3),
+            MsgpackHeader::Int(-100),
+            MsgpackHeader::Int(-40_000),
+            MsgpackHeader::Int(i64::MIN),
+            MsgpackHeader::F32(1.5),
+            MsgpackHeader::F64(-2.25),
+            MsgpackHeader::Str(3),
+            MsgpackHeader::Str(300),
+            MsgpackHeader::Bin(3),
+            MsgpackHeader::Array(2),
+            MsgpackHeader::Array(70_000),
+            MsgpackHeader::Map(20),
+            MsgpackHeader::Ext(-1, 4),
+            MsgpackHeader::Ext(7, 3),
+        ];
+        let mut out = Vec::new();
+        for header in headers {
+            out.write_msgpack_header(header).unwrap();
+        }
+        let mut cursor = Cursor::new(out);
+        for header in headers {
+            assert_eq!(cursor.read_msgpack_header().unwrap(), header);
+        }
+    }
+
+    #[test]
+    fn msgpack_encodings() {
+        let mut out = Vec::new();
+        out.write_msgpack_header(MsgpackHeader::Int(-1)).unwrap();
+        out.write_msgpack_header(MsgpackHeader::Int(1)).unwrap();
+        out.write_msgpack_header(MsgpackHeader::Map(1)).unwrap();
+        out.write_msgpack_str("a").unwrap();
+        out.write_msgpack_bin(b"\x01\x02").unwrap();
+        assert_eq!(out, b"\xff\x01\x81\xa1a\xc4\x02\x01\x02");
+    }
+
+    #[test]
+    fn skip_msgpack_value() {
+        let mut out = Vec::new();
+        out.write_msgpack_header(MsgpackHeader::Map(2)).unwrap();
+        out.write_msgpack_str("list").unwrap();
+        out.write_msgpack_header(MsgpackHeader::Array(2)).unwrap();
+        out.write_msgpack_bin(b"xyz").unwrap();
+        out.write_msgpack_header(MsgpackHeader::Nil).unwrap();
+        out.write_msgpack_str("ext").unwrap();
+        out.write_msgpack_header(MsgpackHeader::Ext(1, 2)).unwrap();
+        out.extend(b"\x00\x00");
+        out.write_msgpack_str("after").unwrap();
+        let mut cursor = Cursor::new(out);
+        cursor.skip_msgpack_value().unwrap();
+        assert_eq!(cursor.read_msgpack_str().unwrap(), "after");
+    }
+
+    #[test]
+    fn read_msgpack_payload() {
+        let mut out = Vec::new();
+        out.write_msgpack_header(MsgpackHeader::Ext(-1, 4)).unwrap();
+        out.extend(b"\x00\x00\x00\x2a");
+        out.write_msgpack_header(MsgpackHeader::Nil).unwrap();
+        let mut cursor = Cursor::new(out);
+        let mut stream = Stream::new(&mut cursor).be();
+        let (header, seconds) = stream
+            .read_msgpack_payload(|_, chunk| chunk.read_u32())
+            .unwrap();
+        assert_eq!(header, MsgpackHeader::Ext(-1, 4));
+        assert_eq!(seconds, 42);
+        assert_eq!(stream.read_msgpack_header().unwrap(), MsgpackHeader::Nil);
+    }
+
+    #[test]
+    fn msgpack_errors() {
+        let err = Cursor::new([0xc1]).read_msgpack_header().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let err = Cursor::new([0xc0]).read_msgpack_str().unwrap_err();
+        assert_eq!(err.to_string(), "expected msgpack str or bin, found Nil");
+        let err = Cursor::new(b"\xa3ab").read_msgpack_bytes().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let err = Cursor::new(b"\x92\x01").skip_msgpack_value().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    any, fmt,
+    io::{Error, ErrorKind, Result, Seek},
+};
+
+use crate::read_num::{Numeric, ReadNum};
+
+pub trait ReadNarrow: ReadNum + Seek {
+    fn read_as<N, M>(&mut self) -> Result<M>
+    where
+        N: Numeric + Copy + fmt::Display,
+        M: TryFrom<N>,
+    {
+        let offset = self.stream_position()?;
+        let value = N::read_from(self)?;
+        M::try_from(value).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "value {} at offset {} does not fit in {}",
+                    value,
+                    offset,
+                    any::type_name::<M>()
+                ),
+            )
+        })
+    }
+
+    fn read_u16_as_usize(&mut self) -> Result<usize> {
+        self.read_as::<u16, usize>()
+    }
+
+    fn read_u32_as_usize(&mut self) -> Result<usize> {
+        self.read_as::<u32, usize>()
+    }
+
+    fn read_u64_as_usize(&mut self) -> Result<usize> {
+        self.read_as::<u64, usize>()
+    }
+
+    fn read_u64_as_u32(&mut self) -> Result<u32> {
+        self.read_as::<u64, u32>()
+    }
+
+    fn read_i32_as_usize(&mut self) -> Result<usize> {
+        self.read_as::<i32, usize>()
+    }
+
+    fn read_i64_as_usize(&mut self) -> Result<usize> {
+        self.read_as::<i64, usize>()
+    }
+}
+
+impl<R> ReadNarrow for R where R: ReadNum + Seek + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::read_num::ReadEndianExt;
+
+    #[test]
+    fn read_narrowed_values() {
+        let mut data = 7u32.to_be_bytes().to_vec();
+        data.extend(8u64.to_be_bytes());
+        data.extend(9u64.to_be_bytes());
+        let mut cursor = Cursor::new(data);
+        let mut reader = cursor.be();
+        assert_eq!(reader.read_u32_as_usize().unwrap(), 7);
+        assert_eq!(reader.read_u64_as_usize().unwrap(), 8);
+        assert_eq!(reader.read_u64_as_u32().unwrap(), 9);
+    }
+
+    #[test]
+    fn read_narrowed_value_overflow() {
+        let mut data = 0u16.to_le_bytes().to_vec();
+        data.extend(u64::MAX.to_le_bytes());
+        let mut cursor = Cursor::new(data);
+        let mut reader = cursor.le();
+        let _ = reader.read_u16();
+        let err = reader.read_u64_as_u32().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "value 18446744073709551615 at offset 2 does not fit in u32"
+        );
+    }
+
+    #[test]
+    fn read_negative_as_usize() {
+        let mut cursor = Cursor::new((-1i32).to_le_bytes());
+        let result = cursor.le().read_i32_as_usize();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    fmt,
+    io::{Read, Result, Write},
+    net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6},
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MacAddr(pub [u8; 6]);
+
+impl MacAddr {
+    pub fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+
+    pub fn oui(&self) -> [u8; 3] {
+        [self.0[0], self.0[1], self.0[2]]
+    }
+}
+
+impl From<[u8; 6]> for MacAddr {
+    fn from(octets: [u8; 6]) -> MacAddr {
+        MacAddr(octets)
+    }
+}
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            a, b, c, d, e, g
+        )
+    }
+}
+
+pub trait ReadNet: Read {
+    fn read_ipv4(&mut self) -> Result<Ipv4Addr> {
+        let mut buf = [0u8; 4];
+        self.read_exact(&mut buf)?;
+        Ok(Ipv4Addr::from(buf))
+    }
+
+    fn read_ipv6(&mut self) -> Result<Ipv6Addr> {
+        let mut buf = [0u8; 16];
+        self.read_exact(&mut buf)?;
+        Ok(Ipv6Addr::from(buf))
+    }
+
+    fn read_port(&mut self) -> Result<u16> {
+        let mut buf = [0u8; 2];
+        self.read_exact(&mut buf)?;
+        Ok(u16::from_be_bytes(buf))
+    }
+
+    fn read_socket_addr_v4(&mut self) -> Result<SocketAddrV4> {
+        let ip = self.read_ipv4()?;
+        let port = self.read_port()?;
+        Ok(SocketAddrV4::new(ip, port))
+    }
+
+    fn read_socket_addr_v6(&mut self) -> Result<SocketAddrV6> {
+        let ip = self.read_ipv6()?;
+        let port = self.read_port()?;
+        Ok(SocketAddrV6::new(ip, port, 0, 0))
+    }
+
+    fn read_mac(&mut self) -> Result<[u8; 6]> {
+        let mut buf = [0u8; 6];
+        self.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn read_mac_addr(&mut self) -> Result<MacAddr> {
+        Ok(MacAddr(self.read_mac()?))
+    }
+
+    fn read_oui(&mut self) -> Result<[u8; 3]> {
+        let mut buf = [0u8; 3];
+        self.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+impl<R> ReadNet for R where R: Read + ?Sized {}
+
+pub trait WriteNet: Write {
+    fn write_mac(&mut self, mac: &[u8; 6]) -> Result<()> {
+        self.write_all(mac)
+    }
+
+    fn write_mac_addr(&mut self, mac: &MacAddr) -> Result<()> {
+        self.write_mac(&mac.0)
+    }
+
+    fn write_oui(&mut self, oui: &[u8; 3]) -> Result<()> {
+        self.write_all(oui)
+    }
+}
+
+impl<W> WriteNet for W where W: Write + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use super::*;
+
+    #[test]
+    fn read_ipv4() {
+        let mut cursor = Cursor::new([192, 168, 0, 1]);
+        assert_eq!(cursor.read_ipv4().unwrap(), Ipv4Addr::new(192, 168, 0, 1));
+    }
+
+    #[test]
+    fn read_ipv6() {
+        let mut cursor = Cursor::new(Ipv6Addr::LOCALHOST.octets());
+        assert_eq!(cursor.read_ipv6().unwrap(), Ipv6Addr::LOCALHOST);
+    }
+
+    #[test]
+    fn read_socket_addr_v4() {
+        let mut cursor = Cursor::new([127, 0, 0, 1, 0x1f, 0x90]);
+        assert_eq!(
+            cursor.read_socket_addr_v4().unwrap(),
+            SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8080)
+        );
+    }
+
+    #[test]
+    fn read_socket_addr_v6() {
+        let mut data = Ipv6Addr::LOCALHOST.octets().to_vec();
+        data.extend([0x00, 0x50]);
+        let mut cursor = Cursor::new(data);
+        assert_eq!(
+            cursor.read_socket_addr_v6().unwrap(),
+            SocketAddrV6::new(Ipv6Addr::LOCALHOST, 80, 0, 0)
+        );
+    }
+
+    #[test]
+    fn read_ipv4_when_eof() {
+        let mut cursor = Cursor::new([10, 0, 0]);
+        let result = cursor.read_ipv4();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_mac() {
+        let mut cursor = Cursor::new([0x00, 0x1b, 0x63, 0x84, 0x45, 0xe6, 0x00, 0x1b, 0x63]);
+        let mac = cursor.read_mac_addr().unwrap();
+        assert_eq!(mac.octets(), [0x00, 0x1b, 0x63, 0x84, 0x45, 0xe6]);
+        assert_eq!(mac.oui(), [0x00, 0x1b, 0x63]);
+        assert_eq!(mac.to_string(), "00:1b:63:84:45:e6");
+        assert_eq!(cursor.read_oui().unwrap(), [0x00, 0x1b, 0x63]);
+    }
+
+    #[test]
+    fn write_mac_round_trip() {
+        let mac = MacAddr::from([0xde, 0xad, 0xbe, 0xef, 0x00, 0x01]);
+        let mut out = Vec::new();
+        out.write_mac_addr(&mac).unwrap();
+        out.write_oui(&mac.oui()).unwrap();
+        let mut cursor = Cursor::new(out);
+        assert_eq!(cursor.read_mac().unwrap(), mac.octets());
+        assert_eq!(cursor.read_oui().unwrap(), [0xde, 0xad, 0xbe]);
+    }
+}
@@ -0,0 +1,141 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use crate::{
+    read_num::{Numeric, ReadNum},
+    Stream,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OffsetBase {
+    Start,
+    Chunk,
+    Field,
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn follow_offset<L, F, R>(&mut self, base: OffsetBase, len: Option<u64>, f: F) -> Result<R>
+    where
+        Self: ReadNum,
+        L: Numeric + TryInto<u64>,
+        F: FnOnce(&mut Stream<'_, T, E>) -> Result<R>,
+    {
+        let field_pos = self.inner.stream_position()?;
+        let offset: u64 = L::read_from(self)?
+            .try_into()
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "invalid offset field"))?;
+        let return_pos = self.inner.stream_position()?;
+        let base_pos = match base {
+            OffsetBase::Start => 0,
+            OffsetBase::Chunk => self.origin_pos,
+            OffsetBase::Field => field_pos,
+        };
+        let end_pos = std::cmp::min(self.inner.stream_len()?, self.limit_pos);
+        let target = base_pos
+            .checked_add(offset)
+            .filter(|target| *target <= end_pos)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("offset {} points past the end of the stream", offset),
+                )
+            })?;
+        if let Some(len) = len {
+            if len > end_pos - target {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "{} bytes at offset {} exceed the remaining {} bytes",
+                        len,
+                        offset,
+                        end_pos - target
+                    ),
+                ));
+            }
+        }
+        self.inner.seek(SeekFrom::Start(target))?;
+        let result = f(&mut self.borrow_chunk(Some(len.unwrap_or(end_pos - target)))?);
+        self.inner.seek(SeekFrom::Start(return_pos))?;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn follow_offset_from_start() {
+        let mut cursor = Cursor::new(vec![0x00, 0x00, 0x00, 0x06, 0xaa, 0xbb, 0x12, 0x34]);
+        let mut stream = Stream::new(&mut cursor).be();
+        let value = stream
+            .follow_offset::<u32, _, _>(OffsetBase::Start, Some(2), |target| {
+                assert_eq!(target.remainder_len()?, 2);
+                target.read_u16()
+            })
+            .unwrap();
+        assert_eq!(value, 0x1234);
+        assert_eq!(stream.read_u8().unwrap(), 0xaa);
+    }
+
+    #[test]
+    fn follow_offset_relative_bases() {
+        let mut cursor = Cursor::new(vec![0xff, 0xff, 0x01, 0x03, 0x10, 0x20, 0x30]);
+        let mut stream = Stream::new(&mut cursor).le();
+        stream.seek(SeekFrom::Start(2)).unwrap();
+        let mut chunk = stream.borrow_chunk(None).unwrap();
+        let from_chunk = chunk
+            .follow_offset::<u8, _, _>(OffsetBase::Chunk, None, |t| t.read_u8())
+            .unwrap();
+        let from_field = chunk
+            .follow_offset::<u8, _, _>(OffsetBase::Field, None, |t| t.read_u8())
+            .unwrap();
+        assert_eq!((from_chunk, from_field), (0x03, 0x30));
+        assert_eq!(chunk.read_u8().unwrap(), 0x10);
+    }
+
+    #[test]
+    fn follow_offset_without_len_stays_within_parent() {
+        let mut cursor = Cursor::new(vec![0x02, 0x00, 0xaa, 0xbb, 0xcc, 0xdd]);
+        let mut stream = Stream::new(&mut cursor);
+        let mut chunk = stream.borrow_chunk(Some(4)).unwrap();
+        let rest = chunk
+            .follow_offset::<u8, _, _>(OffsetBase::Start, None, |t| {
+                assert_eq!(t.remainder_len()?, 2);
+                let mut rest = Vec::new();
+                t.read_to_end(&mut rest)?;
+                Ok(rest)
+            })
+            .unwrap();
+        assert_eq!(rest, [0xaa, 0xbb]);
+    }
+
+    #[test]
+    fn follow_offset_restores_position_on_error() {
+        let mut cursor = Cursor::new(vec![0x03, 0x00, 0x00, 0x01]);
+        let mut stream = Stream::new(&mut cursor);
+        let result = stream.follow_offset::<u8, _, _>(OffsetBase::Start, None, |t| t.read_u16());
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(stream.stream_position().unwrap(), 1);
+    }
+
+    #[test]
+    fn follow_offset_out_of_bounds() {
+        let mut cursor = Cursor::new(vec![0x09, 0x00, 0x00]);
+        let mut stream = Stream::new(&mut cursor);
+        let result = stream.follow_offset::<u8, _, _>(OffsetBase::Start, None, |t| t.read_u8());
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        stream.seek(SeekFrom::Start(0)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(2)).unwrap();
+        let result = chunk.follow_offset::<u8, _, _>(OffsetBase::Start, None, |t| t.read_u8());
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let mut cursor = Cursor::new(vec![0x01, 0x00, 0x00]);
+        let mut stream = Stream::new(&mut cursor);
+        let result = stream.follow_offset::<u8, _, _>(OffsetBase::Start, Some(3), |t| t.read_u8());
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,75 @@
+use std::io::Result;
+
+use rayon::prelude::*;
+
+use crate::{
+    lazy::ChunkSpec,
+    read_at::{PositionalReader, ReadAt},
+    Stream,
+};
+
+pub fn par_map_chunks<B, F, R>(backend: &B, specs: &[ChunkSpec], map: F) -> Result<Vec<R>>
+where
+    B: ReadAt + Sync + ?Sized,
+    F: Fn(Stream<'_, PositionalReader<'_, B>>) -> Result<R> + Sync,
+    R: Send,
+{
+    specs
+        .par_iter()
+        .map(|spec| {
+            let mut reader = PositionalReader::new(backend);
+            map(spec.open(&mut reader)?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{ErrorKind, Read};
+
+    use super::*;
+    use crate::read_num::ReadNum;
+
+    fn archive(entries: usize) -> (Vec<u8>, Vec<ChunkSpec>) {
+        let mut data = Vec::new();
+        let mut specs = Vec::new();
+        for i in 0..entries {
+            let offset = data.len() as u64;
+            data.extend_from_slice(&(i as u32).to_be_bytes());
+            data.extend(std::iter::repeat_n(i as u8, i % 7));
+            specs.push(ChunkSpec {
+                offset,
+                len: data.len() as u64 - offset,
+            });
+        }
+        (data, specs)
+    }
+
+    #[test]
+    fn par_map_chunks_in_order() {
+        let (data, specs) = archive(500);
+        let results = par_map_chunks(&data[..], &specs, |chunk| {
+            let mut chunk = chunk.be();
+            let index = chunk.read_u32()?;
+            Ok((index, chunk.remainder_len()?))
+        })
+        .unwrap();
+        let expected = (0..500).map(|i| (i, i as u64 % 7)).collect::<Vec<_>>();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn par_map_chunks_errors() {
+        let (data, mut specs) = archive(10);
+        specs[4].len = 2;
+        let result = par_map_chunks(&data, &specs, |mut chunk| {
+            let mut buf = [0u8; 4];
+            chunk.read_exact(&mut buf)?;
+            Ok(buf)
+        });
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        specs[4].offset = u64::MAX;
+        let result = par_map_chunks(&data, &specs, |_| Ok(()));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+}
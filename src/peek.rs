@@ -0,0 +1,114 @@
+use std::io::{Result, Seek, SeekFrom};
+
+use crate::read_num::{Numeric, ReadNum};
+
+pub trait PeekNum: ReadNum + Seek {
+    fn peek_num<N: Numeric>(&mut self) -> Result<N> {
+        let pos = self.stream_position()?;
+        let result = N::read_from(self);
+        self.seek(SeekFrom::Start(pos))?;
+        result
+    }
+
+    fn peek_u8(&mut self) -> Result<u8> {
+        self.peek_num()
+    }
+
+    fn peek_u16(&mut self) -> Result<u16> {
+        self.peek_num()
+    }
+
+    fn peek_u32(&mut self) -> Result<u32> {
+        self.peek_num()
+    }
+
+    fn peek_u64(&mut self) -> Result<u64> {
+        self.peek_num()
+    }
+
+    fn peek_u128(&mut self) -> Result<u128> {
+        self.peek_num()
+    }
+
+    fn peek_i8(&mut self) -> Result<i8> {
+        self.peek_num()
+    }
+
+    fn peek_i16(&mut self) -> Result<i16> {
+        self.peek_num()
+    }
+
+    fn peek_i32(&mut self) -> Result<i32> {
+        self.peek_num()
+    }
+
+    fn peek_i64(&mut self) -> Result<i64> {
+        self.peek_num()
+    }
+
+    fn peek_i128(&mut self) -> Result<i128> {
+        self.peek_num()
+    }
+
+    fn peek_usize(&mut self) -> Result<usize> {
+        self.peek_num()
+    }
+
+    fn peek_isize(&mut self) -> Result<isize> {
+        self.peek_num()
+    }
+
+    fn peek_f32(&mut self) -> Result<f32> {
+        self.peek_num()
+    }
+
+    fn peek_f64(&mut self) -> Result<f64> {
+        self.peek_num()
+    }
+}
+
+impl<R> PeekNum for R where R: ReadNum + Seek + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use super::*;
+    use crate::{read_num::ReadEndianExt, Stream};
+
+    #[test]
+    fn peek_does_not_consume() {
+        let mut cursor = Cursor::new([0x01, 0x02, 0x03, 0x04]);
+        let mut stream = Stream::new(&mut cursor).be();
+        assert_eq!(stream.peek_u16().unwrap(), 0x0102);
+        assert_eq!(stream.peek_u32().unwrap(), 0x01020304);
+        assert_eq!(stream.read_u8().unwrap(), 0x01);
+        assert_eq!(stream.peek_num::<u8>().unwrap(), 0x02);
+        assert_eq!(stream.read_u8().unwrap(), 0x02);
+    }
+
+    #[test]
+    fn peek_with_endian_adapter() {
+        let mut cursor = Cursor::new([0x2a, 0x00]);
+        let mut reader = cursor.le();
+        assert_eq!(reader.peek_i16().unwrap(), 42);
+        assert_eq!(reader.read_i16().unwrap(), 42);
+    }
+
+    #[test]
+    fn peek_past_limit_restores_position() {
+        let mut cursor = Cursor::new([0u8; 8]);
+        let mut stream = Stream::new(&mut cursor);
+        let mut chunk = stream.borrow_chunk(Some(3)).unwrap();
+        let _ = chunk.read_u8();
+        let result = chunk.peek_u32();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(chunk.stream_position().unwrap(), 1);
+    }
+
+    #[test]
+    fn read_num_generic() {
+        let mut cursor = Cursor::new(1.5f64.to_be_bytes());
+        assert_eq!(cursor.be().read_num::<f64>().unwrap(), 1.5);
+    }
+}
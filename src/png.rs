@@ -0,0 +1,204 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+use crate::{crc::Crc32, Stream};
+
+pub const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PngChunk {
+    pub len: u32,
+    pub kind: [u8; 4],
+    pub crc: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PngFormat {
+    verify_crc: bool,
+}
+
+impl Default for PngFormat {
+    fn default() -> PngFormat {
+        PngFormat::new()
+    }
+}
+
+fn read_u32_be<R: Read + ?Sized>(reader: &mut R) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+impl PngFormat {
+    pub fn new() -> PngFormat {
+        PngFormat { verify_crc: true }
+    }
+
+    pub fn verify_crc(mut self, verify_crc: bool) -> PngFormat {
+        self.verify_crc = verify_crc;
+        self
+    }
+
+    pub fn read_chunk<T, E, F, R>(&self, stream: &mut Stream<'_, T, E>, f: F) -> Result<R>
+    where
+        T: Read + Seek,
+        F: FnOnce(&PngChunk, &mut Stream<'_, T, E>) -> Result<R>,
+    {
+        let len = read_u32_be(stream)?;
+        let mut kind = [0u8; 4];
+        stream.read_exact(&mut kind)?;
+        let remainder_len = stream.remainder_len()?;
+        if len as u64 + 4 > remainder_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "chunk {:?} length {} exceeds the remaining {} bytes",
+                    String::from_utf8_lossy(&kind),
+                    len,
+                    remainder_len
+                ),
+            ));
+        }
+        let data_pos = stream.stream_position()?;
+        stream.seek(SeekFrom::Current(len as i64))?;
+        let crc = read_u32_be(stream)?;
+        if self.verify_crc {
+            stream.seek(SeekFrom::Start(data_pos))?;
+            let mut computed = Crc32::new();
+            computed.update(&kind);
+            let mut data = stream.borrow_chunk(Some(len as u64))?;
+            let mut buf = [0u8; 8192];
+            loop {
+                let n = data.read(&mut buf)?;
+                if n == 0 {
+                    break;
+                }
+                computed.update(&buf[..n]);
+            }
+            if computed.finish() != crc {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "CRC mismatch in chunk {:?}: stored {:#010x}, computed {:#010x}",
+                        String::from_utf8_lossy(&kind),
+                        crc,
+                        computed.finish()
+                    ),
+                ));
+            }
+        }
+        stream.seek(SeekFrom::Start(data_pos))?;
+        let header = PngChunk { len, kind, crc };
+        let mut chunk = stream.borrow_chunk(Some(len as u64))?;
+        let result = f(&header, &mut chunk)?;
+        chunk.seek(SeekFrom::End(0))?;
+        stream.seek(SeekFrom::Current(4))?;
+        Ok(result)
+    }
+
+    pub fn for_each_chunk<T, E, F>(&self, stream: &mut Stream<'_, T, E>, mut f: F) -> Result<()>
+    where
+        T: Read + Seek,
+        F: FnMut(&PngChunk, &mut Stream<'_, T, E>) -> Result<()>,
+    {
+        while stream.remainder_len()? > 0 {
+            self.read_chunk(stream, &mut f)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_chunk<W: Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        kind: &[u8; 4],
+        data: &[u8],
+    ) -> Result<()> {
+        let len = u32::try_from(data.len()).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("chunk of {} bytes exceeds the 32-bit length", data.len()),
+            )
+        })?;
+        let mut crc = Crc32::new();
+        crc.update(kind);
+        crc.update(data);
+        writer.write_all(&len.to_be_bytes())?;
+        writer.write_all(kind)?;
+        writer.write_all(data)?;
+        writer.write_all(&crc.finish().to_be_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{magic::ReadMagic, read_num::ReadNum};
+
+    const IEND: &[u8] = b"\x00\x00\x00\x00IEND\xae\x42\x60\x82";
+
+    fn png() -> Vec<u8> {
+        let mut out = PNG_SIGNATURE.to_vec();
+        let format = PngFormat::new();
+        let ihdr = [0, 0, 0, 1, 0, 0, 0, 2, 8, 6, 0, 0, 0];
+        format.write_chunk(&mut out, b"IHDR", &ihdr).unwrap();
+        format.write_chunk(&mut out, b"tEXt", b"a\0b").unwrap();
+        format.write_chunk(&mut out, b"IEND", &[]).unwrap();
+        out
+    }
+
+    #[test]
+    fn write_chunk_crc() {
+        let data = png();
+        assert!(data.ends_with(IEND));
+    }
+
+    #[test]
+    fn for_each_chunk() {
+        let mut cursor = Cursor::new(png());
+        let mut stream = Stream::new(&mut cursor).be();
+        stream.expect_magic(PNG_SIGNATURE).unwrap();
+        let mut chunks = Vec::new();
+        PngFormat::new()
+            .for_each_chunk(&mut stream, |chunk, data| {
+                if &chunk.kind == b"IHDR" {
+                    assert_eq!(data.read_u32()?, 1);
+                    assert_eq!(data.read_u32()?, 2);
+                }
+                chunks.push((chunk.kind, chunk.len));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(chunks, [(*b"IHDR", 13), (*b"tEXt", 3), (*b"IEND", 0)]);
+    }
+
+    #[test]
+    fn crc_mismatch() {
+        let mut data = png();
+        data[8 + 8 + 12] ^= 0x01;
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(8)).unwrap();
+        let err = PngFormat::new()
+            .read_chunk(&mut stream, |_, _| Ok(()))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err
+            .to_string()
+            .starts_with("CRC mismatch in chunk \"IHDR\""));
+        stream.seek(SeekFrom::Start(8)).unwrap();
+        let len = PngFormat::new()
+            .verify_crc(false)
+            .read_chunk(&mut stream, |chunk, _| Ok(chunk.len))
+            .unwrap();
+        assert_eq!(len, 13);
+    }
+
+    #[test]
+    fn chunk_over_remainder() {
+        let mut cursor = Cursor::new(b"\x00\x00\x00\x05IDATabcde".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let result = PngFormat::new().read_chunk(&mut stream, |_, _| Ok(()));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
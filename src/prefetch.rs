@@ -0,0 +1,271 @@
+use std::{
+    io::{Error, ErrorKind, Read, Result, Seek, SeekFrom},
+    sync::mpsc::{self, Receiver, Sender},
+    thread::{self, JoinHandle},
+};
+
+use crate::cached::load_block;
+
+const DEFAULT_BLOCK_SIZE: usize = 256 * 1024;
+
+struct Request {
+    offset: u64,
+    len: usize,
+}
+
+fn worker_gone() -> Error {
+    Error::new(ErrorKind::BrokenPipe, "prefetch worker has stopped")
+}
+
+fn run<T: Read + Seek>(
+    mut inner: T,
+    requests: Receiver<Request>,
+    responses: Sender<(u64, Result<Vec<u8>>)>,
+) -> T {
+    for request in requests {
+        let block = load_block(&mut inner, request.offset, request.len);
+        if responses.send((request.offset, block)).is_err() {
+            break;
+        }
+    }
+    inner
+}
+
+pub struct Prefetcher<T> {
+    requests: Option<Sender<Request>>,
+    responses: Receiver<(u64, Result<Vec<u8>>)>,
+    worker: Option<JoinHandle<T>>,
+    block_size: usize,
+    current_pos: u64,
+    current: Vec<u8>,
+    pending: Option<u64>,
+    pos: u64,
+    last_end: u64,
+    len: u64,
+}
+
+impl<T> Prefetcher<T>
+where
+    T: Read + Seek + Send + 'static,
+{
+    pub fn new(mut inner: T) -> Result<Prefetcher<T>> {
+        let len = inner.stream_len()?;
+        let (requests, worker_requests) = mpsc::channel();
+        let (worker_responses, responses) = mpsc::channel();
+        let worker = thread::spawn(move || run(inner, worker_requests, worker_responses));
+        Ok(Prefetcher {
+            requests: Some(requests),
+            responses,
+            worker: Some(worker),
+            block_size: DEFAULT_BLOCK_SIZE,
+            current_pos: 0,
+            current: Vec::new(),
+            pending: None,
+            pos: 0,
+            last_end: 0,
+            len,
+        })
+    }
+
+    pub fn block_size(mut self, block_size: usize) -> Prefetcher<T> {
+        assert!(block_size > 0, "block size must not be zero");
+        self.block_size = block_size;
+        self
+    }
+
+    pub fn into_inner(mut self) -> Result<T> {
+        self.stop().ok_or_else(worker_gone)
+    }
+}
+
+impl<T> Prefetcher<T> {
+    fn stop(&mut self) -> Option<T> {
+        self.requests = None;
+        self.worker.take()?.join().ok()
+    }
+
+    fn request(&mut self, offset: u64) -> Result<()> {
+        let len = std::cmp::min(self.block_size as u64, self.len - offset) as usize;
+        let requests = self.requests.as_ref().ok_or_else(worker_gone)?;
+        requests
+            .send(Request { offset, len })
+            .map_err(|_| worker_gone())
+    }
+
+    fn wait_for(&mut self, offset: u64) -> Result<()> {
+        if self.pending.take() != Some(offset) {
+            self.request(offset)?;
+        }
+        loop {
+            let (block_pos, block) = self.responses.recv().map_err(|_| worker_gone())?;
+            if block_pos == offset {
+                self.current = block?;
+                self.current_pos = offset;
+                return Ok(());
+            }
+        }
+    }
+
+    fn prefetch(&mut self, offset: u64) -> Result<()> {
+        if self.pending.is_none() && offset < self.len {
+            self.request(offset)?;
+            self.pending = Some(offset);
+        }
+        Ok(())
+    }
+
+    fn buffered(&self) -> &[u8] {
+        match self.pos.checked_sub(self.current_pos) {
+            Some(offset) if offset < self.current.len() as u64 => &self.current[offset as usize..],
+            _ => &[],
+        }
+    }
+}
+
+impl<T> Read for Prefetcher<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() || self.pos >= self.len {
+            return Ok(0);
+        }
+        if self.buffered().is_empty() {
+            let sequential = self.pos == self.last_end;
+            self.wait_for(self.pos)?;
+            if sequential {
+                self.prefetch(self.current_pos + self.current.len() as u64)?;
+            }
+        }
+        let buffered = self.buffered();
+        let len = std::cmp::min(buf.len(), buffered.len());
+        buf[..len].copy_from_slice(&buffered[..len]);
+        self.pos += len as u64;
+        self.last_end = self.pos;
+        Ok(len)
+    }
+}
+
+impl<T> Seek for Prefetcher<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(n) => self.len.checked_add_signed(n),
+            SeekFrom::Current(n) => self.pos.checked_add_signed(n),
+        };
+        self.pos = target.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.pos)
+    }
+
+    fn stream_len(&mut self) -> Result<u64> {
+        Ok(self.len)
+    }
+
+    fn stream_position(&mut self) -> Result<u64> {
+        Ok(self.pos)
+    }
+}
+
+impl<T> Drop for Prefetcher<T> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Cursor,
+        sync::{Arc, Mutex},
+    };
+
+    use super::*;
+    use crate::{read_num::ReadNum, Stream};
+
+    struct Disk {
+        inner: Cursor<Vec<u8>>,
+        reads: Arc<Mutex<Vec<u64>>>,
+        bad_block: Option<u64>,
+    }
+
+    impl Read for Disk {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let pos = self.inner.position();
+            if self.bad_block == Some(pos) {
+                return Err(Error::new(ErrorKind::TimedOut, "disk timed out"));
+            }
+            self.reads.lock().unwrap().push(pos);
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for Disk {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    fn disk(bad_block: Option<u64>) -> (Disk, Arc<Mutex<Vec<u64>>>) {
+        let reads = Arc::new(Mutex::new(Vec::new()));
+        let disk = Disk {
+            inner: Cursor::new((0..1000u32).map(|i| i as u8).collect()),
+            reads: reads.clone(),
+            bad_block,
+        };
+        (disk, reads)
+    }
+
+    #[test]
+    fn prefetch_sequential_scan() {
+        let (disk, reads) = disk(None);
+        let mut prefetcher = Prefetcher::new(disk).unwrap().block_size(100);
+        let mut stream = Stream::new(&mut prefetcher).be();
+        stream.seek(SeekFrom::Start(10)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(980)).unwrap();
+        let mut sum = 0u64;
+        while chunk.remainder_len().unwrap() > 0 {
+            sum += chunk.read_u8().unwrap() as u64;
+        }
+        let expected = (10..990u32).map(|i| (i as u8) as u64).sum::<u64>();
+        assert_eq!(sum, expected);
+        let reads = reads.lock().unwrap().clone();
+        assert_eq!(reads.iter().filter(|&&pos| pos % 100 == 10).count(), 10);
+        let disk = prefetcher.into_inner().unwrap();
+        assert_eq!(disk.inner.get_ref().len(), 1000);
+    }
+
+    #[test]
+    fn prefetch_random_access() {
+        let (disk, reads) = disk(None);
+        let mut prefetcher = Prefetcher::new(disk).unwrap().block_size(64);
+        let mut stream = Stream::new(&mut prefetcher).le();
+        for pos in [500, 20, 998, 63] {
+            stream.seek(SeekFrom::Start(pos)).unwrap();
+            let expected = u16::from_le_bytes([pos as u8, (pos + 1) as u8]);
+            assert_eq!(stream.read_u16().unwrap(), expected);
+        }
+        assert_eq!(stream.read_u16().unwrap(), 0x4241);
+        stream.seek(SeekFrom::End(0)).unwrap();
+        assert_eq!(
+            stream.read_u8().unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+        drop(prefetcher);
+        assert!(reads.lock().unwrap().starts_with(&[500, 20, 998, 63]));
+    }
+
+    #[test]
+    fn prefetch_errors_surface_on_read() {
+        let (disk, _) = disk(Some(200));
+        let mut prefetcher = Prefetcher::new(disk).unwrap().block_size(100);
+        let mut buf = [0u8; 150];
+        prefetcher.read_exact(&mut buf).unwrap();
+        let err = prefetcher.read_exact(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+        prefetcher.seek(SeekFrom::Start(300)).unwrap();
+        prefetcher.read_exact(&mut buf[..10]).unwrap();
+        assert_eq!(buf[0], 44);
+    }
+}
@@ -0,0 +1,130 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use crate::{
+    read_num::{Numeric, ReadNum},
+    Stream,
+};
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn read_prefixed<L, F, R>(&mut self, f: F) -> Result<R>
+    where
+        Self: ReadNum,
+        L: Numeric + TryInto<u64>,
+        F: FnOnce(&mut Stream<'_, T, E>) -> Result<R>,
+    {
+        self.read_prefixed_with::<L, F, R>(false, f)
+    }
+
+    pub fn read_prefixed_strict<L, F, R>(&mut self, f: F) -> Result<R>
+    where
+        Self: ReadNum,
+        L: Numeric + TryInto<u64>,
+        F: FnOnce(&mut Stream<'_, T, E>) -> Result<R>,
+    {
+        self.read_prefixed_with::<L, F, R>(true, f)
+    }
+
+    fn read_prefixed_with<L, F, R>(&mut self, strict: bool, f: F) -> Result<R>
+    where
+        Self: ReadNum,
+        L: Numeric + TryInto<u64>,
+        F: FnOnce(&mut Stream<'_, T, E>) -> Result<R>,
+    {
+        let len = L::read_from(self)?
+            .try_into()
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "invalid length prefix"))?;
+        let remainder_len = self.remainder_len()?;
+        if len > remainder_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "length prefix {} exceeds the remaining {} bytes",
+                    len, remainder_len
+                ),
+            ));
+        }
+        let mut chunk = self.borrow_chunk(Some(len))?;
+        let result = f(&mut chunk)?;
+        let unread = chunk.remainder_len()?;
+        if strict && unread > 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("{} bytes of the prefixed chunk were not consumed", unread),
+            ));
+        }
+        chunk.seek(SeekFrom::End(0))?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn read_prefixed() {
+        let mut cursor = Cursor::new([0x00, 0x03, 0x01, 0x02, 0x03, 0x04]);
+        let mut stream = Stream::new(&mut cursor).be();
+        let value = stream
+            .read_prefixed::<u16, _, _>(|chunk| {
+                assert_eq!(chunk.remainder_len()?, 3);
+                chunk.read_u16()
+            })
+            .unwrap();
+        assert_eq!(value, 0x0102);
+        assert_eq!(stream.read_u8().unwrap(), 0x04);
+    }
+
+    #[test]
+    fn read_prefixed_nested() {
+        let mut cursor = Cursor::new([0x04, 0x02, 0xaa, 0xbb, 0xcc, 0xdd]);
+        let mut stream = Stream::new(&mut cursor).le();
+        let value = stream
+            .read_prefixed::<u8, _, _>(|outer| {
+                let inner = outer.read_prefixed::<u8, _, _>(|inner| inner.read_u8())?;
+                Ok((inner, outer.read_u8()?))
+            })
+            .unwrap();
+        assert_eq!(value, (0xaa, 0xcc));
+        assert_eq!(stream.read_u8().unwrap(), 0xdd);
+    }
+
+    #[test]
+    fn read_prefixed_strict() {
+        let mut cursor = Cursor::new([0x02, 0x01, 0x02, 0x03, 0x01, 0x02, 0x03]);
+        let mut stream = Stream::new(&mut cursor).be();
+        let value = stream
+            .read_prefixed_strict::<u8, _, _>(|chunk| chunk.read_u16())
+            .unwrap();
+        assert_eq!(value, 0x0102);
+        let err = stream
+            .read_prefixed_strict::<u8, _, _>(|chunk| chunk.read_u8())
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "2 bytes of the prefixed chunk were not consumed"
+        );
+    }
+
+    #[test]
+    fn read_prefixed_over_remainder() {
+        let mut cursor = Cursor::new([0x05, 0x01, 0x02]);
+        let mut stream = Stream::new(&mut cursor);
+        let result = stream.read_prefixed::<u8, _, _>(|chunk| chunk.read_u8());
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_prefixed_negative_length() {
+        let mut cursor = Cursor::new([0xff, 0x01]);
+        let mut stream = Stream::new(&mut cursor);
+        let result = stream.read_prefixed::<i8, _, _>(|chunk| chunk.read_u8());
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
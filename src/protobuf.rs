@@ -0,0 +1,274 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+use crate::{tlv::FieldEncoding, Stream};
+
+const MAX_GROUP_DEPTH: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WireType {
+    Varint,
+    I64,
+    Len,
+    StartGroup,
+    EndGroup,
+    I32,
+}
+
+impl WireType {
+    pub fn from_bits(bits: u8) -> Option<WireType> {
+        match bits {
+            0 => Some(WireType::Varint),
+            1 => Some(WireType::I64),
+            2 => Some(WireType::Len),
+            3 => Some(WireType::StartGroup),
+            4 => Some(WireType::EndGroup),
+            5 => Some(WireType::I32),
+            _ => None,
+        }
+    }
+
+    pub fn bits(self) -> u8 {
+        match self {
+            WireType::Varint => 0,
+            WireType::I64 => 1,
+            WireType::Len => 2,
+            WireType::StartGroup => 3,
+            WireType::EndGroup => 4,
+            WireType::I32 => 5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FieldKey {
+    pub field: u32,
+    pub wire_type: WireType,
+}
+
+impl FieldKey {
+    pub fn new(field: u32, wire_type: WireType) -> FieldKey {
+        FieldKey { field, wire_type }
+    }
+
+    pub fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        let key = (u64::from(self.field) << 3) | u64::from(self.wire_type.bits());
+        FieldEncoding::Varint.write(writer, key)
+    }
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn read_proto_key(&mut self) -> Result<Option<FieldKey>> {
+        if self.remainder_len()? == 0 {
+            return Ok(None);
+        }
+        let key = FieldEncoding::Varint.read(self)?;
+        let wire_type = WireType::from_bits((key & 0x7) as u8).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid wire type {}", key & 0x7),
+            )
+        })?;
+        let field = u32::try_from(key >> 3)
+            .ok()
+            .filter(|field| *field != 0)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid field number {}", key >> 3),
+                )
+            })?;
+        Ok(Some(FieldKey { field, wire_type }))
+    }
+
+    pub fn skip_proto_field(&mut self, key: FieldKey) -> Result<()> {
+        let mut groups = Vec::new();
+        let mut key = key;
+        loop {
+            match key.wire_type {
+                WireType::Varint => FieldEncoding::Varint.read(self).map(|_| ())?,
+                WireType::I64 => self.skip_proto_bytes(8)?,
+                WireType::I32 => self.skip_proto_bytes(4)?,
+                WireType::Len => {
+                    let len = FieldEncoding::Varint.read(self)?;
+                    self.skip_proto_bytes(len)?
+                }
+                WireType::StartGroup => {
+                    if groups.len() == MAX_GROUP_DEPTH {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!("groups nested deeper than {} levels", MAX_GROUP_DEPTH),
+                        ));
+                    }
+                    groups.push(key.field);
+                }
+                WireType::EndGroup => match groups.pop() {
+                    Some(field) if field == key.field => {}
+                    Some(field) => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!("group {} closed by end group {}", field, key.field),
+                        ))
+                    }
+                    None => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!("unexpected end group {}", key.field),
+                        ))
+                    }
+                },
+            }
+            if groups.is_empty() {
+                return Ok(());
+            }
+            key = self
+                .read_proto_key()?
+                .ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))?;
+        }
+    }
+
+    pub fn read_proto_len_delimited<F, R>(&mut self, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut Stream<'_, T, E>) -> Result<R>,
+    {
+        let len = FieldEncoding::Varint.read(self)?;
+        self.check_proto_len(len)?;
+        let mut chunk = self.borrow_chunk(Some(len))?;
+        let result = f(&mut chunk)?;
+        chunk.seek(SeekFrom::End(0))?;
+        Ok(result)
+    }
+
+    fn skip_proto_bytes(&mut self, len: u64) -> Result<()> {
+        self.check_proto_len(len)?;
+        self.seek(SeekFrom::Current(len as i64)).map(|_| ())
+    }
+
+    fn check_proto_len(&mut self, len: u64) -> Result<()> {
+        let remainder_len = self.remainder_len()?;
+        if len > remainder_len {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!(
+                    "field of {} bytes exceeds the remaining {} bytes",
+                    len, remainder_len
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const MESSAGE: &[u8] = b"\x08\x96\x01\x12\x07\x0a\x05hello\x19\x01\x02\x03\x04\x05\x06\x07\x08\
+        \x23\x08\x01\x24\x2d\x01\x02\x03\x04";
+
+    #[test]
+    fn read_proto_keys() {
+        let mut cursor = Cursor::new(MESSAGE.to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let mut keys = Vec::new();
+        while let Some(key) = stream.read_proto_key().unwrap() {
+            keys.push(key);
+            stream.skip_proto_field(key).unwrap();
+        }
+        assert_eq!(
+            keys,
+            [
+                FieldKey::new(1, WireType::Varint),
+                FieldKey::new(2, WireType::Len),
+                FieldKey::new(3, WireType::I64),
+                FieldKey::new(4, WireType::StartGroup),
+                FieldKey::new(5, WireType::I32),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_proto_len_delimited() {
+        let mut cursor = Cursor::new(MESSAGE.to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let key = stream.read_proto_key().unwrap().unwrap();
+        stream.skip_proto_field(key).unwrap();
+        assert_eq!(
+            stream.read_proto_key().unwrap(),
+            Some(FieldKey::new(2, WireType::Len))
+        );
+        let text = stream
+            .read_proto_len_delimited(|message| {
+                let key = message.read_proto_key()?.unwrap();
+                assert_eq!(key, FieldKey::new(1, WireType::Len));
+                message.read_proto_len_delimited(|text| {
+                    let mut buf = String::new();
+                    text.read_to_string(&mut buf)?;
+                    Ok(buf)
+                })
+            })
+            .unwrap();
+        assert_eq!(text, "hello");
+        assert_eq!(
+            stream.read_proto_key().unwrap(),
+            Some(FieldKey::new(3, WireType::I64))
+        );
+    }
+
+    #[test]
+    fn proto_errors() {
+        let mut cursor = Cursor::new(b"\x0f".to_vec());
+        let err = Stream::new(&mut cursor).read_proto_key().unwrap_err();
+        assert_eq!(err.to_string(), "invalid wire type 7");
+        let mut cursor = Cursor::new(b"\x02".to_vec());
+        let err = Stream::new(&mut cursor).read_proto_key().unwrap_err();
+        assert_eq!(err.to_string(), "invalid field number 0");
+        let mut cursor = Cursor::new(b"\x0a\x05abc".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let key = stream.read_proto_key().unwrap().unwrap();
+        let err = stream.skip_proto_field(key).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let mut cursor = Cursor::new(b"\x0b\x2c".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let key = stream.read_proto_key().unwrap().unwrap();
+        let err = stream.skip_proto_field(key).unwrap_err();
+        assert_eq!(err.to_string(), "group 1 closed by end group 5");
+    }
+
+    #[test]
+    fn proto_group_depth_limit() {
+        let mut nested = vec![0x0b; MAX_GROUP_DEPTH];
+        nested.extend(vec![0x0c; MAX_GROUP_DEPTH]);
+        nested.push(0x10);
+        let mut cursor = Cursor::new(nested);
+        let mut stream = Stream::new(&mut cursor);
+        let key = stream.read_proto_key().unwrap().unwrap();
+        stream.skip_proto_field(key).unwrap();
+        assert_eq!(
+            stream.read_proto_key().unwrap(),
+            Some(FieldKey::new(2, WireType::Varint))
+        );
+        let mut cursor = Cursor::new(vec![0x0b; 100_000]);
+        let mut stream = Stream::new(&mut cursor);
+        let key = stream.read_proto_key().unwrap().unwrap();
+        let err = stream.skip_proto_field(key).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "groups nested deeper than 100 levels");
+    }
+
+    #[test]
+    fn write_field_key() {
+        let mut out = Vec::new();
+        FieldKey::new(1, WireType::Varint)
+            .write_to(&mut out)
+            .unwrap();
+        FieldKey::new(300, WireType::Len)
+            .write_to(&mut out)
+            .unwrap();
+        assert_eq!(out, b"\x08\xe2\x12");
+    }
+}
@@ -0,0 +1,360 @@
+use std::{
+    collections::VecDeque,
+    io::{Error, ErrorKind, Read, Result, Seek, SeekFrom},
+};
+
+const DEFAULT_BLOCK_SIZE: usize = 64 * 1024;
+const DEFAULT_CAPACITY: usize = 16;
+
+pub trait RangeFetch {
+    fn fetch(&mut self, offset: u64, len: usize) -> Result<Vec<u8>>;
+
+    fn size(&mut self) -> Result<u64>;
+}
+
+impl<F> RangeFetch for &mut F
+where
+    F: RangeFetch + ?Sized,
+{
+    fn fetch(&mut self, offset: u64, len: usize) -> Result<Vec<u8>> {
+        (**self).fetch(offset, len)
+    }
+
+    fn size(&mut self) -> Result<u64> {
+        (**self).size()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Eviction {
+    Lru,
+    Fifo,
+}
+
+pub(crate) struct BlockCache {
+    block_size: usize,
+    capacity: usize,
+    eviction: Eviction,
+    blocks: VecDeque<(u64, Vec<u8>)>,
+}
+
+impl BlockCache {
+    pub(crate) fn new() -> BlockCache {
+        BlockCache {
+            block_size: DEFAULT_BLOCK_SIZE,
+            capacity: DEFAULT_CAPACITY,
+            eviction: Eviction::Lru,
+            blocks: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn set_block_size(&mut self, block_size: usize) {
+        assert!(block_size > 0, "block size must not be zero");
+        self.block_size = block_size;
+        self.blocks.clear();
+    }
+
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        assert!(capacity > 0, "cache capacity must not be zero");
+        self.capacity = capacity;
+        while self.blocks.len() > capacity {
+            self.blocks.pop_front();
+        }
+    }
+
+    pub(crate) fn set_eviction(&mut self, eviction: Eviction) {
+        self.eviction = eviction;
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    pub(crate) fn read<L>(&mut self, pos: u64, size: u64, buf: &mut [u8], load: L) -> Result<usize>
+    where
+        L: FnOnce(u64, usize) -> Result<Vec<u8>>,
+    {
+        let index = pos / self.block_size as u64;
+        let offset = (pos % self.block_size as u64) as usize;
+        let block = self.block(index, size, load)?;
+        let len = std::cmp::min(buf.len(), block.len().saturating_sub(offset));
+        buf[..len].copy_from_slice(&block[offset..offset + len]);
+        Ok(len)
+    }
+
+    fn block<L>(&mut self, index: u64, size: u64, load: L) -> Result<&[u8]>
+    where
+        L: FnOnce(u64, usize) -> Result<Vec<u8>>,
+    {
+        match self.blocks.iter().position(|(i, _)| *i == index) {
+            Some(slot) if self.eviction == Eviction::Lru => {
+                let block = self.blocks.remove(slot).unwrap();
+                self.blocks.push_back(block);
+            }
+            Some(slot) => return Ok(&self.blocks[slot].1),
+            None => {
+                let offset = index.checked_mul(self.block_size as u64).ok_or_else(|| {
+                    Error::new(ErrorKind::InvalidInput, "block offset overflows u64")
+                })?;
+                let len =
+                    std::cmp::min(self.block_size as u64, size.saturating_sub(offset)) as usize;
+                let mut data = load(offset, len)?;
+                if data.len() < len {
+                    return Err(Error::new(
+                        ErrorKind::UnexpectedEof,
+                        format!(
+                            "block at offset {} returned {} of {} bytes",
+                            offset,
+                            data.len(),
+                            len
+                        ),
+                    ));
+                }
+                data.truncate(len);
+                if self.blocks.len() == self.capacity {
+                    self.blocks.pop_front();
+                }
+                self.blocks.push_back((index, data));
+            }
+        }
+        Ok(&self.blocks.back().unwrap().1)
+    }
+}
+
+pub struct RangeReader<F> {
+    fetch: F,
+    cache: BlockCache,
+    pos: u64,
+    size: Option<u64>,
+}
+
+impl<F> RangeReader<F>
+where
+    F: RangeFetch,
+{
+    pub fn new(fetch: F) -> RangeReader<F> {
+        RangeReader {
+            fetch,
+            cache: BlockCache::new(),
+            pos: 0,
+            size: None,
+        }
+    }
+
+    pub fn block_size(mut self, block_size: usize) -> RangeReader<F> {
+        self.cache.set_block_size(block_size);
+        self
+    }
+
+    pub fn capacity(mut self, capacity: usize) -> RangeReader<F> {
+        self.cache.set_capacity(capacity);
+        self
+    }
+
+    pub fn eviction(mut self, eviction: Eviction) -> RangeReader<F> {
+        self.cache.set_eviction(eviction);
+        self
+    }
+
+    pub fn cached_blocks(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+        self.size = None;
+    }
+
+    pub fn get_ref(&self) -> &F {
+        &self.fetch
+    }
+
+    pub fn get_mut(&mut self) -> &mut F {
+        &mut self.fetch
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fetch
+    }
+
+    fn size(&mut self) -> Result<u64> {
+        match self.size {
+            Some(size) => Ok(size),
+            None => {
+                let size = self.fetch.size()?;
+                self.size = Some(size);
+                Ok(size)
+            }
+        }
+    }
+}
+
+impl<F> Read for RangeReader<F>
+where
+    F: RangeFetch,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let size = self.size()?;
+        if buf.is_empty() || self.pos >= size {
+            return Ok(0);
+        }
+        let fetch = &mut self.fetch;
+        let len = self
+            .cache
+            .read(self.pos, size, buf, |offset, len| fetch.fetch(offset, len))?;
+        self.pos += len as u64;
+        Ok(len)
+    }
+}
+
+impl<F> Seek for RangeReader<F>
+where
+    F: RangeFetch,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(n) => self.size()?.checked_add_signed(n),
+            SeekFrom::Current(n) => self.pos.checked_add_signed(n),
+        };
+        self.pos = target.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.pos)
+    }
+
+    fn stream_len(&mut self) -> Result<u64> {
+        self.size()
+    }
+
+    fn stream_position(&mut self) -> Result<u64> {
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_num::ReadNum, Stream};
+
+    struct Remote {
+        data: Vec<u8>,
+        fetches: Vec<(u64, usize)>,
+    }
+
+    impl Remote {
+        fn new(len: usize) -> Remote {
+            Remote {
+                data: (0..len).map(|i| i as u8).collect(),
+                fetches: Vec::new(),
+            }
+        }
+    }
+
+    impl RangeFetch for Remote {
+        fn fetch(&mut self, offset: u64, len: usize) -> Result<Vec<u8>> {
+            self.fetches.push((offset, len));
+            let start = std::cmp::min(offset as usize, self.data.len());
+            let end = std::cmp::min(start + len, self.data.len());
+            Ok(self.data[start..end].to_vec())
+        }
+
+        fn size(&mut self) -> Result<u64> {
+            Ok(self.data.len() as u64)
+        }
+    }
+
+    #[test]
+    fn range_reader_caches_blocks() {
+        let mut reader = RangeReader::new(Remote::new(100)).block_size(16);
+        let mut stream = Stream::new(&mut reader).be();
+        stream.seek(SeekFrom::Start(14)).unwrap();
+        assert_eq!(stream.read_u32().unwrap(), 0x0e0f_1011);
+        stream.seek(SeekFrom::Start(2)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(4)).unwrap();
+        assert_eq!(chunk.read_u16().unwrap(), 0x0203);
+        stream.seek(SeekFrom::End(-1)).unwrap();
+        assert_eq!(stream.read_u8().unwrap(), 99);
+        assert_eq!(
+            stream.read_u8().unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+        assert_eq!(reader.cached_blocks(), 3);
+        assert_eq!(reader.get_ref().fetches, [(0, 16), (16, 16), (96, 4)]);
+    }
+
+    #[test]
+    fn range_reader_eviction() {
+        let mut reader = RangeReader::new(Remote::new(64)).block_size(16).capacity(2);
+        let mut byte = [0u8; 1];
+        for pos in [0, 16, 0, 32, 0] {
+            reader.seek(SeekFrom::Start(pos)).unwrap();
+            reader.read_exact(&mut byte).unwrap();
+        }
+        assert_eq!(reader.get_ref().fetches, [(0, 16), (16, 16), (32, 16)]);
+        let mut reader = reader.eviction(Eviction::Fifo);
+        reader.get_mut().fetches.clear();
+        for pos in [48, 0, 32] {
+            reader.seek(SeekFrom::Start(pos)).unwrap();
+            reader.read_exact(&mut byte).unwrap();
+        }
+        assert_eq!(reader.get_ref().fetches, [(48, 16), (32, 16)]);
+    }
+
+    #[test]
+    fn range_reader_fetch_errors() {
+        struct Failing;
+
+        impl RangeFetch for Failing {
+            fn fetch(&mut self, _: u64, _: usize) -> Result<Vec<u8>> {
+                Err(Error::new(ErrorKind::TimedOut, "request timed out"))
+            }
+
+            fn size(&mut self) -> Result<u64> {
+                Ok(8)
+            }
+        }
+
+        let mut reader = RangeReader::new(Failing);
+        let err = reader.read(&mut [0u8; 4]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+        reader.seek(SeekFrom::Start(8)).unwrap();
+        assert_eq!(reader.read(&mut [0u8; 4]).unwrap(), 0);
+        assert_eq!(reader.cached_blocks(), 0);
+    }
+
+    #[test]
+    fn range_reader_rejects_short_blocks() {
+        struct Truncating(Remote);
+
+        impl RangeFetch for Truncating {
+            fn fetch(&mut self, offset: u64, len: usize) -> Result<Vec<u8>> {
+                let mut data = self.0.fetch(offset, len)?;
+                data.truncate(10);
+                Ok(data)
+            }
+
+            fn size(&mut self) -> Result<u64> {
+                Ok(40)
+            }
+        }
+
+        let mut reader = RangeReader::new(Truncating(Remote::new(40))).block_size(16);
+        let err = reader.read(&mut [0u8; 4]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(err.to_string(), "block at offset 0 returned 10 of 16 bytes");
+        assert_eq!(reader.cached_blocks(), 0);
+        reader.seek(SeekFrom::Start(32)).unwrap();
+        let mut tail = Vec::new();
+        reader.read_to_end(&mut tail).unwrap();
+        assert_eq!(tail, (32..40).collect::<Vec<u8>>());
+        assert_eq!(reader.get_ref().0.fetches, [(0, 16), (32, 8)]);
+    }
+}
@@ -0,0 +1,189 @@
+#[cfg(any(unix, windows))]
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+pub trait ReadAt {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize>;
+
+    fn size(&self) -> Result<u64>;
+
+    fn read_exact_at(&self, mut buf: &mut [u8], mut offset: u64) -> Result<()> {
+        while !buf.is_empty() {
+            match self.read_at(buf, offset) {
+                Ok(0) => {
+                    return Err(Error::new(
+                        ErrorKind::UnexpectedEof,
+                        "failed to fill whole buffer",
+                    ))
+                }
+                Ok(len) => {
+                    buf = &mut buf[len..];
+                    offset += len as u64;
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ReadAt for [u8] {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        let start = std::cmp::min(offset, self.len() as u64) as usize;
+        let len = std::cmp::min(buf.len(), self.len() - start);
+        buf[..len].copy_from_slice(&self[start..start + len]);
+        Ok(len)
+    }
+
+    fn size(&self) -> Result<u64> {
+        Ok(self.len() as u64)
+    }
+}
+
+impl ReadAt for Vec<u8> {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        self.as_slice().read_at(buf, offset)
+    }
+
+    fn size(&self) -> Result<u64> {
+        Ok(self.len() as u64)
+    }
+}
+
+#[cfg(any(unix, windows))]
+impl ReadAt for File {
+    #[cfg(unix)]
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        std::os::unix::fs::FileExt::read_at(self, buf, offset)
+    }
+
+    #[cfg(windows)]
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        std::os::windows::fs::FileExt::seek_read(self, buf, offset)
+    }
+
+    fn size(&self) -> Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+}
+
+impl<T> ReadAt for &T
+where
+    T: ReadAt + ?Sized,
+{
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        (**self).read_at(buf, offset)
+    }
+
+    fn size(&self) -> Result<u64> {
+        (**self).size()
+    }
+}
+
+pub struct PositionalReader<'a, B: ?Sized> {
+    backend: &'a B,
+    pos: u64,
+}
+
+impl<'a, B> PositionalReader<'a, B>
+where
+    B: ReadAt + ?Sized,
+{
+    pub fn new(backend: &'a B) -> PositionalReader<'a, B> {
+        PositionalReader { backend, pos: 0 }
+    }
+
+    pub fn get_ref(&self) -> &'a B {
+        self.backend
+    }
+}
+
+impl<B> Read for PositionalReader<'_, B>
+where
+    B: ReadAt + ?Sized,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.backend.read_at(buf, self.pos)?;
+        self.pos += len as u64;
+        Ok(len)
+    }
+}
+
+impl<B> Seek for PositionalReader<'_, B>
+where
+    B: ReadAt + ?Sized,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(n) => self.backend.size()?.checked_add_signed(n),
+            SeekFrom::Current(n) => self.pos.checked_add_signed(n),
+        };
+        self.pos = target.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.pos)
+    }
+
+    fn stream_len(&mut self) -> Result<u64> {
+        self.backend.size()
+    }
+
+    fn stream_position(&mut self) -> Result<u64> {
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::{read_num::ReadNum, Stream};
+
+    #[test]
+    fn read_at_slice() {
+        let data = b"abcdef";
+        let mut buf = [0u8; 4];
+        assert_eq!(data[..].read_at(&mut buf, 4).unwrap(), 2);
+        assert_eq!(&buf[..2], b"ef");
+        assert_eq!(data[..].read_at(&mut buf, 9).unwrap(), 0);
+        data[..].read_exact_at(&mut buf, 1).unwrap();
+        assert_eq!(&buf, b"bcde");
+        let err = data[..].read_exact_at(&mut buf, 3).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn read_at_file() {
+        let path = std::env::temp_dir().join(format!("once_io_read_at_{}", std::process::id()));
+        File::create(&path)
+            .unwrap()
+            .write_all(b"0123456789")
+            .unwrap();
+        let file = File::open(&path).unwrap();
+        let mut buf = [0u8; 3];
+        file.read_exact_at(&mut buf, 6).unwrap();
+        assert_eq!(&buf, b"678");
+        assert_eq!(file.size().unwrap(), 10);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn positional_reader_stream() {
+        let data = vec![0x00, 0x02, 0x12, 0x34, 0xff];
+        let mut reader = PositionalReader::new(&data);
+        let mut stream = Stream::new(&mut reader).be();
+        let len = stream.read_u16().unwrap();
+        let mut chunk = stream.borrow_chunk(Some(len as u64)).unwrap();
+        assert_eq!(chunk.read_u16().unwrap(), 0x1234);
+        assert_eq!(stream.remainder_len().unwrap(), 1);
+        let err = reader.seek(SeekFrom::Current(-6)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -1,6 +1,9 @@
-use std::{
-    io::{Read, Result},
-    mem,
+use alloc::{format, vec};
+use core::{marker::PhantomData, mem};
+
+use crate::{
+    io::{Error, ErrorKind, Read, Result, Seek, SeekFrom},
+    write_num::WriteNum,
 };
 
 pub trait NumReader<T: ?Sized> {
@@ -23,6 +26,10 @@ pub trait NumReader<T: ?Sized> {
     fn read_f64(_: &mut T) -> Result<f64>;
 }
 
+pub trait NumReaderEndianness<T: ?Sized> {
+    fn endianness(_: &T) -> Endianness;
+}
+
 pub trait ReadNum {
     type Reader: NumReader<Self>;
 
@@ -81,16 +88,102 @@ pub trait ReadNum {
     fn read_f64(&mut self) -> Result<f64> {
         Self::Reader::read_f64(self)
     }
+
+    fn read_num<N: Numeric>(&mut self) -> Result<N> {
+        N::read_from(self)
+    }
+
+    fn endianness(&self) -> Endianness
+    where
+        Self::Reader: NumReaderEndianness<Self>,
+    {
+        Self::Reader::endianness(self)
+    }
+}
+
+pub trait Numeric: Sized {
+    fn read_from<R: ReadNum + ?Sized>(reader: &mut R) -> Result<Self>;
+    fn write_to<W: WriteNum + ?Sized>(self, writer: &mut W) -> Result<()>;
+}
+
+macro_rules! impl_numeric {
+    ($type: ty, $read: ident, $write: ident) => {
+        impl Numeric for $type {
+            fn read_from<R: ReadNum + ?Sized>(reader: &mut R) -> Result<$type> {
+                reader.$read()
+            }
+
+            fn write_to<W: WriteNum + ?Sized>(self, writer: &mut W) -> Result<()> {
+                writer.$write(self)
+            }
+        }
+    };
+}
+
+impl_numeric! {u8, read_u8, write_u8}
+impl_numeric! {u16, read_u16, write_u16}
+impl_numeric! {u32, read_u32, write_u32}
+impl_numeric! {u64, read_u64, write_u64}
+impl_numeric! {u128, read_u128, write_u128}
+impl_numeric! {i8, read_i8, write_i8}
+impl_numeric! {i16, read_i16, write_i16}
+impl_numeric! {i32, read_i32, write_i32}
+impl_numeric! {i64, read_i64, write_i64}
+impl_numeric! {i128, read_i128, write_i128}
+impl_numeric! {usize, read_usize, write_usize}
+impl_numeric! {isize, read_isize, write_isize}
+impl_numeric! {f32, read_f32, write_f32}
+impl_numeric! {f64, read_f64, write_f64}
+
+pub trait FromEndianBytes: Numeric {
+    type Bytes: AsMut<[u8]> + Default;
+
+    fn from_endian_bytes(bytes: Self::Bytes, endianness: Endianness) -> Self;
+}
+
+macro_rules! impl_from_endian_bytes {
+    ($type: ty) => {
+        impl FromEndianBytes for $type {
+            type Bytes = [u8; mem::size_of::<$type>()];
+
+            fn from_endian_bytes(bytes: Self::Bytes, endianness: Endianness) -> $type {
+                match endianness {
+                    Endianness::Big => <$type>::from_be_bytes(bytes),
+                    Endianness::Little => <$type>::from_le_bytes(bytes),
+                }
+            }
+        }
+    };
 }
 
-macro_rules! impl_num_reader_be {
+impl_from_endian_bytes! {u8}
+impl_from_endian_bytes! {u16}
+impl_from_endian_bytes! {u32}
+impl_from_endian_bytes! {u64}
+impl_from_endian_bytes! {u128}
+impl_from_endian_bytes! {i8}
+impl_from_endian_bytes! {i16}
+impl_from_endian_bytes! {i32}
+impl_from_endian_bytes! {i64}
+impl_from_endian_bytes! {i128}
+impl_from_endian_bytes! {usize}
+impl_from_endian_bytes! {isize}
+impl_from_endian_bytes! {f32}
+impl_from_endian_bytes! {f64}
+
+pub trait StaticEndianness {
+    fn endianness() -> Endianness;
+}
+
+macro_rules! impl_num_reader {
     ($type: ty, $method: ident) => {
         fn $method(reader: &mut T) -> Result<$type> {
-            let mut buf = [0u8; mem::size_of::<$type>()];
-            match reader.read_exact(&mut buf) {
-                Ok(()) => Ok(<$type>::from_be_bytes(buf)),
-                Err(e) => Err(e),
-            }
+            let mut bytes = <$type as FromEndianBytes>::Bytes::default();
+            reader.read_exact(&mut bytes)?;
+            Ok(<$type>::from_endian_bytes(
+                bytes,
+                <Self as StaticEndianness>::endianness(),
+            ))
         }
     };
 }
@@ -101,32 +194,35 @@ impl<T> NumReader<T> for BigEndianReader
 where
     T: Read,
 {
-    impl_num_reader_be! {u8, read_u8}
-    impl_num_reader_be! {u16, read_u16}
-    impl_num_reader_be! {u32, read_u32}
-    impl_num_reader_be! {u64, read_u64}
-    impl_num_reader_be! {u128, read_u128}
-    impl_num_reader_be! {i8, read_i8}
-    impl_num_reader_be! {i16, read_i16}
-    impl_num_reader_be! {i32, read_i32}
-    impl_num_reader_be! {i64, read_i64}
-    impl_num_reader_be! {i128, read_i128}
-    impl_num_reader_be! {usize, read_usize}
-    impl_num_reader_be! {isize, read_isize}
-    impl_num_reader_be! {f32, read_f32}
-    impl_num_reader_be! {f64, read_f64}
-}
-
-macro_rules! impl_num_reader_le {
-    ($type: ty, $method: ident) => {
-        fn $method(reader: &mut T) -> Result<$type> {
-            let mut buf = [0u8; mem::size_of::<$type>()];
-            match reader.read_exact(&mut buf) {
-                Ok(()) => Ok(<$type>::from_le_bytes(buf)),
-                Err(e) => Err(e),
-            }
-        }
-    };
+    impl_num_reader! {u8, read_u8}
+    impl_num_reader! {u16, read_u16}
+    impl_num_reader! {u32, read_u32}
+    impl_num_reader! {u64, read_u64}
+    impl_num_reader! {u128, read_u128}
+    impl_num_reader! {i8, read_i8}
+    impl_num_reader! {i16, read_i16}
+    impl_num_reader! {i32, read_i32}
+    impl_num_reader! {i64, read_i64}
+    impl_num_reader! {i128, read_i128}
+    impl_num_reader! {usize, read_usize}
+    impl_num_reader! {isize, read_isize}
+    impl_num_reader! {f32, read_f32}
+    impl_num_reader! {f64, read_f64}
+}
+
+impl<T> NumReaderEndianness<T> for BigEndianReader
+where
+    T: ?Sized,
+{
+    fn endianness(_: &T) -> Endianness {
+        Endianness::Big
+    }
+}
+
+impl StaticEndianness for BigEndianReader {
+    fn endianness() -> Endianness {
+        Endianness::Big
+    }
 }
 
 pub struct LittleEndianReader;
@@ -135,54 +231,310 @@ impl<T> NumReader<T> for LittleEndianReader
 where
     T: Read,
 {
-    impl_num_reader_le! {u8, read_u8}
-    impl_num_reader_le! {u16, read_u16}
-    impl_num_reader_le! {u32, read_u32}
-    impl_num_reader_le! {u64, read_u64}
-    impl_num_reader_le! {u128, read_u128}
-    impl_num_reader_le! {i8, read_i8}
-    impl_num_reader_le! {i16, read_i16}
-    impl_num_reader_le! {i32, read_i32}
-    impl_num_reader_le! {i64, read_i64}
-    impl_num_reader_le! {i128, read_i128}
-    impl_num_reader_le! {usize, read_usize}
-    impl_num_reader_le! {isize, read_isize}
-    impl_num_reader_le! {f32, read_f32}
-    impl_num_reader_le! {f64, read_f64}
-}
-
-macro_rules! impl_num_reader_ne {
+    impl_num_reader! {u8, read_u8}
+    impl_num_reader! {u16, read_u16}
+    impl_num_reader! {u32, read_u32}
+    impl_num_reader! {u64, read_u64}
+    impl_num_reader! {u128, read_u128}
+    impl_num_reader! {i8, read_i8}
+    impl_num_reader! {i16, read_i16}
+    impl_num_reader! {i32, read_i32}
+    impl_num_reader! {i64, read_i64}
+    impl_num_reader! {i128, read_i128}
+    impl_num_reader! {usize, read_usize}
+    impl_num_reader! {isize, read_isize}
+    impl_num_reader! {f32, read_f32}
+    impl_num_reader! {f64, read_f64}
+}
+
+impl<T> NumReaderEndianness<T> for LittleEndianReader
+where
+    T: ?Sized,
+{
+    fn endianness(_: &T) -> Endianness {
+        Endianness::Little
+    }
+}
+
+impl StaticEndianness for LittleEndianReader {
+    fn endianness() -> Endianness {
+        Endianness::Little
+    }
+}
+
+pub struct NativeEndianReader;
+
+impl<T> NumReader<T> for NativeEndianReader
+where
+    T: Read,
+{
+    impl_num_reader! {u8, read_u8}
+    impl_num_reader! {u16, read_u16}
+    impl_num_reader! {u32, read_u32}
+    impl_num_reader! {u64, read_u64}
+    impl_num_reader! {u128, read_u128}
+    impl_num_reader! {i8, read_i8}
+    impl_num_reader! {i16, read_i16}
+    impl_num_reader! {i32, read_i32}
+    impl_num_reader! {i64, read_i64}
+    impl_num_reader! {i128, read_i128}
+    impl_num_reader! {usize, read_usize}
+    impl_num_reader! {isize, read_isize}
+    impl_num_reader! {f32, read_f32}
+    impl_num_reader! {f64, read_f64}
+}
+
+impl<T> NumReaderEndianness<T> for NativeEndianReader
+where
+    T: ?Sized,
+{
+    fn endianness(_: &T) -> Endianness {
+        Endianness::native()
+    }
+}
+
+impl StaticEndianness for NativeEndianReader {
+    fn endianness() -> Endianness {
+        Endianness::native()
+    }
+}
+
+pub struct EndianRead<R, E> {
+    inner: R,
+    endianness: PhantomData<E>,
+}
+
+impl<R, E> EndianRead<R, E> {
+    pub fn new(inner: R) -> EndianRead<R, E> {
+        EndianRead {
+            inner,
+            endianness: PhantomData,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, E> Read for EndianRead<R, E>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R, E> Seek for EndianRead<R, E>
+where
+    R: Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl<R, E> ReadNum for EndianRead<R, E>
+where
+    E: NumReader<EndianRead<R, E>>,
+{
+    type Reader = E;
+}
+
+pub trait ReadEndianExt: Read {
+    fn be(&mut self) -> EndianRead<&mut Self, BigEndianReader> {
+        EndianRead::new(self)
+    }
+
+    fn le(&mut self) -> EndianRead<&mut Self, LittleEndianReader> {
+        EndianRead::new(self)
+    }
+
+    fn native(&mut self) -> EndianRead<&mut Self, NativeEndianReader> {
+        EndianRead::new(self)
+    }
+}
+
+impl<R> ReadEndianExt for R where R: Read + ?Sized {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    Big,
+    Little,
+}
+
+impl Endianness {
+    pub fn native() -> Endianness {
+        if cfg!(target_endian = "big") {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        }
+    }
+}
+
+pub struct DynEndianRead<R> {
+    inner: R,
+    endianness: Endianness,
+}
+
+impl<R> DynEndianRead<R> {
+    pub fn new(inner: R, endianness: Endianness) -> DynEndianRead<R> {
+        DynEndianRead { inner, endianness }
+    }
+
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Read for DynEndianRead<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R> Seek for DynEndianRead<R>
+where
+    R: Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+macro_rules! impl_num_reader_dyn {
     ($type: ty, $method: ident) => {
-        fn $method(reader: &mut T) -> Result<$type> {
-            let mut buf = [0u8; mem::size_of::<$type>()];
-            match reader.read_exact(&mut buf) {
-                Ok(()) => Ok(<$type>::from_le_bytes(buf)),
-                Err(e) => Err(e),
+        fn $method(reader: &mut DynEndianRead<R>) -> Result<$type> {
+            match reader.endianness {
+                Endianness::Big => <BigEndianReader as NumReader<R>>::$method(&mut reader.inner),
+                Endianness::Little => {
+                    <LittleEndianReader as NumReader<R>>::$method(&mut reader.inner)
+                }
             }
         }
     };
 }
 
-pub struct NativeEndianReader;
+pub struct DynamicEndianReader;
 
-impl<T> NumReader<T> for NativeEndianReader
+impl<R> NumReader<DynEndianRead<R>> for DynamicEndianReader
 where
-    T: Read,
+    R: Read,
 {
-    impl_num_reader_ne! {u8, read_u8}
-    impl_num_reader_ne! {u16, read_u16}
-    impl_num_reader_ne! {u32, read_u32}
-    impl_num_reader_ne! {u64, read_u64}
-    impl_num_reader_ne! {u128, read_u128}
-    impl_num_reader_ne! {i8, read_i8}
-    impl_num_reader_ne! {i16, read_i16}
-    impl_num_reader_ne! {i32, read_i32}
-    impl_num_reader_ne! {i64, read_i64}
-    impl_num_reader_ne! {i128, read_i128}
-    impl_num_reader_ne! {usize, read_usize}
-    impl_num_reader_ne! {isize, read_isize}
-    impl_num_reader_ne! {f32, read_f32}
-    impl_num_reader_ne! {f64, read_f64}
+    impl_num_reader_dyn! {u8, read_u8}
+    impl_num_reader_dyn! {u16, read_u16}
+    impl_num_reader_dyn! {u32, read_u32}
+    impl_num_reader_dyn! {u64, read_u64}
+    impl_num_reader_dyn! {u128, read_u128}
+    impl_num_reader_dyn! {i8, read_i8}
+    impl_num_reader_dyn! {i16, read_i16}
+    impl_num_reader_dyn! {i32, read_i32}
+    impl_num_reader_dyn! {i64, read_i64}
+    impl_num_reader_dyn! {i128, read_i128}
+    impl_num_reader_dyn! {usize, read_usize}
+    impl_num_reader_dyn! {isize, read_isize}
+    impl_num_reader_dyn! {f32, read_f32}
+    impl_num_reader_dyn! {f64, read_f64}
+}
+
+impl<R> NumReaderEndianness<DynEndianRead<R>> for DynamicEndianReader {
+    fn endianness(reader: &DynEndianRead<R>) -> Endianness {
+        reader.endianness
+    }
+}
+
+impl<R> ReadNum for DynEndianRead<R>
+where
+    R: Read,
+{
+    type Reader = DynamicEndianReader;
+}
+
+pub struct EndianMarker<'m> {
+    pub big: &'m [u8],
+    pub little: &'m [u8],
+}
+
+impl EndianMarker<'static> {
+    pub const UTF16_BOM: EndianMarker<'static> = EndianMarker {
+        big: &[0xfe, 0xff],
+        little: &[0xff, 0xfe],
+    };
+
+    pub const UTF32_BOM: EndianMarker<'static> = EndianMarker {
+        big: &[0x00, 0x00, 0xfe, 0xff],
+        little: &[0xff, 0xfe, 0x00, 0x00],
+    };
+
+    pub const TIFF: EndianMarker<'static> = EndianMarker {
+        big: b"MM",
+        little: b"II",
+    };
+}
+
+impl EndianMarker<'_> {
+    pub fn detect<R>(&self, reader: &mut R, restore_on_mismatch: bool) -> Result<Endianness>
+    where
+        R: Read + Seek + ?Sized,
+    {
+        let start_pos = reader.stream_position()?;
+        let len = core::cmp::max(self.big.len(), self.little.len());
+        let mut buf = vec![0u8; len];
+        let result = match reader.read_exact(&mut buf) {
+            Ok(()) if buf == self.big => Ok(Endianness::Big),
+            Ok(()) if buf == self.little => Ok(Endianness::Little),
+            Ok(()) => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unknown endianness marker {:02x?}", buf),
+            )),
+            Err(e) => Err(e),
+        };
+        if result.is_err() && restore_on_mismatch {
+            reader.seek(SeekFrom::Start(start_pos))?;
+        }
+        result
+    }
+}
+
+pub fn detect_endianness<R>(
+    mut reader: R,
+    marker: &EndianMarker<'_>,
+    restore_on_mismatch: bool,
+) -> Result<(Endianness, DynEndianRead<R>)>
+where
+    R: Read + Seek,
+{
+    let endianness = marker.detect(&mut reader, restore_on_mismatch)?;
+    Ok((endianness, DynEndianRead::new(reader, endianness)))
 }
 
 #[cfg(test)]
@@ -419,4 +771,94 @@ mod tests {
     generate_read_num_ne_test! {read_num_f64_val_ne, f64, 11f64, read_f64}
     generate_read_num_ne_test! {read_num_f64_max_ne, f64, f64::MAX, read_f64}
     generate_read_num_ne_test! {read_num_f64_min_ne, f64, f64::MIN, read_f64}
+
+    #[test]
+    fn native_reader_uses_host_byte_order() {
+        let mut reader = NEReader {
+            inner: Cursor::new([0x01, 0x02, 0x03, 0x04]),
+        };
+        let expected = if cfg!(target_endian = "big") {
+            0x0102_0304
+        } else {
+            0x0403_0201
+        };
+        assert_eq!(reader.read_u32().unwrap(), expected);
+    }
+
+    #[test]
+    fn endian_adapters() {
+        let mut cursor = Cursor::new([0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        assert_eq!(cursor.be().read_u16().unwrap(), 0x0102);
+        assert_eq!(cursor.le().read_u16().unwrap(), 0x0403);
+        let mut reader = cursor.native();
+        assert_eq!(reader.read_u16().unwrap(), u16::from_ne_bytes([0x05, 0x06]));
+        assert!(reader.read_u8().is_err());
+    }
+
+    #[test]
+    fn endian_adapter_by_value() {
+        let mut reader = EndianRead::<_, BigEndianReader>::new(Cursor::new([0x00, 0x2a]));
+        assert_eq!(reader.read_u16().unwrap(), 42);
+        assert_eq!(reader.into_inner().position(), 2);
+    }
+
+    #[test]
+    fn dyn_endian_read() {
+        let data = [0x01, 0x02, 0x01, 0x02];
+        let mut reader = DynEndianRead::new(Cursor::new(data), Endianness::Big);
+        assert_eq!(reader.read_u16().unwrap(), 0x0102);
+        let mut reader = DynEndianRead::new(reader.into_inner(), Endianness::Little);
+        assert_eq!(reader.read_u16().unwrap(), 0x0201);
+    }
+
+    #[test]
+    fn dyn_endian_read_switch_endianness() {
+        let data = [0x00, 0x01, 0x02, 0x00, 0x00, 0x03];
+        let mut reader = DynEndianRead::new(Cursor::new(data), Endianness::Big);
+        assert_eq!(reader.read_u16().unwrap(), 1);
+        reader.set_endianness(Endianness::Little);
+        assert_eq!(reader.endianness(), Endianness::Little);
+        assert_eq!(reader.read_u16().unwrap(), 2);
+        reader.set_endianness(Endianness::Big);
+        assert_eq!(reader.read_u16().unwrap(), 3);
+    }
+
+    #[test]
+    fn detect_endianness_from_tiff_header() {
+        let mut cursor = Cursor::new([b'I', b'I', 0x2a, 0x00]);
+        let (endianness, mut reader) =
+            detect_endianness(&mut cursor, &EndianMarker::TIFF, false).unwrap();
+        assert_eq!(endianness, Endianness::Little);
+        assert_eq!(reader.read_u16().unwrap(), 42);
+        let mut cursor = Cursor::new([b'M', b'M', 0x00, 0x2a]);
+        let (endianness, mut reader) =
+            detect_endianness(&mut cursor, &EndianMarker::TIFF, false).unwrap();
+        assert_eq!(endianness, Endianness::Big);
+        assert_eq!(reader.read_u16().unwrap(), 42);
+    }
+
+    #[test]
+    fn detect_endianness_from_magic_value() {
+        let magic = 0xa1b2c3d4u32;
+        let marker = EndianMarker {
+            big: &magic.to_be_bytes(),
+            little: &magic.to_le_bytes(),
+        };
+        let mut cursor = Cursor::new(magic.to_le_bytes());
+        assert_eq!(
+            marker.detect(&mut cursor, false).unwrap(),
+            Endianness::Little
+        );
+    }
+
+    #[test]
+    fn detect_endianness_restores_position_on_mismatch() {
+        let mut cursor = Cursor::new([0x00, 0x01, 0x02]);
+        let result = EndianMarker::UTF16_BOM.detect(&mut cursor, true);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(cursor.position(), 0);
+        let result = EndianMarker::UTF16_BOM.detect(&mut cursor, false);
+        assert!(result.is_err());
+        assert_eq!(cursor.position(), 2);
+    }
 }
@@ -0,0 +1,280 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use crate::Stream;
+
+pub struct Records<'s, 'a, T, E, F>
+where
+    T: Seek,
+{
+    stream: &'s mut Stream<'a, T, E>,
+    record_size: u64,
+    strict: bool,
+    parse: F,
+    done: bool,
+}
+
+impl<'a, T, E> Stream<'a, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn records<F, R>(&mut self, record_size: u64, parse: F) -> Records<'_, 'a, T, E, F>
+    where
+        F: FnMut(&mut Stream<'_, T, E>) -> Result<R>,
+    {
+        Records {
+            stream: self,
+            record_size,
+            strict: false,
+            parse,
+            done: false,
+        }
+    }
+}
+
+impl<T, E, F> Records<'_, '_, T, E, F>
+where
+    T: Seek,
+{
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    fn next_record<R>(&mut self) -> Result<Option<R>>
+    where
+        T: Read,
+        F: FnMut(&mut Stream<'_, T, E>) -> Result<R>,
+    {
+        if self.record_size == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "record size must not be zero",
+            ));
+        }
+        let remainder_len = self.stream.remainder_len()?;
+        if remainder_len == 0 {
+            return Ok(None);
+        }
+        if remainder_len < self.record_size {
+            if self.strict {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "trailing partial record of {} bytes, expected {}",
+                        remainder_len, self.record_size
+                    ),
+                ));
+            }
+            return Ok(None);
+        }
+        let mut chunk = self.stream.borrow_chunk(Some(self.record_size))?;
+        let record = (self.parse)(&mut chunk)?;
+        chunk.seek(SeekFrom::End(0))?;
+        Ok(Some(record))
+    }
+}
+
+impl<T, E, F, R> Iterator for Records<'_, '_, T, E, F>
+where
+    T: Read + Seek,
+    F: FnMut(&mut Stream<'_, T, E>) -> Result<R>,
+{
+    type Item = Result<R>;
+
+    fn next(&mut self) -> Option<Result<R>> {
+        if self.done {
+            return None;
+        }
+        let result = self.next_record().transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+}
+
+pub struct ParseIter<'s, 'a, T, E, F>
+where
+    T: Seek,
+{
+    stream: &'s mut Stream<'a, T, E>,
+    parse: F,
+    done: bool,
+}
+
+impl<'a, T, E> Stream<'a, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn parse_iter<F, R>(&mut self, parse: F) -> ParseIter<'_, 'a, T, E, F>
+    where
+        F: FnMut(&mut Stream<'a, T, E>) -> Result<Option<R>>,
+    {
+        ParseIter {
+            stream: self,
+            parse,
+            done: false,
+        }
+    }
+}
+
+impl<'a, T, E, F, R> ParseIter<'_, 'a, T, E, F>
+where
+    T: Read + Seek,
+    F: FnMut(&mut Stream<'a, T, E>) -> Result<Option<R>>,
+{
+    fn parse_next(&mut self) -> Result<Option<R>> {
+        let start = self.stream.stream_position()?;
+        let item = (self.parse)(self.stream)?;
+        if item.is_some() && self.stream.stream_position()? == start {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("parser consumed no bytes at offset {}", start),
+            ));
+        }
+        Ok(item)
+    }
+}
+
+impl<'a, T, E, F, R> Iterator for ParseIter<'_, 'a, T, E, F>
+where
+    T: Read + Seek,
+    F: FnMut(&mut Stream<'a, T, E>) -> Result<Option<R>>,
+{
+    type Item = Result<R>;
+
+    fn next(&mut self) -> Option<Result<R>> {
+        if self.done {
+            return None;
+        }
+        let result = match self.stream.remainder_len() {
+            Ok(0) => Ok(None),
+            Ok(_) => self.parse_next(),
+            Err(err) => Err(err),
+        };
+        let result = result.transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::read_num::ReadNum;
+
+    #[test]
+    fn records() {
+        let mut cursor = Cursor::new(b"\x00\x01xx\x00\x02yy\x00\x03".to_vec());
+        let mut stream = Stream::new(&mut cursor).be();
+        let records = stream
+            .records(4, |record| record.read_u16())
+            .collect::<Result<Vec<u16>>>()
+            .unwrap();
+        assert_eq!(records, [1, 2]);
+        assert_eq!(stream.remainder_len().unwrap(), 2);
+    }
+
+    #[test]
+    fn records_strict() {
+        let mut cursor = Cursor::new(b"\x00\x01xx\x00\x02yy\x00\x03".to_vec());
+        let mut stream = Stream::new(&mut cursor).be();
+        let mut records = stream.records(4, |record| record.read_u16()).strict();
+        assert_eq!(records.next().unwrap().unwrap(), 1);
+        assert_eq!(records.next().unwrap().unwrap(), 2);
+        let err = records.next().unwrap().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "trailing partial record of 2 bytes, expected 4"
+        );
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn records_stop_after_error() {
+        let mut cursor = Cursor::new(vec![0u8; 8]);
+        let mut stream = Stream::new(&mut cursor);
+        let mut calls = 0;
+        let results: Vec<Result<()>> = stream
+            .records(2, |_| {
+                calls += 1;
+                Err(Error::from(ErrorKind::InvalidData))
+            })
+            .collect();
+        assert_eq!(results.len(), 1);
+        assert_eq!(calls, 1);
+        let mut stream = Stream::new(&mut cursor);
+        let err = stream.records(0, |_| Ok(())).next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn parse_iter() {
+        let mut cursor = Cursor::new(b"\x02ab\x01c\x00\x01d".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let items = stream
+            .parse_iter(|s| {
+                let len = s.read_u8()?;
+                if len == 0 {
+                    return Ok(None);
+                }
+                let mut buf = vec![0u8; len as usize];
+                s.read_exact(&mut buf)?;
+                Ok(Some(buf))
+            })
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(items, [b"ab".to_vec(), b"c".to_vec()]);
+        assert_eq!(stream.remainder_len().unwrap(), 2);
+    }
+
+    #[test]
+    fn parse_iter_until_exhausted() {
+        let mut cursor = Cursor::new(b"\x00\x01\x00\x02\x00\x03".to_vec());
+        let mut stream = Stream::new(&mut cursor).be();
+        let mut chunk = stream.borrow_chunk(Some(4)).unwrap();
+        let sum: u16 = chunk
+            .parse_iter(|s| s.read_u16().map(Some))
+            .map(Result::unwrap)
+            .sum();
+        assert_eq!(sum, 3);
+    }
+
+    #[test]
+    fn parse_iter_stops_after_error() {
+        let mut cursor = Cursor::new(b"\x00\x01\x00".to_vec());
+        let mut stream = Stream::new(&mut cursor).be();
+        let results: Vec<Result<u16>> = stream.parse_iter(|s| s.read_u16().map(Some)).collect();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap(), &1);
+        assert_eq!(
+            results[1].as_ref().unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn parse_iter_rejects_no_progress() {
+        let mut cursor = Cursor::new(b"\x00\x01\x00\x02".to_vec());
+        let mut stream = Stream::new(&mut cursor).be();
+        let mut calls = 0;
+        let results: Vec<Result<u16>> = stream
+            .parse_iter(|s| {
+                calls += 1;
+                match calls {
+                    1 => s.read_u16().map(Some),
+                    _ => Ok(Some(0)),
+                }
+            })
+            .collect();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap(), &1);
+        let err = results[1].as_ref().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "parser consumed no bytes at offset 2");
+    }
+}
@@ -0,0 +1,337 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+use crate::Stream;
+
+const MAX_DEPTH: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RiffFormat {
+    Riff,
+    Iff,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RiffChunk {
+    pub id: [u8; 4],
+    pub size: u32,
+    pub form_type: Option<[u8; 4]>,
+}
+
+impl RiffFormat {
+    pub fn is_container(self, id: &[u8; 4]) -> bool {
+        match self {
+            RiffFormat::Riff => matches!(id, b"RIFF" | b"LIST"),
+            RiffFormat::Iff => matches!(id, b"FORM" | b"LIST" | b"CAT " | b"PROP"),
+        }
+    }
+
+    fn read_size<R: Read + ?Sized>(self, reader: &mut R) -> Result<u32> {
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?;
+        Ok(match self {
+            RiffFormat::Riff => u32::from_le_bytes(buf),
+            RiffFormat::Iff => u32::from_be_bytes(buf),
+        })
+    }
+
+    fn size_bytes(self, size: u32) -> [u8; 4] {
+        match self {
+            RiffFormat::Riff => size.to_le_bytes(),
+            RiffFormat::Iff => size.to_be_bytes(),
+        }
+    }
+
+    pub fn read_chunk<T, E, F, R>(self, stream: &mut Stream<'_, T, E>, f: F) -> Result<R>
+    where
+        T: Read + Seek,
+        F: FnOnce(&RiffChunk, &mut Stream<'_, T, E>) -> Result<R>,
+    {
+        let mut id = [0u8; 4];
+        stream.read_exact(&mut id)?;
+        let size = self.read_size(stream)?;
+        let remainder_len = stream.remainder_len()?;
+        if size as u64 > remainder_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "chunk {:?} size {} exceeds the remaining {} bytes",
+                    String::from_utf8_lossy(&id),
+                    size,
+                    remainder_len
+                ),
+            ));
+        }
+        let mut chunk = stream.borrow_chunk(Some(size as u64))?;
+        let form_type = if self.is_container(&id) {
+            let mut form_type = [0u8; 4];
+            chunk.read_exact(&mut form_type).map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "container chunk {:?} is too small for a form type",
+                        String::from_utf8_lossy(&id)
+                    ),
+                )
+            })?;
+            Some(form_type)
+        } else {
+            None
+        };
+        let header = RiffChunk {
+            id,
+            size,
+            form_type,
+        };
+        let result = f(&header, &mut chunk)?;
+        chunk.seek(SeekFrom::End(0))?;
+        let padding = std::cmp::min((size & 1) as u64, stream.remainder_len()?);
+        stream.seek(SeekFrom::Current(padding as i64))?;
+        Ok(result)
+    }
+
+    pub fn for_each_chunk<T, E, F>(self, stream: &mut Stream<'_, T, E>, mut f: F) -> Result<()>
+    where
+        T: Read + Seek,
+        F: FnMut(&RiffChunk, &mut Stream<'_, T, E>) -> Result<()>,
+    {
+        while stream.remainder_len()? > 0 {
+            self.read_chunk(stream, &mut f)?;
+        }
+        Ok(())
+    }
+
+    pub fn walk<T, E, F>(self, stream: &mut Stream<'_, T, E>, mut f: F) -> Result<()>
+    where
+        T: Read + Seek,
+        F: FnMut(&[RiffChunk], &mut Stream<'_, T, E>) -> Result<()>,
+    {
+        self.walk_at(stream, &mut Vec::new(), &mut f)
+    }
+
+    fn walk_at<T, E, F>(
+        self,
+        stream: &mut Stream<'_, T, E>,
+        path: &mut Vec<RiffChunk>,
+        f: &mut F,
+    ) -> Result<()>
+    where
+        T: Read + Seek,
+        F: FnMut(&[RiffChunk], &mut Stream<'_, T, E>) -> Result<()>,
+    {
+        self.for_each_chunk(stream, |header, chunk| {
+            path.push(*header);
+            let result = if header.form_type.is_some() {
+                if path.len() > MAX_DEPTH {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("chunks nested deeper than {} levels", MAX_DEPTH),
+                    ));
+                }
+                self.walk_at(chunk, path, f)
+            } else {
+                f(path, chunk)
+            };
+            path.pop();
+            result
+        })
+    }
+}
+
+pub struct RiffWriter<W>
+where
+    W: Write + Seek,
+{
+    inner: W,
+    format: RiffFormat,
+    open: Vec<u64>,
+}
+
+impl<W> RiffWriter<W>
+where
+    W: Write + Seek,
+{
+    pub fn new(inner: W, format: RiffFormat) -> RiffWriter<W> {
+        RiffWriter {
+            inner,
+            format,
+            open: Vec::new(),
+        }
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn begin_chunk(&mut self, id: &[u8; 4]) -> Result<()> {
+        self.inner.write_all(id)?;
+        self.inner.write_all(&[0u8; 4])?;
+        self.open.push(self.inner.stream_position()?);
+        Ok(())
+    }
+
+    pub fn begin_container(&mut self, id: &[u8; 4], form_type: &[u8; 4]) -> Result<()> {
+        self.begin_chunk(id)?;
+        self.inner.write_all(form_type)
+    }
+
+    pub fn write_chunk(&mut self, id: &[u8; 4], data: &[u8]) -> Result<()> {
+        self.begin_chunk(id)?;
+        self.inner.write_all(data)?;
+        self.end_chunk()
+    }
+
+    pub fn end_chunk(&mut self) -> Result<()> {
+        let start = self
+            .open
+            .pop()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "no open chunk to end"))?;
+        let end = self.inner.stream_position()?;
+        let size = u32::try_from(end - start).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "chunk of {} bytes exceeds the 32-bit size field",
+                    end - start
+                ),
+            )
+        })?;
+        self.inner.seek(SeekFrom::Start(start - 4))?;
+        self.inner.write_all(&self.format.size_bytes(size))?;
+        self.inner.seek(SeekFrom::Start(end))?;
+        if size & 1 == 1 {
+            self.inner.write_all(&[0])?;
+        }
+        Ok(())
+    }
+
+    pub fn into_inner(mut self) -> Result<W> {
+        while !self.open.is_empty() {
+            self.end_chunk()?;
+        }
+        Ok(self.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn wave() -> Vec<u8> {
+        let mut writer = RiffWriter::new(Cursor::new(Vec::new()), RiffFormat::Riff);
+        writer.begin_container(b"RIFF", b"WAVE").unwrap();
+        writer.write_chunk(b"fmt ", &[1, 0, 2, 0]).unwrap();
+        writer.begin_container(b"LIST", b"INFO").unwrap();
+        writer.write_chunk(b"INAM", b"odd").unwrap();
+        writer.end_chunk().unwrap();
+        writer.write_chunk(b"data", &[9; 6]).unwrap();
+        writer.into_inner().unwrap().into_inner()
+    }
+
+    #[test]
+    fn riff_writer_sizes_and_padding() {
+        let data = wave();
+        assert_eq!(&data[..12], b"RIFF\x36\x00\x00\x00WAVE");
+        assert_eq!(&data[12..24], b"fmt \x04\x00\x00\x00\x01\x00\x02\x00");
+        assert_eq!(&data[24..36], b"LIST\x10\x00\x00\x00INFO");
+        assert_eq!(&data[36..48], b"INAM\x03\x00\x00\x00odd\x00");
+        assert_eq!(data.len(), 62);
+    }
+
+    #[test]
+    fn for_each_chunk() {
+        let data = wave();
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let mut ids = Vec::new();
+        RiffFormat::Riff
+            .for_each_chunk(&mut stream, |riff, body| {
+                assert_eq!(riff.form_type, Some(*b"WAVE"));
+                RiffFormat::Riff.for_each_chunk(body, |chunk, _| {
+                    ids.push((chunk.id, chunk.size));
+                    Ok(())
+                })
+            })
+            .unwrap();
+        assert_eq!(ids, [(*b"fmt ", 4), (*b"LIST", 16), (*b"data", 6)]);
+    }
+
+    #[test]
+    fn walk_nested_containers() {
+        let data = wave();
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let mut leaves = Vec::new();
+        RiffFormat::Riff
+            .walk(&mut stream, |path, chunk| {
+                let labels: Vec<[u8; 4]> = path.iter().map(|c| c.id).collect();
+                let mut body = Vec::new();
+                chunk.read_to_end(&mut body)?;
+                leaves.push((labels, body));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(leaves.len(), 3);
+        assert_eq!(leaves[1].0, [*b"RIFF", *b"LIST", *b"INAM"]);
+        assert_eq!(leaves[1].1, b"odd");
+        assert_eq!(leaves[2].0, [*b"RIFF", *b"data"]);
+    }
+
+    #[test]
+    fn iff_big_endian_sizes() {
+        let mut writer = RiffWriter::new(Cursor::new(Vec::new()), RiffFormat::Iff);
+        writer.begin_container(b"FORM", b"AIFF").unwrap();
+        writer.write_chunk(b"COMM", &[0; 18]).unwrap();
+        let data = writer.into_inner().unwrap().into_inner();
+        assert_eq!(&data[..12], b"FORM\x00\x00\x00\x1eAIFF");
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let mut found = Vec::new();
+        RiffFormat::Iff
+            .walk(&mut stream, |path, chunk| {
+                found.push((path.last().unwrap().id, chunk.remainder_len()?));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(found, [(*b"COMM", 18)]);
+    }
+
+    #[test]
+    fn chunk_size_over_remainder() {
+        let mut cursor = Cursor::new(b"data\x10\x00\x00\x00abc".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let result = RiffFormat::Riff.read_chunk(&mut stream, |_, _| Ok(()));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let mut cursor = Cursor::new(b"LIST\x02\x00\x00\x00ab".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let result = RiffFormat::Riff.read_chunk(&mut stream, |_, _| Ok(()));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn walk_depth_limit() {
+        let nested = |depth| {
+            let mut writer = RiffWriter::new(Cursor::new(Vec::new()), RiffFormat::Riff);
+            for _ in 0..depth {
+                writer.begin_container(b"LIST", b"DEEP").unwrap();
+            }
+            writer.write_chunk(b"data", b"x").unwrap();
+            writer.into_inner().unwrap().into_inner()
+        };
+        let mut cursor = Cursor::new(nested(MAX_DEPTH));
+        let mut stream = Stream::new(&mut cursor);
+        let mut depth = 0;
+        RiffFormat::Riff
+            .walk(&mut stream, |path, _| {
+                depth = path.len();
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(depth, MAX_DEPTH + 1);
+        let mut cursor = Cursor::new(nested(MAX_DEPTH + 1));
+        let mut stream = Stream::new(&mut cursor);
+        let result = RiffFormat::Riff.walk(&mut stream, |_, _| Ok(()));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
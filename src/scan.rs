@@ -0,0 +1,180 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use memchr::memmem::Finder;
+
+use crate::Stream;
+
+const SCAN_BUFFER_LEN: usize = 8192;
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    fn scan(&mut self, needle: &[u8]) -> Result<Option<u64>> {
+        let finder = Finder::new(needle);
+        let mut window_start = self.stream_position()?;
+        let mut window = Vec::with_capacity(SCAN_BUFFER_LEN + needle.len());
+        loop {
+            if let Some(index) = finder.find(&window) {
+                return Ok(Some(window_start + index as u64));
+            }
+            let keep = std::cmp::min(window.len(), needle.len().saturating_sub(1));
+            window_start += (window.len() - keep) as u64;
+            window.drain(..window.len() - keep);
+            let filled = window.len();
+            window.resize(filled + SCAN_BUFFER_LEN, 0);
+            let n = self.read(&mut window[filled..])?;
+            window.truncate(filled + n);
+            if n == 0 {
+                return Ok(None);
+            }
+        }
+    }
+
+    pub fn find(&mut self, needle: &[u8]) -> Result<Option<u64>> {
+        let start = self.stream_position()?;
+        let found = self.scan(needle)?;
+        self.seek(SeekFrom::Start(found.unwrap_or(start)))?;
+        Ok(found)
+    }
+
+    pub fn search(&mut self, needle: &[u8]) -> Result<Vec<u64>> {
+        if needle.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "search needle must not be empty",
+            ));
+        }
+        let finder = Finder::new(needle);
+        let start = self.stream_position()?;
+        let mut window_start = start;
+        let mut window = Vec::with_capacity(SCAN_BUFFER_LEN + needle.len());
+        let mut matches = Vec::new();
+        loop {
+            let mut pos = 0;
+            while let Some(index) = finder.find(&window[pos..]) {
+                matches.push(window_start + (pos + index) as u64);
+                pos += index + 1;
+            }
+            let keep = std::cmp::min(window.len(), needle.len() - 1);
+            window_start += (window.len() - keep) as u64;
+            window.drain(..window.len() - keep);
+            let filled = window.len();
+            window.resize(filled + SCAN_BUFFER_LEN, 0);
+            let n = self.read(&mut window[filled..])?;
+            window.truncate(filled + n);
+            if n == 0 {
+                break;
+            }
+        }
+        self.seek(SeekFrom::Start(start))?;
+        Ok(matches)
+    }
+
+    pub fn resync_to(&mut self, needle: &[u8]) -> Result<Option<u64>> {
+        let start = self.stream_position()?;
+        match self.scan(needle)? {
+            Some(found) => {
+                self.seek(SeekFrom::Start(found + needle.len() as u64))?;
+                Ok(Some(found - start))
+            }
+            None => {
+                self.seek(SeekFrom::End(0))?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use super::*;
+    use crate::read_num::ReadNum;
+
+    #[test]
+    fn find() {
+        let mut cursor = Cursor::new(b"garbage\xff\xfbframe\xff\xfbnext".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        assert_eq!(stream.find(b"\xff\xfb").unwrap(), Some(7));
+        assert_eq!(stream.stream_position().unwrap(), 7);
+        assert_eq!(stream.find(b"\xff\xfb").unwrap(), Some(7));
+        assert_eq!(stream.find(b"").unwrap(), Some(7));
+        assert_eq!(stream.find(b"missing").unwrap(), None);
+        assert_eq!(stream.stream_position().unwrap(), 7);
+    }
+
+    #[test]
+    fn resync_to() {
+        let mut cursor = Cursor::new(b"junk\x47\x01\x02\x47\x03".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        assert_eq!(stream.resync_to(b"\x47").unwrap(), Some(4));
+        assert_eq!(stream.read_u8().unwrap(), 0x01);
+        assert_eq!(stream.resync_to(b"\x47").unwrap(), Some(1));
+        assert_eq!(stream.read_u8().unwrap(), 0x03);
+        assert_eq!(stream.resync_to(b"\x47").unwrap(), None);
+        let result = stream.read_u8();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn find_across_buffer_boundary() {
+        let mut data = vec![0u8; SCAN_BUFFER_LEN * 2 + 3];
+        let at = SCAN_BUFFER_LEN - 2;
+        data[at..at + 4].copy_from_slice(b"SYNC");
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        assert_eq!(stream.find(b"SYNC").unwrap(), Some(at as u64));
+        stream.seek(SeekFrom::Start(at as u64 + 1)).unwrap();
+        assert_eq!(stream.find(b"SYNC").unwrap(), None);
+    }
+
+    #[test]
+    fn search_all_matches() {
+        let mut cursor = Cursor::new(b"xxaaaxxaxaa".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(1)).unwrap();
+        assert_eq!(stream.search(b"aa").unwrap(), [2, 3, 9]);
+        assert_eq!(stream.search(b"xa").unwrap(), [1, 6, 8]);
+        assert_eq!(stream.search(b"missing").unwrap(), []);
+        assert_eq!(stream.stream_position().unwrap(), 1);
+        let result = stream.search(b"");
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn search_across_buffer_boundaries() {
+        let mut data = vec![0u8; SCAN_BUFFER_LEN * 3];
+        let offsets = [
+            0,
+            SCAN_BUFFER_LEN - 3,
+            SCAN_BUFFER_LEN * 2 - 1,
+            SCAN_BUFFER_LEN * 3 - 4,
+        ];
+        for offset in offsets {
+            data[offset..offset + 4].copy_from_slice(b"SYNC");
+        }
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let expected = offsets.map(|offset| offset as u64);
+        assert_eq!(stream.search(b"SYNC").unwrap(), expected);
+        let mut chunk = stream
+            .borrow_chunk(Some(SCAN_BUFFER_LEN as u64 * 2))
+            .unwrap();
+        assert_eq!(chunk.search(b"SYNC").unwrap(), expected[..2]);
+    }
+
+    #[test]
+    fn find_within_chunk() {
+        let mut cursor = Cursor::new(b"..abcSYNCxyz".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(2)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(5)).unwrap();
+        assert_eq!(chunk.find(b"SYNC").unwrap(), None);
+        assert_eq!(chunk.find(b"abc").unwrap(), Some(0));
+        let mut chunk = stream.borrow_chunk(Some(7)).unwrap();
+        assert_eq!(chunk.resync_to(b"SYNC").unwrap(), Some(3));
+        assert_eq!(chunk.remainder_len().unwrap(), 0);
+    }
+}
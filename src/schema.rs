@@ -0,0 +1,366 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use crate::{
+    magic::ReadMagic,
+    read_num::{DynEndianRead, Endianness, ReadNum},
+    Stream,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Length {
+    Fixed(u64),
+    Prefix(SchemaType),
+    Field(String),
+    Remaining,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemaType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+    Bytes(Box<Length>),
+    Str(Box<Length>),
+    Array(Box<SchemaType>, Box<Length>),
+    Record(Schema),
+    Chunk(Box<Length>, Schema),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Item {
+    Field(String, SchemaType),
+    Pad(u64),
+    Magic(Vec<u8>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schema {
+    endianness: Endianness,
+    items: Vec<Item>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    Bytes(Vec<u8>),
+    Str(String),
+    Array(Vec<Value>),
+    Record(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Value::U8(v) => Some(v.into()),
+            Value::U16(v) => Some(v.into()),
+            Value::U32(v) => Some(v.into()),
+            Value::U64(v) => Some(v),
+            Value::I8(v) => v.try_into().ok(),
+            Value::I16(v) => v.try_into().ok(),
+            Value::I32(v) => v.try_into().ok(),
+            Value::I64(v) => v.try_into().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Record(fields) => lookup(fields, name),
+            _ => None,
+        }
+    }
+}
+
+fn lookup<'v>(fields: &'v [(String, Value)], name: &str) -> Option<&'v Value> {
+    fields
+        .iter()
+        .rev()
+        .find(|(field, _)| field == name)
+        .map(|(_, value)| value)
+}
+
+impl Schema {
+    pub fn new(endianness: Endianness) -> Schema {
+        Schema {
+            endianness,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn field(mut self, name: impl Into<String>, ty: SchemaType) -> Schema {
+        self.items.push(Item::Field(name.into(), ty));
+        self
+    }
+
+    pub fn pad(mut self, len: u64) -> Schema {
+        self.items.push(Item::Pad(len));
+        self
+    }
+
+    pub fn magic(mut self, magic: &[u8]) -> Schema {
+        self.items.push(Item::Magic(magic.to_vec()));
+        self
+    }
+
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    pub fn parse<T, E>(&self, stream: &mut Stream<'_, T, E>) -> Result<Value>
+    where
+        T: Read + Seek,
+    {
+        Ok(Value::Record(self.parse_fields(stream)?))
+    }
+
+    fn parse_fields<T, E>(&self, stream: &mut Stream<'_, T, E>) -> Result<Vec<(String, Value)>>
+    where
+        T: Read + Seek,
+    {
+        let mut fields = Vec::new();
+        for item in &self.items {
+            match item {
+                Item::Field(name, ty) => {
+                    let value = self.parse_value(ty, stream, &fields)?;
+                    fields.push((name.clone(), value));
+                }
+                Item::Pad(len) => {
+                    check_remaining(stream, *len)?;
+                    stream.seek(SeekFrom::Current(*len as i64))?;
+                }
+                Item::Magic(magic) => stream.expect_magic(magic)?,
+            }
+        }
+        Ok(fields)
+    }
+
+    fn parse_value<T, E>(
+        &self,
+        ty: &SchemaType,
+        stream: &mut Stream<'_, T, E>,
+        fields: &[(String, Value)],
+    ) -> Result<Value>
+    where
+        T: Read + Seek,
+    {
+        let mut reader = DynEndianRead::new(&mut *stream, self.endianness);
+        Ok(match ty {
+            SchemaType::U8 => Value::U8(reader.read_u8()?),
+            SchemaType::U16 => Value::U16(reader.read_u16()?),
+            SchemaType::U32 => Value::U32(reader.read_u32()?),
+            SchemaType::U64 => Value::U64(reader.read_u64()?),
+            SchemaType::I8 => Value::I8(reader.read_i8()?),
+            SchemaType::I16 => Value::I16(reader.read_i16()?),
+            SchemaType::I32 => Value::I32(reader.read_i32()?),
+            SchemaType::I64 => Value::I64(reader.read_i64()?),
+            SchemaType::F32 => Value::F32(reader.read_f32()?),
+            SchemaType::F64 => Value::F64(reader.read_f64()?),
+            SchemaType::Bytes(len) => Value::Bytes(self.read_bytes(len, stream, fields)?),
+            SchemaType::Str(len) => {
+                let bytes = self.read_bytes(len, stream, fields)?;
+                Value::Str(
+                    String::from_utf8(bytes)
+                        .map_err(|err| Error::new(ErrorKind::InvalidData, err))?,
+                )
+            }
+            SchemaType::Array(item, len) => {
+                let count = self.resolve_len(len, stream, fields)?;
+                let mut items = Vec::new();
+                for _ in 0..count {
+                    items.push(self.parse_value(item, stream, fields)?);
+                }
+                Value::Array(items)
+            }
+            SchemaType::Record(schema) => schema.parse(stream)?,
+            SchemaType::Chunk(len, schema) => {
+                let len = self.resolve_len(len, stream, fields)?;
+                check_remaining(stream, len)?;
+                let mut chunk = stream.borrow_chunk(Some(len))?;
+                let value = schema.parse(&mut chunk)?;
+                chunk.seek(SeekFrom::End(0))?;
+                value
+            }
+        })
+    }
+
+    fn read_bytes<T, E>(
+        &self,
+        len: &Length,
+        stream: &mut Stream<'_, T, E>,
+        fields: &[(String, Value)],
+    ) -> Result<Vec<u8>>
+    where
+        T: Read + Seek,
+    {
+        let len = self.resolve_len(len, stream, fields)?;
+        check_remaining(stream, len)?;
+        let mut bytes = vec![0u8; len as usize];
+        stream.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn resolve_len<T, E>(
+        &self,
+        len: &Length,
+        stream: &mut Stream<'_, T, E>,
+        fields: &[(String, Value)],
+    ) -> Result<u64>
+    where
+        T: Read + Seek,
+    {
+        match len {
+            Length::Fixed(len) => Ok(*len),
+            Length::Prefix(ty) => self
+                .parse_value(ty, stream, fields)?
+                .as_u64()
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("length prefix of type {:?} is not a valid length", ty),
+                    )
+                }),
+            Length::Field(name) => match lookup(fields, name) {
+                Some(value) => value.as_u64().ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("field {} is not a valid length", name),
+                    )
+                }),
+                None => Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("length refers to unknown field {}", name),
+                )),
+            },
+            Length::Remaining => stream.remainder_len(),
+        }
+    }
+}
+
+fn check_remaining<T, E>(stream: &mut Stream<'_, T, E>, len: u64) -> Result<()>
+where
+    T: Seek,
+{
+    let remainder_len = stream.remainder_len()?;
+    if len > remainder_len {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "length {} exceeds the remaining {} bytes",
+                len, remainder_len
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn header_schema() -> Schema {
+        let entry = Schema::new(Endianness::Little)
+            .field("id", SchemaType::U8)
+            .field("value", SchemaType::I16);
+        let trailer = Schema::new(Endianness::Big)
+            .field("rest", SchemaType::Bytes(Box::new(Length::Remaining)));
+        Schema::new(Endianness::Big)
+            .magic(b"SCH")
+            .field("version", SchemaType::U16)
+            .pad(1)
+            .field(
+                "name",
+                SchemaType::Str(Box::new(Length::Prefix(SchemaType::U8))),
+            )
+            .field("count", SchemaType::U8)
+            .field(
+                "entries",
+                SchemaType::Array(
+                    Box::new(SchemaType::Record(entry)),
+                    Box::new(Length::Field("count".into())),
+                ),
+            )
+            .field(
+                "trailer",
+                SchemaType::Chunk(Box::new(Length::Fixed(3)), trailer),
+            )
+            .field("end", SchemaType::U8)
+    }
+
+    #[test]
+    fn schema_parse() {
+        let data = b"SCH\x00\x02\xff\x02hi\x02\x01\xfe\xff\x02\x10\x00abc\x7f";
+        let mut cursor = Cursor::new(data.to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let value = header_schema().parse(&mut stream).unwrap();
+        assert_eq!(value.get("version"), Some(&Value::U16(2)));
+        assert_eq!(value.get("name"), Some(&Value::Str("hi".into())));
+        assert_eq!(
+            value.get("entries"),
+            Some(&Value::Array(vec![
+                Value::Record(vec![
+                    ("id".into(), Value::U8(1)),
+                    ("value".into(), Value::I16(-2))
+                ]),
+                Value::Record(vec![
+                    ("id".into(), Value::U8(2)),
+                    ("value".into(), Value::I16(16))
+                ]),
+            ]))
+        );
+        assert_eq!(
+            value.get("trailer").and_then(|t| t.get("rest")),
+            Some(&Value::Bytes(b"abc".to_vec()))
+        );
+        assert_eq!(value.get("end").and_then(Value::as_u64), Some(0x7f));
+        assert_eq!(stream.remainder_len().unwrap(), 0);
+    }
+
+    #[test]
+    fn schema_parse_errors() {
+        let mut cursor = Cursor::new(b"SCX".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let err = header_schema().parse(&mut stream).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        let mut cursor = Cursor::new(b"SCH\x00\x02\xff\x09hi".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let err = header_schema().parse(&mut stream).unwrap_err();
+        assert_eq!(err.to_string(), "length 9 exceeds the remaining 2 bytes");
+
+        let schema = Schema::new(Endianness::Big).field(
+            "data",
+            SchemaType::Bytes(Box::new(Length::Field("missing".into()))),
+        );
+        let mut cursor = Cursor::new(Vec::new());
+        let err = schema.parse(&mut Stream::new(&mut cursor)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        let schema = Schema::new(Endianness::Big)
+            .field("len", SchemaType::I8)
+            .field(
+                "data",
+                SchemaType::Bytes(Box::new(Length::Field("len".into()))),
+            );
+        let mut cursor = Cursor::new(vec![0xff]);
+        let err = schema.parse(&mut Stream::new(&mut cursor)).unwrap_err();
+        assert_eq!(err.to_string(), "field len is not a valid length");
+    }
+}
@@ -0,0 +1,77 @@
+use std::io::Result;
+
+use crate::read_num::{Numeric, ReadNum};
+
+pub trait ReadSentinel: ReadNum {
+    fn read_option<N: Numeric + PartialEq>(&mut self, sentinel: N) -> Result<Option<N>> {
+        let value = N::read_from(self)?;
+        Ok(if value == sentinel { None } else { Some(value) })
+    }
+
+    fn read_option_u8(&mut self, sentinel: u8) -> Result<Option<u8>> {
+        self.read_option(sentinel)
+    }
+
+    fn read_option_u16(&mut self, sentinel: u16) -> Result<Option<u16>> {
+        self.read_option(sentinel)
+    }
+
+    fn read_option_u32(&mut self, sentinel: u32) -> Result<Option<u32>> {
+        self.read_option(sentinel)
+    }
+
+    fn read_option_u64(&mut self, sentinel: u64) -> Result<Option<u64>> {
+        self.read_option(sentinel)
+    }
+
+    fn read_option_i8(&mut self, sentinel: i8) -> Result<Option<i8>> {
+        self.read_option(sentinel)
+    }
+
+    fn read_option_i16(&mut self, sentinel: i16) -> Result<Option<i16>> {
+        self.read_option(sentinel)
+    }
+
+    fn read_option_i32(&mut self, sentinel: i32) -> Result<Option<i32>> {
+        self.read_option(sentinel)
+    }
+
+    fn read_option_i64(&mut self, sentinel: i64) -> Result<Option<i64>> {
+        self.read_option(sentinel)
+    }
+}
+
+impl<R> ReadSentinel for R where R: ReadNum + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use super::*;
+    use crate::read_num::ReadEndianExt;
+
+    #[test]
+    fn read_option_with_max_sentinel() {
+        let mut cursor = Cursor::new([0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x10]);
+        let mut reader = cursor.be();
+        assert_eq!(reader.read_option_u32(u32::MAX).unwrap(), None);
+        assert_eq!(reader.read_option_u32(u32::MAX).unwrap(), Some(16));
+    }
+
+    #[test]
+    fn read_option_with_zero_sentinel() {
+        let mut cursor = Cursor::new([0x00, 0x00, 0x07, 0x00]);
+        let mut reader = cursor.le();
+        assert_eq!(reader.read_option_u16(0).unwrap(), None);
+        assert_eq!(reader.read_option::<u16>(0).unwrap(), Some(7));
+    }
+
+    #[test]
+    fn read_option_signed() {
+        let mut cursor = Cursor::new((-1i64).to_le_bytes());
+        let mut reader = cursor.le();
+        assert_eq!(reader.read_option_i64(-1).unwrap(), None);
+        let result = reader.read_option_i64(-1);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}
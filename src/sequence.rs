@@ -0,0 +1,303 @@
+use std::{
+    collections::BTreeMap,
+    io::{Error, ErrorKind, Read, Result, Write},
+    ops::Range,
+};
+
+use crate::{
+    frame::{FrameFormat, FrameReader, FrameWriter},
+    tlv::FieldEncoding,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SequenceStatus {
+    InOrder,
+    Gap { expected: u64, found: u64 },
+    Duplicate,
+    Reordered,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SequenceTracker {
+    next: Option<u64>,
+    missing: BTreeMap<u64, u64>,
+    gaps: u64,
+    duplicates: u64,
+    reordered: u64,
+}
+
+impl SequenceTracker {
+    pub fn new() -> SequenceTracker {
+        SequenceTracker::default()
+    }
+
+    pub fn starting_at(first: u64) -> SequenceTracker {
+        SequenceTracker {
+            next: Some(first),
+            ..SequenceTracker::default()
+        }
+    }
+
+    pub fn observe(&mut self, seq: u64) -> SequenceStatus {
+        let expected = *self.next.get_or_insert(seq);
+        if seq == expected {
+            self.next = Some(seq.wrapping_add(1));
+            return SequenceStatus::InOrder;
+        }
+        if seq > expected {
+            self.missing.insert(expected, seq);
+            self.gaps += 1;
+            self.next = Some(seq.wrapping_add(1));
+            return SequenceStatus::Gap {
+                expected,
+                found: seq,
+            };
+        }
+        let range = self
+            .missing
+            .range(..=seq)
+            .next_back()
+            .map(|(start, end)| (*start, *end))
+            .filter(|(_, end)| seq < *end);
+        match range {
+            Some((start, end)) => {
+                self.missing.remove(&start);
+                if start < seq {
+                    self.missing.insert(start, seq);
+                }
+                if seq + 1 < end {
+                    self.missing.insert(seq + 1, end);
+                }
+                self.reordered += 1;
+                SequenceStatus::Reordered
+            }
+            None => {
+                self.duplicates += 1;
+                SequenceStatus::Duplicate
+            }
+        }
+    }
+
+    pub fn next_expected(&self) -> Option<u64> {
+        self.next
+    }
+
+    pub fn missing(&self) -> impl Iterator<Item = Range<u64>> + '_ {
+        self.missing.iter().map(|(start, end)| *start..*end)
+    }
+
+    pub fn gaps(&self) -> u64 {
+        self.gaps
+    }
+
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates
+    }
+
+    pub fn reordered(&self) -> u64 {
+        self.reordered
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SequencedRecord {
+    pub seq: u64,
+    pub status: SequenceStatus,
+    pub payload: Vec<u8>,
+}
+
+pub struct SequencedReader<R>
+where
+    R: Read,
+{
+    frames: FrameReader<R>,
+    seq: FieldEncoding,
+    tracker: SequenceTracker,
+}
+
+impl<R> SequencedReader<R>
+where
+    R: Read,
+{
+    pub fn new(inner: R, format: FrameFormat, seq: FieldEncoding) -> SequencedReader<R> {
+        SequencedReader {
+            frames: FrameReader::new(inner, format),
+            seq,
+            tracker: SequenceTracker::new(),
+        }
+    }
+
+    pub fn starting_at(mut self, first: u64) -> SequencedReader<R> {
+        self.tracker = SequenceTracker::starting_at(first);
+        self
+    }
+
+    pub fn tracker(&self) -> &SequenceTracker {
+        &self.tracker
+    }
+
+    pub fn into_inner(self) -> R {
+        self.frames.into_inner()
+    }
+
+    pub fn read_record(&mut self) -> Result<Option<SequencedRecord>> {
+        let frame = match self.frames.read_frame()? {
+            Some(frame) => frame,
+            None => return Ok(None),
+        };
+        let mut payload = &frame[..];
+        let seq = self.seq.read(&mut payload).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("frame of {} bytes has no sequence number", frame.len()),
+            )
+        })?;
+        let status = self.tracker.observe(seq);
+        Ok(Some(SequencedRecord {
+            seq,
+            status,
+            payload: payload.to_vec(),
+        }))
+    }
+}
+
+impl<R> Iterator for SequencedReader<R>
+where
+    R: Read,
+{
+    type Item = Result<SequencedRecord>;
+
+    fn next(&mut self) -> Option<Result<SequencedRecord>> {
+        self.read_record().transpose()
+    }
+}
+
+pub struct SequencedWriter<W>
+where
+    W: Write,
+{
+    frames: FrameWriter<W>,
+    seq: FieldEncoding,
+    next: u64,
+}
+
+impl<W> SequencedWriter<W>
+where
+    W: Write,
+{
+    pub fn new(inner: W, format: FrameFormat, seq: FieldEncoding) -> SequencedWriter<W> {
+        SequencedWriter {
+            frames: FrameWriter::new(inner, format),
+            seq,
+            next: 0,
+        }
+    }
+
+    pub fn starting_at(mut self, first: u64) -> SequencedWriter<W> {
+        self.next = first;
+        self
+    }
+
+    pub fn next_seq(&self) -> u64 {
+        self.next
+    }
+
+    pub fn into_inner(self) -> W {
+        self.frames.into_inner()
+    }
+
+    pub fn write_record(&mut self, payload: &[u8]) -> Result<u64> {
+        let seq = self.next;
+        self.write_record_with_seq(seq, payload)?;
+        self.next = seq.wrapping_add(1);
+        Ok(seq)
+    }
+
+    pub fn write_record_with_seq(&mut self, seq: u64, payload: &[u8]) -> Result<()> {
+        let mut frame = Vec::with_capacity(self.seq.encoded_len(seq) + payload.len());
+        self.seq.write(&mut frame, seq)?;
+        frame.extend_from_slice(payload);
+        self.frames.write_frame(&frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::read_num::Endianness;
+
+    #[test]
+    fn sequence_tracker() {
+        let mut tracker = SequenceTracker::new();
+        assert_eq!(tracker.observe(10), SequenceStatus::InOrder);
+        assert_eq!(tracker.observe(11), SequenceStatus::InOrder);
+        assert_eq!(
+            tracker.observe(15),
+            SequenceStatus::Gap {
+                expected: 12,
+                found: 15
+            }
+        );
+        assert_eq!(tracker.missing().next(), Some(12..15));
+        assert_eq!(tracker.observe(13), SequenceStatus::Reordered);
+        assert_eq!(tracker.missing().collect::<Vec<_>>(), [12..13, 14..15]);
+        assert_eq!(tracker.observe(13), SequenceStatus::Duplicate);
+        assert_eq!(tracker.observe(15), SequenceStatus::Duplicate);
+        assert_eq!(tracker.observe(16), SequenceStatus::InOrder);
+        assert_eq!(tracker.next_expected(), Some(17));
+        assert_eq!(
+            (tracker.gaps(), tracker.duplicates(), tracker.reordered()),
+            (1, 2, 1)
+        );
+    }
+
+    #[test]
+    fn sequenced_round_trip() {
+        let format = FrameFormat::new(FieldEncoding::U16(Endianness::Big));
+        let seq = FieldEncoding::U32(Endianness::Big);
+        let mut writer = SequencedWriter::new(Vec::new(), format, seq).starting_at(1);
+        assert_eq!(writer.write_record(b"a").unwrap(), 1);
+        assert_eq!(writer.write_record(b"b").unwrap(), 2);
+        writer.write_record_with_seq(5, b"e").unwrap();
+        writer.write_record_with_seq(3, b"c").unwrap();
+        writer.write_record_with_seq(3, b"c").unwrap();
+        let data = writer.into_inner();
+        assert_eq!(&data[..7], b"\x00\x05\x00\x00\x00\x01a");
+        let reader = SequencedReader::new(Cursor::new(data), format, seq).starting_at(1);
+        let statuses = reader
+            .map(|record| record.map(|r| (r.seq, r.status, r.payload)))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            statuses,
+            [
+                (1, SequenceStatus::InOrder, b"a".to_vec()),
+                (2, SequenceStatus::InOrder, b"b".to_vec()),
+                (
+                    5,
+                    SequenceStatus::Gap {
+                        expected: 3,
+                        found: 5
+                    },
+                    b"e".to_vec()
+                ),
+                (3, SequenceStatus::Reordered, b"c".to_vec()),
+                (3, SequenceStatus::Duplicate, b"c".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn sequenced_frame_too_short() {
+        let format = FrameFormat::new(FieldEncoding::U8);
+        let mut reader = SequencedReader::new(
+            Cursor::new(b"\x01\x00"),
+            format,
+            FieldEncoding::U16(Endianness::Little),
+        );
+        let err = reader.read_record().unwrap_err();
+        assert_eq!(err.to_string(), "frame of 1 bytes has no sequence number");
+    }
+}
@@ -0,0 +1,897 @@
+use std::{
+    error, fmt,
+    io::{self, ErrorKind, Read, Write},
+};
+
+use ::serde::{
+    de::{
+        self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess,
+        SeqAccess, VariantAccess, Visitor,
+    },
+    ser::{
+        self, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+        SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
+    },
+};
+
+use crate::{
+    layout::Counted,
+    read_num::{Endianness, ReadNum},
+    tlv::FieldEncoding,
+    write_num::WriteNum,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StrEncoding {
+    LenPrefixed,
+    NulTerminated,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BinaryConfig {
+    len_prefix: FieldEncoding,
+    variant_tag: FieldEncoding,
+    strings: StrEncoding,
+    align: u64,
+}
+
+impl BinaryConfig {
+    pub fn new() -> BinaryConfig {
+        BinaryConfig {
+            len_prefix: FieldEncoding::U32(Endianness::Little),
+            variant_tag: FieldEncoding::U32(Endianness::Little),
+            strings: StrEncoding::LenPrefixed,
+            align: 1,
+        }
+    }
+
+    pub fn be_aligned4() -> BinaryConfig {
+        BinaryConfig::new()
+            .len_prefix(FieldEncoding::U32(Endianness::Big))
+            .variant_tag(FieldEncoding::U32(Endianness::Big))
+            .align(4)
+    }
+
+    pub fn len_prefix(mut self, len_prefix: FieldEncoding) -> BinaryConfig {
+        self.len_prefix = len_prefix;
+        self
+    }
+
+    pub fn variant_tag(mut self, variant_tag: FieldEncoding) -> BinaryConfig {
+        self.variant_tag = variant_tag;
+        self
+    }
+
+    pub fn strings(mut self, strings: StrEncoding) -> BinaryConfig {
+        self.strings = strings;
+        self
+    }
+
+    pub fn align(mut self, align: u64) -> BinaryConfig {
+        assert!(align > 0, "alignment must not be zero");
+        self.align = align;
+        self
+    }
+}
+
+impl Default for BinaryConfig {
+    fn default() -> BinaryConfig {
+        BinaryConfig::new()
+    }
+}
+
+#[derive(Debug)]
+pub struct Error(io::Error);
+
+impl Error {
+    pub fn kind(&self) -> ErrorKind {
+        self.0.kind()
+    }
+
+    pub fn into_io(self) -> io::Error {
+        self.0
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error(err)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> io::Error {
+        err.0
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Error {
+        Error(io::Error::new(ErrorKind::InvalidData, msg.to_string()))
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Error {
+        Error(io::Error::new(ErrorKind::InvalidInput, msg.to_string()))
+    }
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+fn invalid_data(msg: String) -> Error {
+    Error(io::Error::new(ErrorKind::InvalidData, msg))
+}
+
+pub struct Deserializer<'a, R: ?Sized> {
+    reader: Counted<'a, R>,
+    config: BinaryConfig,
+}
+
+impl<'a, R> Deserializer<'a, R>
+where
+    R: ReadNum + Read + ?Sized,
+{
+    pub fn new(reader: &'a mut R, config: BinaryConfig) -> Deserializer<'a, R> {
+        Deserializer {
+            reader: Counted::new(reader),
+            config,
+        }
+    }
+
+    pub fn position(&self) -> u64 {
+        self.reader.count()
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        let len = self.config.len_prefix.read(&mut self.reader)?;
+        usize::try_from(len).map_err(|_| invalid_data(format!("invalid length {}", len)))
+    }
+
+    fn read_payload(&mut self, len: usize) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        (&mut self.reader)
+            .take(len as u64)
+            .read_to_end(&mut bytes)?;
+        if bytes.len() < len {
+            return Err(io::Error::from(ErrorKind::UnexpectedEof).into());
+        }
+        self.reader.read_align(self.config.align)?;
+        Ok(bytes)
+    }
+
+    fn read_bytes(&mut self) -> Result<Vec<u8>> {
+        let len = self.read_len()?;
+        self.read_payload(len)
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let bytes = match self.config.strings {
+            StrEncoding::LenPrefixed => self.read_bytes()?,
+            StrEncoding::NulTerminated => {
+                let mut bytes = Vec::new();
+                loop {
+                    let byte = self.reader.read_u8()?;
+                    if byte == 0 {
+                        break;
+                    }
+                    bytes.push(byte);
+                }
+                self.reader.read_align(self.config.align)?;
+                bytes
+            }
+        };
+        String::from_utf8(bytes).map_err(|err| invalid_data(err.to_string()))
+    }
+
+    fn read_flag(&mut self, what: &str) -> Result<bool> {
+        match self.reader.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            byte => Err(invalid_data(format!("invalid {} byte {:#04x}", what, byte))),
+        }
+    }
+}
+
+pub fn from_reader<T, R>(reader: &mut R, config: BinaryConfig) -> io::Result<T>
+where
+    T: DeserializeOwned,
+    R: ReadNum + Read + ?Sized,
+{
+    Ok(T::deserialize(&mut Deserializer::new(reader, config))?)
+}
+
+macro_rules! deserialize_num {
+    ($($method: ident, $read: ident, $visit: ident);*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                visitor.$visit(self.reader.$read()?)
+            }
+        )*
+    };
+}
+
+impl<'de, R> de::Deserializer<'de> for &mut Deserializer<'_, R>
+where
+    R: ReadNum + Read + ?Sized,
+{
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value> {
+        Err(Error(io::Error::new(
+            ErrorKind::Unsupported,
+            "binary layouts are not self-describing",
+        )))
+    }
+
+    deserialize_num! {
+        deserialize_u8, read_u8, visit_u8;
+        deserialize_u16, read_u16, visit_u16;
+        deserialize_u32, read_u32, visit_u32;
+        deserialize_u64, read_u64, visit_u64;
+        deserialize_u128, read_u128, visit_u128;
+        deserialize_i8, read_i8, visit_i8;
+        deserialize_i16, read_i16, visit_i16;
+        deserialize_i32, read_i32, visit_i32;
+        deserialize_i64, read_i64, visit_i64;
+        deserialize_i128, read_i128, visit_i128;
+        deserialize_f32, read_f32, visit_f32;
+        deserialize_f64, read_f64, visit_f64
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_bool(self.read_flag("bool")?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let code = self.reader.read_u32()?;
+        let c = char::from_u32(code)
+            .ok_or_else(|| invalid_data(format!("invalid char {:#x}", code)))?;
+        visitor.visit_char(c)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_string(self.read_string()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_string(self.read_string()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_byte_buf(self.read_bytes()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_byte_buf(self.read_bytes()?)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.read_flag("option tag")? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let remaining = self.read_len()?;
+        visitor.visit_seq(Access {
+            de: self,
+            remaining,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(Access {
+            de: self,
+            remaining: len,
+        })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let remaining = self.read_len()?;
+        visitor.visit_map(Access {
+            de: self,
+            remaining,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_u32(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_any(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+struct Access<'d, 'a, R: ?Sized> {
+    de: &'d mut Deserializer<'a, R>,
+    remaining: usize,
+}
+
+impl<'de, R> SeqAccess<'de> for Access<'_, '_, R>
+where
+    R: ReadNum + Read + ?Sized,
+{
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining.min(4096))
+    }
+}
+
+impl<'de, R> MapAccess<'de> for Access<'_, '_, R>
+where
+    R: ReadNum + Read + ?Sized,
+{
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        self.next_element_seed(seed)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining.min(4096))
+    }
+}
+
+impl<'de, R> EnumAccess<'de> for &mut Deserializer<'_, R>
+where
+    R: ReadNum + Read + ?Sized,
+{
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let tag = self.config.variant_tag.read(&mut self.reader)?;
+        let index =
+            u32::try_from(tag).map_err(|_| invalid_data(format!("invalid variant tag {}", tag)))?;
+        let value = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(index))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de, R> VariantAccess<'de> for &mut Deserializer<'_, R>
+where
+    R: ReadNum + Read + ?Sized,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
+
+pub struct Serializer<'a, W: ?Sized> {
+    writer: Counted<'a, W>,
+    config: BinaryConfig,
+}
+
+impl<'a, W> Serializer<'a, W>
+where
+    W: WriteNum + Write + ?Sized,
+{
+    pub fn new(writer: &'a mut W, config: BinaryConfig) -> Serializer<'a, W> {
+        Serializer {
+            writer: Counted::new(writer),
+            config,
+        }
+    }
+
+    pub fn position(&self) -> u64 {
+        self.writer.count()
+    }
+
+    fn write_len(&mut self, len: Option<usize>) -> Result<()> {
+        let len = len.ok_or_else(|| {
+            Error(io::Error::new(
+                ErrorKind::InvalidInput,
+                "sequence length must be known up front",
+            ))
+        })?;
+        Ok(self.config.len_prefix.write(&mut self.writer, len as u64)?)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.write_len(Some(bytes.len()))?;
+        self.writer.write_all(bytes)?;
+        Ok(self.writer.write_align(self.config.align)?)
+    }
+
+    fn write_string(&mut self, s: &str) -> Result<()> {
+        match self.config.strings {
+            StrEncoding::LenPrefixed => self.write_bytes(s.as_bytes()),
+            StrEncoding::NulTerminated => {
+                if s.contains('\0') {
+                    return Err(Error(io::Error::new(
+                        ErrorKind::InvalidInput,
+                        "string contains an interior NUL byte",
+                    )));
+                }
+                self.writer.write_all(s.as_bytes())?;
+                self.writer.write_all(&[0])?;
+                Ok(self.writer.write_align(self.config.align)?)
+            }
+        }
+    }
+
+    fn write_variant(&mut self, variant_index: u32) -> Result<()> {
+        Ok(self
+            .config
+            .variant_tag
+            .write(&mut self.writer, variant_index.into())?)
+    }
+}
+
+pub fn to_writer<T, W>(value: &T, writer: &mut W, config: BinaryConfig) -> io::Result<()>
+where
+    T: Serialize + ?Sized,
+    W: WriteNum + Write + ?Sized,
+{
+    Ok(value.serialize(&mut Serializer::new(writer, config))?)
+}
+
+macro_rules! serialize_num {
+    ($($method: ident, $type: ty, $write: ident);*) => {
+        $(
+            fn $method(self, value: $type) -> Result<()> {
+                Ok(self.writer.$write(value)?)
+            }
+        )*
+    };
+}
+
+impl<'s, 'a, W> ser::Serializer for &'s mut Serializer<'a, W>
+where
+    W: WriteNum + Write + ?Sized,
+{
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Compound<'s, 'a, W>;
+    type SerializeTuple = Compound<'s, 'a, W>;
+    type SerializeTupleStruct = Compound<'s, 'a, W>;
+    type SerializeTupleVariant = Compound<'s, 'a, W>;
+    type SerializeMap = Compound<'s, 'a, W>;
+    type SerializeStruct = Compound<'s, 'a, W>;
+    type SerializeStructVariant = Compound<'s, 'a, W>;
+
+    serialize_num! {
+        serialize_u8, u8, write_u8;
+        serialize_u16, u16, write_u16;
+        serialize_u32, u32, write_u32;
+        serialize_u64, u64, write_u64;
+        serialize_u128, u128, write_u128;
+        serialize_i8, i8, write_i8;
+        serialize_i16, i16, write_i16;
+        serialize_i32, i32, write_i32;
+        serialize_i64, i64, write_i64;
+        serialize_i128, i128, write_i128;
+        serialize_f32, f32, write_f32;
+        serialize_f64, f64, write_f64
+    }
+
+    fn serialize_bool(self, value: bool) -> Result<()> {
+        Ok(self.writer.write_u8(value.into())?)
+    }
+
+    fn serialize_char(self, value: char) -> Result<()> {
+        Ok(self.writer.write_u32(value.into())?)
+    }
+
+    fn serialize_str(self, value: &str) -> Result<()> {
+        self.write_string(value)
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<()> {
+        self.write_bytes(value)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        Ok(self.writer.write_u8(0)?)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        self.writer.write_u8(1)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+    ) -> Result<()> {
+        self.write_variant(variant_index)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.write_variant(variant_index)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'s, 'a, W>> {
+        self.write_len(len)?;
+        Ok(Compound { ser: self })
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Compound<'s, 'a, W>> {
+        Ok(Compound { ser: self })
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Compound<'s, 'a, W>> {
+        Ok(Compound { ser: self })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'s, 'a, W>> {
+        self.write_variant(variant_index)?;
+        Ok(Compound { ser: self })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'s, 'a, W>> {
+        self.write_len(len)?;
+        Ok(Compound { ser: self })
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Compound<'s, 'a, W>> {
+        Ok(Compound { ser: self })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'s, 'a, W>> {
+        self.write_variant(variant_index)?;
+        Ok(Compound { ser: self })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+pub struct Compound<'s, 'a, W: ?Sized> {
+    ser: &'s mut Serializer<'a, W>,
+}
+
+macro_rules! impl_compound {
+    ($($trait: ident, $method: ident);*) => {
+        $(
+            impl<W> $trait for Compound<'_, '_, W>
+            where
+                W: WriteNum + Write + ?Sized,
+            {
+                type Ok = ();
+                type Error = Error;
+
+                fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+                    value.serialize(&mut *self.ser)
+                }
+
+                fn end(self) -> Result<()> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_compound! {
+    SerializeSeq, serialize_element;
+    SerializeTuple, serialize_element;
+    SerializeTupleStruct, serialize_field;
+    SerializeTupleVariant, serialize_field
+}
+
+impl<W> SerializeMap for Compound<'_, '_, W>
+where
+    W: WriteNum + Write + ?Sized,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        key.serialize(&mut *self.ser)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<W> SerializeStruct for Compound<'_, '_, W>
+where
+    W: WriteNum + Write + ?Sized,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &'static str, value: &T) -> Result<()> {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<W> SerializeStructVariant for Compound<'_, '_, W>
+where
+    W: WriteNum + Write + ?Sized,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &'static str, value: &T) -> Result<()> {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, io::Cursor};
+
+    use ::serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::{read_num::ReadEndianExt, write_num::WriteEndianExt, Stream};
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    enum Shape {
+        Empty,
+        Circle(u16),
+        Rect { w: u8, h: u8 },
+    }
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Record {
+        id: u32,
+        name: String,
+        flag: bool,
+        score: Option<i16>,
+        tags: Vec<u8>,
+        pair: (u8, char),
+        shapes: Vec<Shape>,
+    }
+
+    #[test]
+    fn deserialize_record() {
+        let data = b"\x01\x00\x00\x00\x02\x00\x00\x00hi\x01\x01\xfe\xff\x02\x00\x00\x00\x07\x08\
+            \x09\x41\x00\x00\x00\x03\x00\x00\x00\x00\x00\x00\x00\x01\x00\x00\x00\x05\x00\
+            \x02\x00\x00\x00\x03\x04";
+        let mut cursor = Cursor::new(data.to_vec());
+        let mut stream = Stream::new(&mut cursor).le();
+        let record: Record = from_reader(&mut stream, BinaryConfig::new()).unwrap();
+        assert_eq!(
+            record,
+            Record {
+                id: 1,
+                name: "hi".into(),
+                flag: true,
+                score: Some(-2),
+                tags: vec![7, 8],
+                pair: (9, 'A'),
+                shapes: vec![Shape::Empty, Shape::Circle(5), Shape::Rect { w: 3, h: 4 }],
+            }
+        );
+        assert_eq!(stream.remainder_len().unwrap(), 0);
+    }
+
+    #[test]
+    fn deserialize_be_aligned4() {
+        let data = b"\x00\x00\x00\x05hello\x00\x00\x00\x00\x00\x00\x02\x00\x01\x00\x02";
+        let mut cursor = Cursor::new(data.to_vec());
+        let value: (String, Vec<u16>) =
+            from_reader(&mut cursor.be(), BinaryConfig::be_aligned4()).unwrap();
+        assert_eq!(value, ("hello".into(), vec![1, 2]));
+    }
+
+    #[test]
+    fn deserialize_nul_terminated_map() {
+        let config = BinaryConfig::new()
+            .len_prefix(FieldEncoding::Varint)
+            .strings(StrEncoding::NulTerminated);
+        let data = b"\x02a\x00\x01b\x00\x02";
+        let map: BTreeMap<String, u8> = from_reader(&mut Cursor::new(data).le(), config).unwrap();
+        assert_eq!(map, BTreeMap::from([("a".into(), 1), ("b".into(), 2)]));
+    }
+
+    #[test]
+    fn deserialize_errors() {
+        let err =
+            from_reader::<bool, _>(&mut Cursor::new([2u8]).le(), BinaryConfig::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "invalid bool byte 0x02");
+        let err = from_reader::<String, _>(
+            &mut Cursor::new(b"\x05\x00\x00\x00ab").le(),
+            BinaryConfig::new(),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let err = from_reader::<Shape, _>(
+            &mut Cursor::new(b"\x07\x00\x00\x00").le(),
+            BinaryConfig::new(),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let record = Record {
+            id: 0x0102,
+            name: "once".into(),
+            flag: false,
+            score: None,
+            tags: vec![1, 2, 3],
+            pair: (4, '\u{e9}'),
+            shapes: vec![Shape::Rect { w: 5, h: 6 }, Shape::Circle(7)],
+        };
+        for config in [
+            BinaryConfig::new(),
+            BinaryConfig::be_aligned4(),
+            BinaryConfig::new()
+                .len_prefix(FieldEncoding::Varint)
+                .variant_tag(FieldEncoding::U8)
+                .strings(StrEncoding::NulTerminated)
+                .align(8),
+        ] {
+            let mut out = Vec::new();
+            to_writer(&record, &mut out.be_writer(), config).unwrap();
+            let decoded: Record = from_reader(&mut Cursor::new(&out).be(), config).unwrap();
+            assert_eq!(decoded, record);
+        }
+    }
+
+    #[test]
+    fn serialize_be_aligned4() {
+        let mut out = Vec::new();
+        let value = ("hello".to_string(), vec![1u16, 2]);
+        to_writer(&value, &mut out.be_writer(), BinaryConfig::be_aligned4()).unwrap();
+        assert_eq!(
+            out,
+            b"\x00\x00\x00\x05hello\x00\x00\x00\x00\x00\x00\x02\x00\x01\x00\x02"
+        );
+    }
+
+    #[test]
+    fn serialize_errors() {
+        let config = BinaryConfig::new().strings(StrEncoding::NulTerminated);
+        let err = to_writer("a\0b", &mut Vec::new().le_writer(), config).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        let config = BinaryConfig::new().len_prefix(FieldEncoding::U8);
+        let err = to_writer(&vec![0u8; 256], &mut Vec::new().le_writer(), config).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,715 @@
+use alloc::{
+    format,
+    string::{FromUtf8Error, String},
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    io::{Error, ErrorKind, Read, Result, Seek, Write},
+    read_num::{Endianness, Numeric, ReadNum},
+    write_num::WriteNum,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Padding {
+    Nul,
+    Space,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overflow {
+    #[default]
+    Error,
+    Truncate,
+}
+
+impl Padding {
+    fn byte(self) -> u8 {
+        match self {
+            Padding::Nul => 0,
+            Padding::Space => b' ',
+        }
+    }
+
+    fn strip(self, field: &[u8]) -> &[u8] {
+        match self {
+            Padding::Nul => match field.iter().position(|b| *b == 0) {
+                Some(end) => &field[..end],
+                None => field,
+            },
+            Padding::Space => match field.iter().rposition(|b| *b != b' ') {
+                Some(last) => &field[..=last],
+                None => &[],
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NonAscii {
+    Error,
+    Replace,
+}
+
+pub(crate) fn invalid_utf8(e: FromUtf8Error) -> Error {
+    Error::new(ErrorKind::InvalidData, e)
+}
+
+pub(crate) fn missing_nul(max_len: usize) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("no NUL terminator found within {} bytes", max_len),
+    )
+}
+
+pub(crate) fn check_len_prefix<L: TryInto<u64>>(len: L, max_len: u64) -> Result<u64> {
+    let len: u64 = len
+        .try_into()
+        .map_err(|_| Error::new(ErrorKind::InvalidData, "invalid length prefix"))?;
+    if len > max_len {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("length prefix {} exceeds the maximum of {}", len, max_len),
+        ));
+    }
+    Ok(len)
+}
+
+pub(crate) fn check_interior_nul(value: &str) -> Result<()> {
+    match value.bytes().position(|b| b == 0) {
+        Some(index) => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("string contains an interior NUL at index {}", index),
+        )),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn len_prefix<L: TryFrom<usize>>(len: usize) -> Result<L> {
+    L::try_from(len).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "{} bytes do not fit in a {} length prefix",
+                len,
+                core::any::type_name::<L>()
+            ),
+        )
+    })
+}
+
+pub(crate) fn pstr_len(value: &str) -> Result<u8> {
+    u8::try_from(value.len()).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "string of {} bytes is too long for a u8 length prefix",
+                value.len()
+            ),
+        )
+    })
+}
+
+fn read_prefixed_body<R: Read + Seek + ?Sized>(reader: &mut R, len: u64) -> Result<Vec<u8>> {
+    let remainder_len = reader
+        .stream_len()?
+        .saturating_sub(reader.stream_position()?);
+    if len > remainder_len {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "length prefix {} exceeds the remaining {} bytes",
+                len, remainder_len
+            ),
+        ));
+    }
+    let mut bytes = vec![0u8; len as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn utf16_byte_len(len_units: usize) -> Result<usize> {
+    len_units.checked_mul(2).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} UTF-16 code units overflow the buffer size", len_units),
+        )
+    })
+}
+
+fn utf16_units(bytes: &[u8], endianness: Endianness) -> Vec<u16> {
+    bytes
+        .chunks_exact(2)
+        .map(|unit| match endianness {
+            Endianness::Big => u16::from_be_bytes([unit[0], unit[1]]),
+            Endianness::Little => u16::from_le_bytes([unit[0], unit[1]]),
+        })
+        .collect()
+}
+
+fn decode_utf16(units: &[u16], base_offset: usize) -> Result<String> {
+    let mut value = String::with_capacity(units.len());
+    let mut offset = base_offset;
+    for c in char::decode_utf16(units.iter().copied()) {
+        match c {
+            Ok(c) => {
+                value.push(c);
+                offset += c.len_utf16() * 2;
+            }
+            Err(e) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "unpaired UTF-16 surrogate {:#06x} at byte offset {}",
+                        e.unpaired_surrogate(),
+                        offset
+                    ),
+                ))
+            }
+        }
+    }
+    Ok(value)
+}
+
+pub trait ReadStr: Read {
+    fn read_cstr_bytes(&mut self, max_len: usize) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        let mut byte = [0u8; 1];
+        for _ in 0..max_len {
+            self.read_exact(&mut byte)?;
+            if byte[0] == 0 {
+                return Ok(bytes);
+            }
+            bytes.push(byte[0]);
+        }
+        Err(missing_nul(max_len))
+    }
+
+    fn read_cstr(&mut self, max_len: usize) -> Result<String> {
+        String::from_utf8(self.read_cstr_bytes(max_len)?).map_err(invalid_utf8)
+    }
+
+    fn read_cstr_lossy(&mut self, max_len: usize) -> Result<String> {
+        Ok(String::from_utf8_lossy(&self.read_cstr_bytes(max_len)?).into_owned())
+    }
+
+    fn read_padded_bytes(&mut self, len: usize, padding: Padding) -> Result<Vec<u8>> {
+        let mut field = vec![0u8; len];
+        self.read_exact(&mut field)?;
+        let stripped_len = padding.strip(&field).len();
+        field.truncate(stripped_len);
+        Ok(field)
+    }
+
+    fn read_padded_str(&mut self, len: usize, padding: Padding) -> Result<String> {
+        String::from_utf8(self.read_padded_bytes(len, padding)?).map_err(invalid_utf8)
+    }
+
+    fn read_ascii_string(&mut self, len: usize, non_ascii: NonAscii) -> Result<String> {
+        let mut bytes = vec![0u8; len];
+        self.read_exact(&mut bytes)?;
+        match (bytes.iter().position(|b| !b.is_ascii()), non_ascii) {
+            (None, _) => Ok(bytes.into_iter().map(char::from).collect()),
+            (Some(index), NonAscii::Error) => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("non-ASCII byte {:#04x} at index {}", bytes[index], index),
+            )),
+            (Some(_), NonAscii::Replace) => Ok(bytes
+                .into_iter()
+                .map(|b| {
+                    if b.is_ascii() {
+                        char::from(b)
+                    } else {
+                        char::REPLACEMENT_CHARACTER
+                    }
+                })
+                .collect()),
+        }
+    }
+
+    fn read_utf16_string(&mut self, len_units: usize, endianness: Endianness) -> Result<String> {
+        let mut bytes = vec![0u8; utf16_byte_len(len_units)?];
+        self.read_exact(&mut bytes)?;
+        decode_utf16(&utf16_units(&bytes, endianness), 0)
+    }
+
+    fn read_utf16_string_bom(
+        &mut self,
+        len_units: usize,
+        default: Endianness,
+    ) -> Result<(String, Endianness)> {
+        let mut bytes = vec![0u8; utf16_byte_len(len_units)?];
+        self.read_exact(&mut bytes)?;
+        let (endianness, skip) = match bytes.get(..2) {
+            Some([0xfe, 0xff]) => (Endianness::Big, 2),
+            Some([0xff, 0xfe]) => (Endianness::Little, 2),
+            _ => (default, 0),
+        };
+        let value = decode_utf16(&utf16_units(&bytes[skip..], endianness), skip)?;
+        Ok((value, endianness))
+    }
+
+    fn read_wcstr(&mut self, max_units: usize, endianness: Endianness) -> Result<String> {
+        let mut units = Vec::new();
+        let mut unit = [0u8; 2];
+        for _ in 0..max_units {
+            self.read_exact(&mut unit)?;
+            let unit = match endianness {
+                Endianness::Big => u16::from_be_bytes(unit),
+                Endianness::Little => u16::from_le_bytes(unit),
+            };
+            if unit == 0 {
+                return decode_utf16(&units, 0);
+            }
+            units.push(unit);
+        }
+        Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("no UTF-16 NUL terminator found within {} units", max_units),
+        ))
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    fn read_string_with_encoding(
+        &mut self,
+        len: usize,
+        encoding: &'static encoding_rs::Encoding,
+    ) -> Result<String> {
+        let mut bytes = vec![0u8; len];
+        self.read_exact(&mut bytes)?;
+        match encoding.decode_without_bom_handling_and_without_replacement(&bytes) {
+            Some(value) => Ok(value.into_owned()),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid {} sequence", encoding.name()),
+            )),
+        }
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    fn read_string_with_encoding_lossy(
+        &mut self,
+        len: usize,
+        encoding: &'static encoding_rs::Encoding,
+    ) -> Result<String> {
+        let mut bytes = vec![0u8; len];
+        self.read_exact(&mut bytes)?;
+        Ok(encoding.decode_without_bom_handling(&bytes).0.into_owned())
+    }
+
+    fn read_len_prefixed_bytes<L>(&mut self, max_len: u64) -> Result<Vec<u8>>
+    where
+        Self: ReadNum + Seek,
+        L: Numeric + TryInto<u64>,
+    {
+        let len = check_len_prefix(L::read_from(self)?, max_len)?;
+        read_prefixed_body(self, len)
+    }
+
+    fn read_len_prefixed_string<L>(&mut self, max_len: u64) -> Result<String>
+    where
+        Self: ReadNum + Seek,
+        L: Numeric + TryInto<u64>,
+    {
+        String::from_utf8(self.read_len_prefixed_bytes::<L>(max_len)?).map_err(invalid_utf8)
+    }
+
+    fn read_pstr_bytes(&mut self) -> Result<Vec<u8>>
+    where
+        Self: Seek,
+    {
+        let mut len = [0u8; 1];
+        self.read_exact(&mut len)?;
+        read_prefixed_body(self, len[0] as u64)
+    }
+
+    fn read_pstr(&mut self) -> Result<String>
+    where
+        Self: Seek,
+    {
+        String::from_utf8(self.read_pstr_bytes()?).map_err(invalid_utf8)
+    }
+}
+
+impl<R> ReadStr for R where R: Read + ?Sized {}
+
+pub trait WriteStr: Write {
+    fn write_padded_str(
+        &mut self,
+        value: &str,
+        len: usize,
+        padding: Padding,
+        overflow: Overflow,
+    ) -> Result<()> {
+        if value.len() > len && overflow == Overflow::Error {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "string of {} bytes does not fit in a {}-byte field",
+                    value.len(),
+                    len
+                ),
+            ));
+        }
+        let mut end = core::cmp::min(value.len(), len);
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        let mut field = vec![padding.byte(); len];
+        field[..end].copy_from_slice(&value.as_bytes()[..end]);
+        self.write_all(&field)
+    }
+
+    fn write_cstr(&mut self, value: &str) -> Result<()> {
+        check_interior_nul(value)?;
+        self.write_all(value.as_bytes())?;
+        self.write_all(&[0])
+    }
+
+    fn write_len_prefixed_bytes<L>(&mut self, value: &[u8]) -> Result<()>
+    where
+        Self: WriteNum,
+        L: Numeric + TryFrom<usize>,
+    {
+        self.write_num(len_prefix::<L>(value.len())?)?;
+        self.write_all(value)
+    }
+
+    fn write_len_prefixed_str<L>(&mut self, value: &str) -> Result<()>
+    where
+        Self: WriteNum,
+        L: Numeric + TryFrom<usize>,
+    {
+        self.write_len_prefixed_bytes::<L>(value.as_bytes())
+    }
+
+    fn write_pstr(&mut self, value: &str) -> Result<()> {
+        self.write_all(&[pstr_len(value)?])?;
+        self.write_all(value.as_bytes())
+    }
+}
+
+impl<W> WriteStr for W where W: Write + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{read_num::ReadEndianExt, Stream};
+
+    #[test]
+    fn read_cstr() {
+        let mut cursor = Cursor::new(b"hello\0world\0".to_vec());
+        assert_eq!(cursor.read_cstr(16).unwrap(), "hello");
+        assert_eq!(cursor.read_cstr(6).unwrap(), "world");
+    }
+
+    #[test]
+    fn read_cstr_without_terminator_within_cap() {
+        let mut cursor = Cursor::new(b"hello\0".to_vec());
+        let result = cursor.read_cstr(5);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_cstr_without_terminator_within_chunk() {
+        let mut cursor = Cursor::new(b"hello\0".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let mut chunk = stream.borrow_chunk(Some(5)).unwrap();
+        let result = chunk.read_cstr(16);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_cstr_invalid_utf8() {
+        let mut cursor = Cursor::new(b"ab\xffc\0ab\xffc\0".to_vec());
+        let result = cursor.read_cstr(8);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(cursor.read_cstr_lossy(8).unwrap(), "ab\u{fffd}c");
+    }
+
+    #[test]
+    fn read_padded_str() {
+        let mut cursor = Cursor::new(b"name\0\0junkTITLE   ".to_vec());
+        assert_eq!(cursor.read_padded_str(10, Padding::Nul).unwrap(), "name");
+        assert_eq!(cursor.read_padded_str(8, Padding::Space).unwrap(), "TITLE");
+        let mut cursor = Cursor::new(b"full    ".to_vec());
+        assert_eq!(cursor.read_padded_str(4, Padding::Nul).unwrap(), "full");
+        assert_eq!(cursor.read_padded_str(4, Padding::Space).unwrap(), "");
+    }
+
+    #[test]
+    fn write_padded_str() {
+        let mut out = Vec::new();
+        out.write_padded_str("abc", 5, Padding::Nul, Overflow::Error)
+            .unwrap();
+        out.write_padded_str("abc", 4, Padding::Space, Overflow::Error)
+            .unwrap();
+        out.write_padded_str("truncated", 5, Padding::Nul, Overflow::Truncate)
+            .unwrap();
+        out.write_padded_str("añb", 2, Padding::Space, Overflow::Truncate)
+            .unwrap();
+        assert_eq!(out, b"abc\0\0abc trunca ");
+    }
+
+    #[test]
+    fn write_padded_str_overflow_error() {
+        let mut out = Vec::new();
+        out.write_padded_str("exact", 5, Padding::Nul, Overflow::default())
+            .unwrap();
+        let result = out.write_padded_str("too long", 5, Padding::Nul, Overflow::default());
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(out, b"exact");
+    }
+
+    #[test]
+    fn read_len_prefixed_string() {
+        let mut cursor = Cursor::new(b"\x00\x05hello\x03\x00abc".to_vec());
+        let mut stream = Stream::new(&mut cursor).be();
+        assert_eq!(stream.read_len_prefixed_string::<u16>(16).unwrap(), "hello");
+        let mut stream = stream.le();
+        assert_eq!(stream.read_len_prefixed_string::<u16>(3).unwrap(), "abc");
+    }
+
+    #[test]
+    fn write_len_prefixed_str_round_trip() {
+        let mut cursor = Cursor::new(vec![0u8; 14]);
+        let mut stream = Stream::new(&mut cursor).be();
+        stream.write_len_prefixed_str::<u16>("hello").unwrap();
+        let mut stream = stream.le();
+        stream.write_len_prefixed_str::<u32>("abc").unwrap();
+        assert_eq!(cursor.get_ref(), b"\x00\x05hello\x03\x00\x00\x00abc");
+        cursor.set_position(0);
+        let mut stream = Stream::new(&mut cursor).be();
+        assert_eq!(stream.read_len_prefixed_string::<u16>(16).unwrap(), "hello");
+        let mut stream = stream.le();
+        assert_eq!(stream.read_len_prefixed_string::<u32>(16).unwrap(), "abc");
+    }
+
+    #[test]
+    fn write_len_prefixed_str_too_long() {
+        let mut cursor = Cursor::new(vec![0u8; 300]);
+        let mut stream = Stream::new(&mut cursor);
+        let err = stream
+            .write_len_prefixed_str::<u8>(&"x".repeat(256))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            err.to_string(),
+            "256 bytes do not fit in a u8 length prefix"
+        );
+    }
+
+    #[test]
+    fn write_cstr_round_trip() {
+        let mut out = Vec::new();
+        out.write_cstr("abc").unwrap();
+        out.write_cstr("").unwrap();
+        assert_eq!(out, b"abc\0\0");
+        let mut cursor = Cursor::new(out);
+        assert_eq!(cursor.read_cstr(8).unwrap(), "abc");
+        assert_eq!(cursor.read_cstr(8).unwrap(), "");
+        let result = Vec::new().write_cstr("a\0b");
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn read_len_prefixed_string_over_cap() {
+        let mut cursor = Cursor::new(b"\x05hello".to_vec());
+        let result = cursor.le().read_len_prefixed_string::<u8>(4);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_len_prefixed_string_over_remainder() {
+        let mut cursor = Cursor::new(b"\x00\x00\xff\xffhello".to_vec());
+        let mut stream = Stream::new(&mut cursor).be();
+        let err = stream
+            .read_len_prefixed_string::<u32>(u64::MAX)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "length prefix 65535 exceeds the remaining 5 bytes"
+        );
+    }
+
+    #[test]
+    fn read_len_prefixed_string_over_chunk_limit() {
+        let mut cursor = Cursor::new(b"\x06hello!".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let mut chunk = stream.borrow_chunk(Some(6)).unwrap();
+        let result = chunk.read_len_prefixed_string::<u8>(16);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_ascii_string() {
+        let mut cursor = Cursor::new(b"GET /\x7f".to_vec());
+        assert_eq!(
+            cursor.read_ascii_string(6, NonAscii::Error).unwrap(),
+            "GET /\x7f"
+        );
+    }
+
+    #[test]
+    fn read_ascii_string_with_high_bytes() {
+        let mut cursor = Cursor::new(b"caf\xc3\xa9".to_vec());
+        let err = cursor.read_ascii_string(5, NonAscii::Error).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "non-ASCII byte 0xc3 at index 3");
+        cursor.set_position(0);
+        assert_eq!(
+            cursor.read_ascii_string(5, NonAscii::Replace).unwrap(),
+            "caf\u{fffd}\u{fffd}"
+        );
+    }
+
+    #[test]
+    fn read_pstr() {
+        let mut cursor = Cursor::new(b"\x04Mac!\x00\x02ok".to_vec());
+        assert_eq!(cursor.read_pstr().unwrap(), "Mac!");
+        assert_eq!(cursor.read_pstr().unwrap(), "");
+        assert_eq!(cursor.read_pstr_bytes().unwrap(), b"ok");
+    }
+
+    #[test]
+    fn read_pstr_over_chunk_limit() {
+        let mut cursor = Cursor::new(b"\x05hello".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        let mut chunk = stream.borrow_chunk(Some(5)).unwrap();
+        let err = chunk.read_pstr().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "length prefix 5 exceeds the remaining 4 bytes"
+        );
+    }
+
+    #[test]
+    fn write_pstr_round_trip() {
+        let mut out = Vec::new();
+        out.write_pstr("Geneva").unwrap();
+        out.write_pstr("").unwrap();
+        assert_eq!(out, b"\x06Geneva\x00");
+        let mut cursor = Cursor::new(out);
+        assert_eq!(cursor.read_pstr().unwrap(), "Geneva");
+        assert_eq!(cursor.read_pstr().unwrap(), "");
+        let result = Vec::new().write_pstr(&"x".repeat(256));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    fn utf16_bytes(value: &str, endianness: Endianness) -> Vec<u8> {
+        value
+            .encode_utf16()
+            .flat_map(|unit| match endianness {
+                Endianness::Big => unit.to_be_bytes(),
+                Endianness::Little => unit.to_le_bytes(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn read_utf16_string() {
+        let value = "h\u{e9}llo \u{1f600}";
+        let mut data = utf16_bytes(value, Endianness::Big);
+        data.extend(utf16_bytes(value, Endianness::Little));
+        let mut cursor = Cursor::new(data);
+        assert_eq!(cursor.read_utf16_string(8, Endianness::Big).unwrap(), value);
+        assert_eq!(
+            cursor.read_utf16_string(8, Endianness::Little).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn read_utf16_string_bom() {
+        let mut data = vec![0xff, 0xfe];
+        data.extend(utf16_bytes("ab", Endianness::Little));
+        data.extend([0xfe, 0xff]);
+        data.extend(utf16_bytes("cd", Endianness::Big));
+        data.extend(utf16_bytes("ef", Endianness::Big));
+        let mut cursor = Cursor::new(data);
+        assert_eq!(
+            cursor.read_utf16_string_bom(3, Endianness::Big).unwrap(),
+            ("ab".to_string(), Endianness::Little)
+        );
+        assert_eq!(
+            cursor.read_utf16_string_bom(3, Endianness::Little).unwrap(),
+            ("cd".to_string(), Endianness::Big)
+        );
+        assert_eq!(
+            cursor.read_utf16_string_bom(2, Endianness::Big).unwrap(),
+            ("ef".to_string(), Endianness::Big)
+        );
+    }
+
+    #[test]
+    fn read_utf16_string_unpaired_surrogate() {
+        let mut cursor = Cursor::new([0x00, 0x61, 0xd8, 0x3d, 0x00, 0x62]);
+        let err = cursor.read_utf16_string(3, Endianness::Big).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "unpaired UTF-16 surrogate 0xd83d at byte offset 2"
+        );
+    }
+
+    #[test]
+    fn read_utf16_string_length_overflow() {
+        let mut cursor = Cursor::new(b"\xfe\xff\x00a".to_vec());
+        let result = cursor.read_utf16_string(usize::MAX, Endianness::Big);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+        let result = cursor.read_utf16_string_bom(usize::MAX / 2 + 1, Endianness::Big);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn read_wcstr() {
+        let mut data = utf16_bytes("Key\0", Endianness::Little);
+        data.extend(utf16_bytes("\u{1f600}\0", Endianness::Big));
+        let mut cursor = Cursor::new(data);
+        assert_eq!(cursor.read_wcstr(16, Endianness::Little).unwrap(), "Key");
+        assert_eq!(cursor.read_wcstr(3, Endianness::Big).unwrap(), "\u{1f600}");
+    }
+
+    #[test]
+    fn read_wcstr_without_terminator() {
+        let data = utf16_bytes("abc\0", Endianness::Little);
+        let result = Cursor::new(&data).read_wcstr(3, Endianness::Little);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let result = Cursor::new(&data[..6]).read_wcstr(8, Endianness::Little);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test]
+    fn read_string_with_encoding() {
+        let mut cursor = Cursor::new(b"caf\xe9\x83\x65\x83\x58\x83\x67".to_vec());
+        assert_eq!(
+            cursor
+                .read_string_with_encoding(4, encoding_rs::WINDOWS_1252)
+                .unwrap(),
+            "caf\u{e9}"
+        );
+        assert_eq!(
+            cursor
+                .read_string_with_encoding(6, encoding_rs::SHIFT_JIS)
+                .unwrap(),
+            "\u{30c6}\u{30b9}\u{30c8}"
+        );
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test]
+    fn read_string_with_invalid_encoding() {
+        let data = b"\x83".to_vec();
+        let result = Cursor::new(&data).read_string_with_encoding(1, encoding_rs::SHIFT_JIS);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let result = Cursor::new(&data).read_string_with_encoding_lossy(1, encoding_rs::SHIFT_JIS);
+        assert_eq!(result.unwrap(), "\u{fffd}");
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    io::{Error, ErrorKind, Read, Result, Write},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::read_num::ReadNum;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DosDateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl DosDateTime {
+    pub fn from_packed(date: u16, time: u16) -> DosDateTime {
+        DosDateTime {
+            year: 1980 + (date >> 9),
+            month: ((date >> 5) & 0x0f) as u8,
+            day: (date & 0x1f) as u8,
+            hour: (time >> 11) as u8,
+            minute: ((time >> 5) & 0x3f) as u8,
+            second: ((time & 0x1f) * 2) as u8,
+        }
+    }
+
+    pub fn to_packed(&self) -> Result<(u16, u16)> {
+        if !self.is_valid() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{:?} cannot be represented as a DOS date/time", self),
+            ));
+        }
+        let date = ((self.year - 1980) << 9) | ((self.month as u16) << 5) | self.day as u16;
+        let time =
+            ((self.hour as u16) << 11) | ((self.minute as u16) << 5) | (self.second as u16 / 2);
+        Ok((date, time))
+    }
+
+    pub fn is_valid(&self) -> bool {
+        (1980..=2107).contains(&self.year)
+            && (1..=12).contains(&self.month)
+            && (1..=days_in_month(self.year, self.month)).contains(&self.day)
+            && self.hour < 24
+            && self.minute < 60
+            && self.second < 60
+    }
+
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        if !self.is_valid() {
+            return None;
+        }
+        let days = days_from_civil(self.year as i64, self.month as i64, self.day as i64);
+        let seconds =
+            days * 86400 + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64;
+        Some(UNIX_EPOCH + Duration::from_secs(seconds as u64))
+    }
+}
+
+pub fn unix_time(seconds: i64, nanos: u32) -> Option<SystemTime> {
+    let since_epoch = Duration::new(seconds.unsigned_abs(), 0);
+    let time = if seconds < 0 {
+        UNIX_EPOCH.checked_sub(since_epoch)?
+    } else {
+        UNIX_EPOCH.checked_add(since_epoch)?
+    };
+    time.checked_add(Duration::from_nanos(nanos as u64))
+}
+
+fn checked_unix_time(seconds: i64, nanos: u32) -> Result<SystemTime> {
+    unix_time(seconds, nanos).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("unix time {}s {}ns is out of range", seconds, nanos),
+        )
+    })
+}
+
+pub trait ReadTime: Read {
+    fn read_dos_datetime(&mut self) -> Result<DosDateTime> {
+        let mut buf = [0u8; 4];
+        self.read_exact(&mut buf)?;
+        let time = u16::from_le_bytes([buf[0], buf[1]]);
+        let date = u16::from_le_bytes([buf[2], buf[3]]);
+        Ok(DosDateTime::from_packed(date, time))
+    }
+
+    fn read_unix_time32(&mut self) -> Result<SystemTime>
+    where
+        Self: ReadNum,
+    {
+        let seconds = self.read_i32()?;
+        checked_unix_time(seconds as i64, 0)
+    }
+
+    fn read_unix_time32_unsigned(&mut self) -> Result<SystemTime>
+    where
+        Self: ReadNum,
+    {
+        let seconds = self.read_u32()?;
+        checked_unix_time(seconds as i64, 0)
+    }
+
+    fn read_unix_time64(&mut self) -> Result<SystemTime>
+    where
+        Self: ReadNum,
+    {
+        let seconds = self.read_i64()?;
+        checked_unix_time(seconds, 0)
+    }
+
+    fn read_unix_time64_millis(&mut self) -> Result<SystemTime>
+    where
+        Self: ReadNum,
+    {
+        let millis = self.read_i64()?;
+        let nanos = millis.rem_euclid(1000) as u32 * 1_000_000;
+        checked_unix_time(millis.div_euclid(1000), nanos)
+    }
+}
+
+impl<R> ReadTime for R where R: Read + ?Sized {}
+
+pub trait WriteTime: Write {
+    fn write_dos_datetime(&mut self, value: &DosDateTime) -> Result<()> {
+        let (date, time) = value.to_packed()?;
+        let mut buf = [0u8; 4];
+        buf[..2].copy_from_slice(&time.to_le_bytes());
+        buf[2..].copy_from_slice(&date.to_le_bytes());
+        self.write_all(&buf)
+    }
+}
+
+impl<W> WriteTime for W where W: Write + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::Stream;
+
+    #[test]
+    fn read_dos_datetime() {
+        let mut cursor = Cursor::new([0x6a, 0x9b, 0x52, 0x57]);
+        let value = cursor.read_dos_datetime().unwrap();
+        assert_eq!(
+            value,
+            DosDateTime {
+                year: 2023,
+                month: 10,
+                day: 18,
+                hour: 19,
+                minute: 27,
+                second: 20,
+            }
+        );
+        assert_eq!(
+            value.to_system_time(),
+            Some(UNIX_EPOCH + Duration::from_secs(1697657240))
+        );
+    }
+
+    #[test]
+    fn write_dos_datetime_round_trip() {
+        let value = DosDateTime {
+            year: 1999,
+            month: 12,
+            day: 31,
+            hour: 23,
+            minute: 59,
+            second: 58,
+        };
+        let mut out = Vec::new();
+        out.write_dos_datetime(&value).unwrap();
+        assert_eq!(Cursor::new(out).read_dos_datetime().unwrap(), value);
+    }
+
+    #[test]
+    fn invalid_dos_datetime() {
+        let mut cursor = Cursor::new([0u8; 4]);
+        let value = cursor.read_dos_datetime().unwrap();
+        assert_eq!(value.month, 0);
+        assert_eq!(value.to_system_time(), None);
+        let result = Vec::new().write_dos_datetime(&value);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn dos_datetime_days_per_month() {
+        let date = |year, month, day| DosDateTime {
+            year,
+            month,
+            day,
+            ..DosDateTime::default()
+        };
+        assert!(!date(2023, 2, 29).is_valid());
+        assert_eq!(date(2023, 2, 29).to_system_time(), None);
+        assert!(date(2024, 2, 29).is_valid());
+        assert_eq!(
+            date(2024, 2, 29).to_system_time(),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_164_800))
+        );
+        assert!(!date(2000, 2, 31).is_valid());
+        assert!(date(2000, 2, 29).is_valid());
+        assert!(!date(2100, 2, 29).is_valid());
+        assert!(!date(2021, 4, 31).is_valid());
+        assert!(date(2021, 12, 31).is_valid());
+    }
+
+    #[test]
+    fn read_unix_time() {
+        let mut cursor = Cursor::new(vec![]);
+        cursor.get_mut().extend(1_000_000_000i32.to_be_bytes());
+        cursor.get_mut().extend((-86400i32).to_be_bytes());
+        cursor.get_mut().extend(u32::MAX.to_be_bytes());
+        cursor.get_mut().extend(1_700_000_000i64.to_be_bytes());
+        let mut stream = Stream::new(&mut cursor).be();
+        assert_eq!(
+            stream.read_unix_time32().unwrap(),
+            UNIX_EPOCH + Duration::from_secs(1_000_000_000)
+        );
+        assert_eq!(
+            stream.read_unix_time32().unwrap(),
+            UNIX_EPOCH - Duration::from_secs(86400)
+        );
+        assert_eq!(
+            stream.read_unix_time32_unsigned().unwrap(),
+            UNIX_EPOCH + Duration::from_secs(u32::MAX as u64)
+        );
+        assert_eq!(
+            stream.read_unix_time64().unwrap(),
+            UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+        );
+    }
+
+    #[test]
+    fn read_unix_time_millis() {
+        let mut cursor = Cursor::new(vec![]);
+        cursor.get_mut().extend(1_500i64.to_le_bytes());
+        cursor.get_mut().extend((-1_500i64).to_le_bytes());
+        let mut stream = Stream::new(&mut cursor).le();
+        assert_eq!(
+            stream.read_unix_time64_millis().unwrap(),
+            UNIX_EPOCH + Duration::from_millis(1_500)
+        );
+        assert_eq!(
+            stream.read_unix_time64_millis().unwrap(),
+            UNIX_EPOCH - Duration::from_millis(1_500)
+        );
+    }
+
+    #[test]
+    fn unix_time_before_epoch_with_nanos() {
+        assert_eq!(
+            unix_time(-1, 500_000_000),
+            Some(UNIX_EPOCH - Duration::from_millis(500))
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+use crate::{read_num::Endianness, Stream};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldEncoding {
+    U8,
+    U16(Endianness),
+    U32(Endianness),
+    U64(Endianness),
+    Varint,
+}
+
+impl FieldEncoding {
+    fn fixed_len(self) -> Option<usize> {
+        match self {
+            FieldEncoding::U8 => Some(1),
+            FieldEncoding::U16(_) => Some(2),
+            FieldEncoding::U32(_) => Some(4),
+            FieldEncoding::U64(_) => Some(8),
+            FieldEncoding::Varint => None,
+        }
+    }
+
+    fn endianness(self) -> Endianness {
+        match self {
+            FieldEncoding::U16(e) | FieldEncoding::U32(e) | FieldEncoding::U64(e) => e,
+            FieldEncoding::U8 | FieldEncoding::Varint => Endianness::Big,
+        }
+    }
+
+    pub fn encoded_len(self, value: u64) -> usize {
+        match self.fixed_len() {
+            Some(len) => len,
+            None => std::cmp::max(1, (64 - value.leading_zeros() as usize).div_ceil(7)),
+        }
+    }
+
+    pub fn read<R: Read + ?Sized>(self, reader: &mut R) -> Result<u64> {
+        let len = match self.fixed_len() {
+            Some(len) => len,
+            None => return read_varint(reader),
+        };
+        let mut buf = [0u8; 8];
+        match self.endianness() {
+            Endianness::Big => {
+                reader.read_exact(&mut buf[8 - len..])?;
+                Ok(u64::from_be_bytes(buf))
+            }
+            Endianness::Little => {
+                reader.read_exact(&mut buf[..len])?;
+                Ok(u64::from_le_bytes(buf))
+            }
+        }
+    }
+
+    pub fn write<W: Write + ?Sized>(self, writer: &mut W, value: u64) -> Result<()> {
+        let len = match self.fixed_len() {
+            Some(len) => len,
+            None => return write_varint(writer, value),
+        };
+        if len < 8 && value >> (len * 8) != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("value {} does not fit in a {}-byte field", value, len),
+            ));
+        }
+        match self.endianness() {
+            Endianness::Big => writer.write_all(&value.to_be_bytes()[8 - len..]),
+            Endianness::Little => writer.write_all(&value.to_le_bytes()[..len]),
+        }
+    }
+}
+
+pub(crate) struct VarintDecoder {
+    value: u64,
+    index: u32,
+}
+
+impl VarintDecoder {
+    pub(crate) fn new() -> VarintDecoder {
+        VarintDecoder { value: 0, index: 0 }
+    }
+
+    pub(crate) fn push(&mut self, byte: u8) -> Result<Option<u64>> {
+        let bits = (byte & 0x7f) as u64;
+        if self.index == 9 && (bits > 1 || byte & 0x80 != 0) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "varint overflows 64 bits",
+            ));
+        }
+        self.value |= bits << (self.index * 7);
+        self.index += 1;
+        if byte & 0x80 != 0 {
+            return Ok(None);
+        }
+        Ok(Some(self.value))
+    }
+}
+
+fn read_varint<R: Read + ?Sized>(reader: &mut R) -> Result<u64> {
+    let mut decoder = VarintDecoder::new();
+    loop {
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte)?;
+        if let Some(value) = decoder.push(byte[0])? {
+            return Ok(value);
+        }
+    }
+}
+
+pub(crate) fn encode_varint(mut value: u64) -> ([u8; 10], usize) {
+    let mut buf = [0u8; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    (buf, len)
+}
+
+fn write_varint<W: Write + ?Sized>(writer: &mut W, value: u64) -> Result<()> {
+    let (buf, len) = encode_varint(value);
+    writer.write_all(&buf[..len])
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TlvFormat {
+    tag: FieldEncoding,
+    length: FieldEncoding,
+    alignment: u64,
+}
+
+impl TlvFormat {
+    pub fn new(tag: FieldEncoding, length: FieldEncoding) -> TlvFormat {
+        TlvFormat {
+            tag,
+            length,
+            alignment: 1,
+        }
+    }
+
+    pub fn align(mut self, alignment: u64) -> TlvFormat {
+        self.alignment = std::cmp::max(alignment, 1);
+        self
+    }
+
+    fn padding(&self, len: u64) -> u64 {
+        len.next_multiple_of(self.alignment) - len
+    }
+
+    pub fn read_record<T, E, F, R>(&self, stream: &mut Stream<'_, T, E>, f: F) -> Result<R>
+    where
+        T: Read + Seek,
+        F: FnOnce(u64, &mut Stream<'_, T, E>) -> Result<R>,
+    {
+        let tag = self.tag.read(stream)?;
+        let len = self.length.read(stream)?;
+        let remainder_len = stream.remainder_len()?;
+        if len > remainder_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "TLV length {} exceeds the remaining {} bytes",
+                    len, remainder_len
+                ),
+            ));
+        }
+        let mut chunk = stream.borrow_chunk(Some(len))?;
+        let result = f(tag, &mut chunk)?;
+        chunk.seek(SeekFrom::End(0))?;
+        let padding = std::cmp::min(self.padding(len), stream.remainder_len()?);
+        stream.seek(SeekFrom::Current(padding as i64))?;
+        Ok(result)
+    }
+
+    pub fn for_each<T, E, F>(&self, stream: &mut Stream<'_, T, E>, mut f: F) -> Result<()>
+    where
+        T: Read + Seek,
+        F: FnMut(u64, &mut Stream<'_, T, E>) -> Result<()>,
+    {
+        while stream.remainder_len()? > 0 {
+            self.read_record(stream, &mut f)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_record<W: Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        tag: u64,
+        value: &[u8],
+    ) -> Result<()> {
+        self.tag.write(writer, tag)?;
+        self.length.write(writer, value.len() as u64)?;
+        writer.write_all(value)?;
+        let padding = self.padding(value.len() as u64) as usize;
+        writer.write_all(&vec![0u8; padding])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::read_num::ReadNum;
+
+    #[test]
+    fn field_encoding_round_trip() {
+        let encodings = [
+            FieldEncoding::U8,
+            FieldEncoding::U16(Endianness::Big),
+            FieldEncoding::U32(Endianness::Little),
+            FieldEncoding::U64(Endianness::Big),
+            FieldEncoding::Varint,
+        ];
+        for encoding in encodings {
+            for value in [0u64, 1, 127, 128, 255] {
+                let mut out = Vec::new();
+                encoding.write(&mut out, value).unwrap();
+                assert_eq!(out.len(), encoding.encoded_len(value));
+                assert_eq!(encoding.read(&mut Cursor::new(out)).unwrap(), value);
+            }
+        }
+        let mut out = Vec::new();
+        FieldEncoding::Varint.write(&mut out, u64::MAX).unwrap();
+        assert_eq!(out.len(), 10);
+        let mut cursor = Cursor::new(out);
+        assert_eq!(FieldEncoding::Varint.read(&mut cursor).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn field_encoding_errors() {
+        let result = FieldEncoding::U8.write(&mut Vec::new(), 256);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+        let mut cursor = Cursor::new([0xff; 10]);
+        let result = FieldEncoding::Varint.read(&mut cursor);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        let mut out = Vec::new();
+        FieldEncoding::U16(Endianness::Little)
+            .write(&mut out, 0x1234)
+            .unwrap();
+        assert_eq!(out, [0x34, 0x12]);
+    }
+
+    #[test]
+    fn for_each_record() {
+        let mut cursor = Cursor::new(vec![0x01, 0x02, 0x12, 0x34, 0x02, 0x01, 0xff, 0x03, 0x00]);
+        let mut stream = Stream::new(&mut cursor).be();
+        let format = TlvFormat::new(FieldEncoding::U8, FieldEncoding::U8);
+        let mut records = Vec::new();
+        format
+            .for_each(&mut stream, |tag, value| {
+                let len = value.remainder_len()?;
+                let first = if len > 0 {
+                    Some(value.read_u8()?)
+                } else {
+                    None
+                };
+                records.push((tag, len, first));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(
+            records,
+            [(1, 2, Some(0x12)), (2, 1, Some(0xff)), (3, 0, None)]
+        );
+    }
+
+    #[test]
+    fn records_with_alignment_and_varint_length() {
+        let format = TlvFormat::new(
+            FieldEncoding::U16(Endianness::Little),
+            FieldEncoding::Varint,
+        )
+        .align(4);
+        let mut out = Vec::new();
+        format.write_record(&mut out, 0x0102, b"abcde").unwrap();
+        format.write_record(&mut out, 7, &[0x55; 200]).unwrap();
+        assert_eq!(&out[..11], b"\x02\x01\x05abcde\0\0\0");
+        assert_eq!(out.len(), 11 + 2 + 2 + 200);
+        let mut cursor = Cursor::new(out);
+        let mut stream = Stream::new(&mut cursor).le();
+        let first = format
+            .read_record(&mut stream, |tag, value| Ok((tag, value.read_u8()?)))
+            .unwrap();
+        assert_eq!(first, (0x0102, b'a'));
+        let second = format
+            .read_record(&mut stream, |tag, value| Ok((tag, value.remainder_len()?)))
+            .unwrap();
+        assert_eq!(second, (7, 200));
+        assert_eq!(stream.remainder_len().unwrap(), 0);
+    }
+
+    #[test]
+    fn record_over_remainder() {
+        let mut cursor = Cursor::new([0x01, 0x05, 0xaa]);
+        let mut stream = Stream::new(&mut cursor);
+        let format = TlvFormat::new(FieldEncoding::U8, FieldEncoding::U8);
+        let result = format.read_record(&mut stream, |_, value| value.read_u8());
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    error, fmt,
+    io::{Error, ErrorKind, Read, Result, Seek},
+};
+
+use crate::Stream;
+
+const VERIFY_BUFFER_LEN: usize = 8192;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionMismatch {
+    pub offset: u64,
+    pub expected: Option<u8>,
+    pub found: Option<u8>,
+}
+
+impl fmt::Display for RegionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe = |byte: Option<u8>| match byte {
+            Some(byte) => format!("{:#04x}", byte),
+            None => "end of region".to_string(),
+        };
+        write!(
+            f,
+            "region mismatch at offset {}: expected {}, found {}",
+            self.offset,
+            describe(self.expected),
+            describe(self.found)
+        )
+    }
+}
+
+impl error::Error for RegionMismatch {}
+
+fn mismatch(offset: u64, expected: Option<u8>, found: Option<u8>) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        RegionMismatch {
+            offset,
+            expected,
+            found,
+        },
+    )
+}
+
+impl<T, E> Stream<'_, T, E>
+where
+    T: Read + Seek,
+{
+    pub fn verify_eq(&mut self, expected: &[u8]) -> Result<()> {
+        self.verify_region(Some(expected.len() as u64), |i| expected[i as usize])
+    }
+
+    pub fn verify_filled(&mut self, pattern: &[u8]) -> Result<()> {
+        if pattern.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "fill pattern must not be empty",
+            ));
+        }
+        self.verify_region(None, |i| pattern[(i % pattern.len() as u64) as usize])
+    }
+
+    fn verify_region<F>(&mut self, len: Option<u64>, expected: F) -> Result<()>
+    where
+        F: Fn(u64) -> u8,
+    {
+        let start = self.stream_position()?;
+        let remainder_len = self.remainder_len()?;
+        let total = len.map_or(remainder_len, |len| std::cmp::min(len, remainder_len));
+        let mut buf = vec![0u8; std::cmp::min(total, VERIFY_BUFFER_LEN as u64) as usize];
+        let mut checked = 0u64;
+        while checked < total {
+            let n = std::cmp::min(buf.len() as u64, total - checked) as usize;
+            self.read_exact(&mut buf[..n])?;
+            let position = buf[..n]
+                .iter()
+                .enumerate()
+                .position(|(i, byte)| *byte != expected(checked + i as u64));
+            if let Some(i) = position {
+                let offset = checked + i as u64;
+                return Err(mismatch(
+                    start + offset,
+                    Some(expected(offset)),
+                    Some(buf[i]),
+                ));
+            }
+            checked += n as u64;
+        }
+        match len {
+            Some(len) if len > remainder_len => Err(mismatch(
+                start + remainder_len,
+                Some(expected(remainder_len)),
+                None,
+            )),
+            Some(len) if len < remainder_len => {
+                let mut found = [0u8; 1];
+                self.read_exact(&mut found)?;
+                Err(mismatch(start + len, None, Some(found[0])))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, SeekFrom};
+
+    use super::*;
+
+    fn region_mismatch(err: Error) -> RegionMismatch {
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        err.into_inner()
+            .unwrap()
+            .downcast::<RegionMismatch>()
+            .map(|mismatch| *mismatch)
+            .unwrap()
+    }
+
+    #[test]
+    fn verify_eq() {
+        let mut cursor = Cursor::new(b"HDRpayload\x00\x00".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(3)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(7)).unwrap();
+        chunk.verify_eq(b"payload").unwrap();
+        chunk.seek(SeekFrom::Start(0)).unwrap();
+        let err = chunk.verify_eq(b"payLoad").unwrap_err();
+        let expected = RegionMismatch {
+            offset: 3,
+            expected: Some(b'L'),
+            found: Some(b'l'),
+        };
+        assert_eq!(region_mismatch(err), expected);
+        chunk.seek(SeekFrom::Start(3)).unwrap();
+        let err = chunk.verify_eq(b"loads").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "region mismatch at offset 7: expected 0x73, found end of region"
+        );
+        chunk.seek(SeekFrom::Start(3)).unwrap();
+        let err = chunk.verify_eq(b"loa").unwrap_err();
+        assert_eq!(
+            region_mismatch(err),
+            RegionMismatch {
+                offset: 6,
+                expected: None,
+                found: Some(b'd'),
+            }
+        );
+    }
+
+    #[test]
+    fn verify_filled() {
+        let mut data = vec![0u8; VERIFY_BUFFER_LEN * 2 + 5];
+        let mut cursor = Cursor::new(data.clone());
+        let mut stream = Stream::new(&mut cursor);
+        stream.verify_filled(&[0]).unwrap();
+        assert_eq!(stream.remainder_len().unwrap(), 0);
+        data[VERIFY_BUFFER_LEN + 1] = 0xee;
+        let mut cursor = Cursor::new(data);
+        let mut stream = Stream::new(&mut cursor);
+        let err = stream.verify_filled(&[0]).unwrap_err();
+        assert_eq!(
+            region_mismatch(err),
+            RegionMismatch {
+                offset: VERIFY_BUFFER_LEN as u64 + 1,
+                expected: Some(0),
+                found: Some(0xee),
+            }
+        );
+        let result = stream.verify_filled(&[]);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn verify_filled_pattern_in_chunk() {
+        let mut cursor = Cursor::new(b"\x01\xde\xad\xde\xad\xde\x02".to_vec());
+        let mut stream = Stream::new(&mut cursor);
+        stream.seek(SeekFrom::Start(1)).unwrap();
+        let mut chunk = stream.borrow_chunk(Some(5)).unwrap();
+        chunk.verify_filled(b"\xde\xad").unwrap();
+        chunk.seek(SeekFrom::Start(1)).unwrap();
+        let err = chunk.verify_filled(b"\xde\xad").unwrap_err();
+        assert_eq!(region_mismatch(err).offset, 1);
+        let mut empty = stream.borrow_chunk(Some(0)).unwrap();
+        empty.verify_filled(&[0]).unwrap();
+        empty.verify_eq(b"").unwrap();
+    }
+}
@@ -0,0 +1,275 @@
+use std::{
+    future::Future,
+    io::{Error, ErrorKind, Read, Result, Seek, SeekFrom},
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use js_sys::Uint8Array;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, FileReaderSync};
+
+const DEFAULT_READ_SIZE: usize = 64 * 1024;
+
+fn js_error(value: JsValue) -> Error {
+    Error::other(format!("blob read failed: {:?}", value))
+}
+
+struct BlobState {
+    blob: Blob,
+    len: u64,
+    pos: u64,
+    read_size: usize,
+    buffer: Vec<u8>,
+    buffer_pos: u64,
+}
+
+impl BlobState {
+    fn new(blob: Blob) -> BlobState {
+        BlobState {
+            len: blob.size() as u64,
+            blob,
+            pos: 0,
+            read_size: DEFAULT_READ_SIZE,
+            buffer: Vec::new(),
+            buffer_pos: 0,
+        }
+    }
+
+    fn buffered(&mut self, buf: &mut [u8]) -> Option<usize> {
+        if self.pos >= self.len || buf.is_empty() {
+            return Some(0);
+        }
+        let offset = self.pos.checked_sub(self.buffer_pos)? as usize;
+        if offset >= self.buffer.len() {
+            return None;
+        }
+        let len = std::cmp::min(buf.len(), self.buffer.len() - offset);
+        buf[..len].copy_from_slice(&self.buffer[offset..offset + len]);
+        self.pos += len as u64;
+        Some(len)
+    }
+
+    fn slice(&self, want: usize) -> Result<Blob> {
+        let size = std::cmp::max(want, self.read_size) as u64;
+        let end = std::cmp::min(self.pos.saturating_add(size), self.len);
+        self.blob
+            .slice_with_f64_and_f64(self.pos as f64, end as f64)
+            .map_err(js_error)
+    }
+
+    fn fill(&mut self, start: u64, data: &JsValue) {
+        self.buffer = Uint8Array::new(data).to_vec();
+        self.buffer_pos = start;
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(n) => self.len.checked_add_signed(n),
+            SeekFrom::Current(n) => self.pos.checked_add_signed(n),
+        };
+        self.pos = target.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.pos)
+    }
+}
+
+pub struct BlobReader {
+    state: BlobState,
+    pending: Option<(u64, JsFuture)>,
+}
+
+impl BlobReader {
+    pub fn new(blob: Blob) -> BlobReader {
+        BlobReader {
+            state: BlobState::new(blob),
+            pending: None,
+        }
+    }
+
+    pub fn read_size(mut self, read_size: usize) -> BlobReader {
+        self.state.read_size = read_size;
+        self
+    }
+
+    pub fn blob(&self) -> &Blob {
+        &self.state.blob
+    }
+
+    pub fn len(&self) -> u64 {
+        self.state.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.len == 0
+    }
+
+    pub fn into_inner(self) -> Blob {
+        self.state.blob
+    }
+
+    fn poll_fill(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        loop {
+            if let Some(len) = self.state.buffered(buf) {
+                return Poll::Ready(Ok(len));
+            }
+            if self.pending.is_none() {
+                let slice = self.state.slice(buf.len())?;
+                let future = JsFuture::from(slice.array_buffer());
+                self.pending = Some((self.state.pos, future));
+            }
+            let (start, future) = self.pending.as_mut().unwrap();
+            let start = *start;
+            let result = ready!(Pin::new(future).poll(cx));
+            self.pending = None;
+            self.state.fill(start, &result.map_err(js_error)?);
+            if self.state.buffer.is_empty() {
+                return Poll::Ready(Ok(0));
+            }
+        }
+    }
+
+    fn seek_to(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.pending = None;
+        self.state.seek(pos)
+    }
+}
+
+impl futures_io::AsyncRead for BlobReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        self.get_mut().poll_fill(cx, buf)
+    }
+}
+
+impl futures_io::AsyncSeek for BlobReader {
+    fn poll_seek(self: Pin<&mut Self>, _: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        Poll::Ready(self.get_mut().seek_to(pos))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncRead for BlobReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        let len = ready!(self.get_mut().poll_fill(cx, buf.initialize_unfilled()))?;
+        buf.advance(len);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncSeek for BlobReader {
+    fn start_seek(self: Pin<&mut Self>, pos: SeekFrom) -> Result<()> {
+        self.get_mut().seek_to(pos).map(drop)
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<u64>> {
+        Poll::Ready(Ok(self.state.pos))
+    }
+}
+
+pub struct SyncBlobReader {
+    state: BlobState,
+    reader: FileReaderSync,
+}
+
+impl SyncBlobReader {
+    pub fn new(blob: Blob) -> Result<SyncBlobReader> {
+        Ok(SyncBlobReader {
+            state: BlobState::new(blob),
+            reader: FileReaderSync::new().map_err(js_error)?,
+        })
+    }
+
+    pub fn read_size(mut self, read_size: usize) -> SyncBlobReader {
+        self.state.read_size = read_size;
+        self
+    }
+
+    pub fn blob(&self) -> &Blob {
+        &self.state.blob
+    }
+
+    pub fn into_inner(self) -> Blob {
+        self.state.blob
+    }
+}
+
+impl Read for SyncBlobReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if let Some(len) = self.state.buffered(buf) {
+            return Ok(len);
+        }
+        let slice = self.state.slice(buf.len())?;
+        let data = self.reader.read_as_array_buffer(&slice).map_err(js_error)?;
+        self.state.fill(self.state.pos, &data);
+        Ok(self.state.buffered(buf).unwrap_or(0))
+    }
+}
+
+impl Seek for SyncBlobReader {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.state.seek(pos)
+    }
+
+    fn stream_len(&mut self) -> Result<u64> {
+        Ok(self.state.len)
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use futures::{AsyncReadExt, AsyncSeekExt};
+    use js_sys::Array;
+    use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+    use super::*;
+    use crate::{read_num::ReadNum, Stream};
+
+    wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    fn blob(data: &[u8]) -> Blob {
+        let parts = Array::of1(&Uint8Array::from(data));
+        Blob::new_with_u8_array_sequence(&parts).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    async fn async_blob_reads() {
+        let data = (0..=255u8).cycle().take(1000).collect::<Vec<_>>();
+        let mut reader = BlobReader::new(blob(&data)).read_size(64);
+        assert_eq!(reader.len(), 1000);
+        let mut buf = [0u8; 10];
+        reader.seek(SeekFrom::Start(300)).await.unwrap();
+        reader.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, data[300..310]);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, data[310..]);
+    }
+
+    #[wasm_bindgen_test]
+    fn sync_blob_chunks() {
+        let mut reader = SyncBlobReader::new(blob(b"hdr\x00\x00\x00\x02\x12\x34tail"))
+            .unwrap()
+            .read_size(4);
+        let mut stream = Stream::new(&mut reader).be();
+        stream.seek(SeekFrom::Start(3)).unwrap();
+        let len = stream.read_u32().unwrap();
+        let mut chunk = stream.borrow_chunk(Some(len as u64)).unwrap();
+        assert_eq!(chunk.read_u16().unwrap(), 0x1234);
+        assert_eq!(stream.remainder_len().unwrap(), 4);
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    io::{Error, ErrorKind, Result, Write},
+    mem,
+    sync::{
+        mpsc::{self, Receiver, SyncSender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+const DEFAULT_QUEUE_LEN: usize = 4;
+
+enum Command {
+    Data(Vec<u8>),
+    Flush,
+}
+
+fn run<W: Write>(
+    mut inner: W,
+    commands: Receiver<Command>,
+    acks: SyncSender<Result<()>>,
+    error: Arc<Mutex<Option<Error>>>,
+) -> W {
+    for command in commands {
+        match command {
+            Command::Data(data) => {
+                if let Err(e) = inner.write_all(&data) {
+                    *error.lock().unwrap() = Some(e);
+                    break;
+                }
+            }
+            Command::Flush => {
+                if acks.send(inner.flush()).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    inner
+}
+
+pub struct WriteBehind<W> {
+    commands: Option<SyncSender<Command>>,
+    acks: Receiver<Result<()>>,
+    error: Arc<Mutex<Option<Error>>>,
+    worker: Option<JoinHandle<W>>,
+    buffer: Vec<u8>,
+    buffer_size: usize,
+}
+
+impl<W> WriteBehind<W>
+where
+    W: Write + Send + 'static,
+{
+    pub fn new(inner: W) -> WriteBehind<W> {
+        WriteBehind::with_capacity(inner, DEFAULT_BUFFER_SIZE, DEFAULT_QUEUE_LEN)
+    }
+
+    pub fn with_capacity(inner: W, buffer_size: usize, queue_len: usize) -> WriteBehind<W> {
+        assert!(buffer_size > 0, "buffer size must not be zero");
+        let (commands, worker_commands) = mpsc::sync_channel(queue_len);
+        let (worker_acks, acks) = mpsc::sync_channel(1);
+        let error = Arc::new(Mutex::new(None));
+        let worker_error = error.clone();
+        let worker = thread::spawn(move || run(inner, worker_commands, worker_acks, worker_error));
+        WriteBehind {
+            commands: Some(commands),
+            acks,
+            error,
+            worker: Some(worker),
+            buffer: Vec::with_capacity(buffer_size),
+            buffer_size,
+        }
+    }
+
+    pub fn into_inner(mut self) -> Result<W> {
+        self.flush()?;
+        self.stop().ok_or_else(|| self.stopped())
+    }
+}
+
+impl<W> WriteBehind<W> {
+    fn stop(&mut self) -> Option<W> {
+        self.commands = None;
+        self.worker.take()?.join().ok()
+    }
+
+    fn stopped(&self) -> Error {
+        self.error
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap_or_else(|| Error::new(ErrorKind::BrokenPipe, "write-behind worker has stopped"))
+    }
+
+    fn check(&self) -> Result<()> {
+        match self.error.lock().unwrap().take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    fn send(&mut self, command: Command) -> Result<()> {
+        let commands = self.commands.as_ref().ok_or_else(|| self.stopped())?;
+        commands.send(command).map_err(|_| self.stopped())
+    }
+
+    fn send_buffer(&mut self) -> Result<()> {
+        let data = mem::replace(&mut self.buffer, Vec::with_capacity(self.buffer_size));
+        self.send(Command::Data(data))
+    }
+}
+
+impl<W> Write for WriteBehind<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.check()?;
+        if self.buffer.len() + buf.len() > self.buffer_size && !self.buffer.is_empty() {
+            self.send_buffer()?;
+        }
+        if buf.len() >= self.buffer_size {
+            self.send(Command::Data(buf.to_vec()))?;
+        } else {
+            self.buffer.extend_from_slice(buf);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.check()?;
+        if !self.buffer.is_empty() {
+            self.send_buffer()?;
+        }
+        self.send(Command::Flush)?;
+        match self.acks.recv() {
+            Ok(result) => result,
+            Err(_) => Err(self.stopped()),
+        }
+    }
+}
+
+impl<W> Drop for WriteBehind<W> {
+    fn drop(&mut self) {
+        if self.commands.is_some() {
+            let _ = self.flush();
+        }
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::write_num::{WriteEndianExt, WriteNum};
+
+    struct Disk {
+        data: Arc<Mutex<Vec<u8>>>,
+        writes: usize,
+        fail_after: usize,
+        flushes: usize,
+    }
+
+    impl Write for Disk {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            thread::sleep(Duration::from_millis(1));
+            if self.writes == self.fail_after {
+                return Err(Error::new(ErrorKind::StorageFull, "disk is full"));
+            }
+            self.writes += 1;
+            self.data.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            self.flushes += 1;
+            Ok(())
+        }
+    }
+
+    fn disk(fail_after: usize) -> (Disk, Arc<Mutex<Vec<u8>>>) {
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let disk = Disk {
+            data: data.clone(),
+            writes: 0,
+            fail_after,
+            flushes: 0,
+        };
+        (disk, data)
+    }
+
+    #[test]
+    fn write_behind_preserves_order() {
+        let (disk, data) = disk(usize::MAX);
+        let mut writer = WriteBehind::with_capacity(disk, 16, 2);
+        for i in 0..100u32 {
+            writer.be_writer().write_u32(i).unwrap();
+        }
+        writer.write_all(&[0xaa; 40]).unwrap();
+        writer.flush().unwrap();
+        let expected = (0..100u32)
+            .flat_map(u32::to_be_bytes)
+            .chain([0xaa; 40])
+            .collect::<Vec<_>>();
+        assert_eq!(*data.lock().unwrap(), expected);
+        writer.write_all(b"tail").unwrap();
+        let disk = writer.into_inner().unwrap();
+        assert_eq!(disk.flushes, 2);
+        assert!(data.lock().unwrap().ends_with(b"tail"));
+    }
+
+    #[test]
+    fn write_behind_surfaces_errors() {
+        let (disk, data) = disk(2);
+        let mut writer = WriteBehind::with_capacity(disk, 4, 1);
+        let mut result = Ok(());
+        for _ in 0..10 {
+            result = writer.write_all(b"abcd");
+            if result.is_err() {
+                break;
+            }
+        }
+        let err = match result {
+            Err(e) => e,
+            Ok(()) => writer.flush().unwrap_err(),
+        };
+        assert_eq!(err.kind(), ErrorKind::StorageFull);
+        assert_eq!(*data.lock().unwrap(), b"abcdabcd");
+        let err = writer.flush().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn write_behind_flushes_on_drop() {
+        let (disk, data) = disk(usize::MAX);
+        let mut writer = WriteBehind::new(disk);
+        writer.write_all(b"pending").unwrap();
+        drop(writer);
+        assert_eq!(*data.lock().unwrap(), b"pending");
+    }
+}
@@ -0,0 +1,551 @@
+use core::{marker::PhantomData, mem};
+
+use crate::{
+    io::{Result, Seek, SeekFrom, Write},
+    read_num::{Endianness, Numeric, StaticEndianness},
+};
+
+pub trait NumWriter<T: ?Sized> {
+    fn write_u8(_: &mut T, _: u8) -> Result<()>;
+    fn write_u16(_: &mut T, _: u16) -> Result<()>;
+    fn write_u32(_: &mut T, _: u32) -> Result<()>;
+    fn write_u64(_: &mut T, _: u64) -> Result<()>;
+    fn write_u128(_: &mut T, _: u128) -> Result<()>;
+
+    fn write_i8(_: &mut T, _: i8) -> Result<()>;
+    fn write_i16(_: &mut T, _: i16) -> Result<()>;
+    fn write_i32(_: &mut T, _: i32) -> Result<()>;
+    fn write_i64(_: &mut T, _: i64) -> Result<()>;
+    fn write_i128(_: &mut T, _: i128) -> Result<()>;
+
+    fn write_usize(_: &mut T, _: usize) -> Result<()>;
+    fn write_isize(_: &mut T, _: isize) -> Result<()>;
+
+    fn write_f32(_: &mut T, _: f32) -> Result<()>;
+    fn write_f64(_: &mut T, _: f64) -> Result<()>;
+}
+
+pub trait NumWriterEndianness<T: ?Sized> {
+    fn endianness(_: &T) -> Endianness;
+}
+
+pub trait WriteNum {
+    type Writer: NumWriter<Self>;
+
+    fn write_u8(&mut self, value: u8) -> Result<()> {
+        Self::Writer::write_u8(self, value)
+    }
+
+    fn write_u16(&mut self, value: u16) -> Result<()> {
+        Self::Writer::write_u16(self, value)
+    }
+
+    fn write_u32(&mut self, value: u32) -> Result<()> {
+        Self::Writer::write_u32(self, value)
+    }
+
+    fn write_u64(&mut self, value: u64) -> Result<()> {
+        Self::Writer::write_u64(self, value)
+    }
+
+    fn write_u128(&mut self, value: u128) -> Result<()> {
+        Self::Writer::write_u128(self, value)
+    }
+
+    fn write_i8(&mut self, value: i8) -> Result<()> {
+        Self::Writer::write_i8(self, value)
+    }
+
+    fn write_i16(&mut self, value: i16) -> Result<()> {
+        Self::Writer::write_i16(self, value)
+    }
+
+    fn write_i32(&mut self, value: i32) -> Result<()> {
+        Self::Writer::write_i32(self, value)
+    }
+
+    fn write_i64(&mut self, value: i64) -> Result<()> {
+        Self::Writer::write_i64(self, value)
+    }
+
+    fn write_i128(&mut self, value: i128) -> Result<()> {
+        Self::Writer::write_i128(self, value)
+    }
+
+    fn write_usize(&mut self, value: usize) -> Result<()> {
+        Self::Writer::write_usize(self, value)
+    }
+
+    fn write_isize(&mut self, value: isize) -> Result<()> {
+        Self::Writer::write_isize(self, value)
+    }
+
+    fn write_f32(&mut self, value: f32) -> Result<()> {
+        Self::Writer::write_f32(self, value)
+    }
+
+    fn write_f64(&mut self, value: f64) -> Result<()> {
+        Self::Writer::write_f64(self, value)
+    }
+
+    fn write_num<N: Numeric>(&mut self, value: N) -> Result<()> {
+        value.write_to(self)
+    }
+}
+
+pub trait ToEndianBytes: Numeric {
+    type Bytes: AsRef<[u8]>;
+
+    fn to_endian_bytes(self, endianness: Endianness) -> Self::Bytes;
+}
+
+macro_rules! impl_to_endian_bytes {
+    ($type: ty) => {
+        impl ToEndianBytes for $type {
+            type Bytes = [u8; mem::size_of::<$type>()];
+
+            fn to_endian_bytes(self, endianness: Endianness) -> Self::Bytes {
+                match endianness {
+                    Endianness::Big => self.to_be_bytes(),
+                    Endianness::Little => self.to_le_bytes(),
+                }
+            }
+        }
+    };
+}
+
+impl_to_endian_bytes! {u8}
+impl_to_endian_bytes! {u16}
+impl_to_endian_bytes! {u32}
+impl_to_endian_bytes! {u64}
+impl_to_endian_bytes! {u128}
+impl_to_endian_bytes! {i8}
+impl_to_endian_bytes! {i16}
+impl_to_endian_bytes! {i32}
+impl_to_endian_bytes! {i64}
+impl_to_endian_bytes! {i128}
+impl_to_endian_bytes! {usize}
+impl_to_endian_bytes! {isize}
+impl_to_endian_bytes! {f32}
+impl_to_endian_bytes! {f64}
+
+macro_rules! impl_num_writer {
+    ($type: ty, $method: ident) => {
+        fn $method(writer: &mut T, value: $type) -> Result<()> {
+            let bytes = value.to_endian_bytes(<Self as StaticEndianness>::endianness());
+            writer.write_all(&bytes)
+        }
+    };
+}
+
+pub struct BigEndianWriter;
+
+impl<T> NumWriter<T> for BigEndianWriter
+where
+    T: Write,
+{
+    impl_num_writer! {u8, write_u8}
+    impl_num_writer! {u16, write_u16}
+    impl_num_writer! {u32, write_u32}
+    impl_num_writer! {u64, write_u64}
+    impl_num_writer! {u128, write_u128}
+    impl_num_writer! {i8, write_i8}
+    impl_num_writer! {i16, write_i16}
+    impl_num_writer! {i32, write_i32}
+    impl_num_writer! {i64, write_i64}
+    impl_num_writer! {i128, write_i128}
+    impl_num_writer! {usize, write_usize}
+    impl_num_writer! {isize, write_isize}
+    impl_num_writer! {f32, write_f32}
+    impl_num_writer! {f64, write_f64}
+}
+
+impl<T> NumWriterEndianness<T> for BigEndianWriter
+where
+    T: ?Sized,
+{
+    fn endianness(_: &T) -> Endianness {
+        Endianness::Big
+    }
+}
+
+impl StaticEndianness for BigEndianWriter {
+    fn endianness() -> Endianness {
+        Endianness::Big
+    }
+}
+
+pub struct LittleEndianWriter;
+
+impl<T> NumWriter<T> for LittleEndianWriter
+where
+    T: Write,
+{
+    impl_num_writer! {u8, write_u8}
+    impl_num_writer! {u16, write_u16}
+    impl_num_writer! {u32, write_u32}
+    impl_num_writer! {u64, write_u64}
+    impl_num_writer! {u128, write_u128}
+    impl_num_writer! {i8, write_i8}
+    impl_num_writer! {i16, write_i16}
+    impl_num_writer! {i32, write_i32}
+    impl_num_writer! {i64, write_i64}
+    impl_num_writer! {i128, write_i128}
+    impl_num_writer! {usize, write_usize}
+    impl_num_writer! {isize, write_isize}
+    impl_num_writer! {f32, write_f32}
+    impl_num_writer! {f64, write_f64}
+}
+
+impl<T> NumWriterEndianness<T> for LittleEndianWriter
+where
+    T: ?Sized,
+{
+    fn endianness(_: &T) -> Endianness {
+        Endianness::Little
+    }
+}
+
+impl StaticEndianness for LittleEndianWriter {
+    fn endianness() -> Endianness {
+        Endianness::Little
+    }
+}
+
+pub struct NativeEndianWriter;
+
+impl<T> NumWriter<T> for NativeEndianWriter
+where
+    T: Write,
+{
+    impl_num_writer! {u8, write_u8}
+    impl_num_writer! {u16, write_u16}
+    impl_num_writer! {u32, write_u32}
+    impl_num_writer! {u64, write_u64}
+    impl_num_writer! {u128, write_u128}
+    impl_num_writer! {i8, write_i8}
+    impl_num_writer! {i16, write_i16}
+    impl_num_writer! {i32, write_i32}
+    impl_num_writer! {i64, write_i64}
+    impl_num_writer! {i128, write_i128}
+    impl_num_writer! {usize, write_usize}
+    impl_num_writer! {isize, write_isize}
+    impl_num_writer! {f32, write_f32}
+    impl_num_writer! {f64, write_f64}
+}
+
+impl<T> NumWriterEndianness<T> for NativeEndianWriter
+where
+    T: ?Sized,
+{
+    fn endianness(_: &T) -> Endianness {
+        Endianness::native()
+    }
+}
+
+impl StaticEndianness for NativeEndianWriter {
+    fn endianness() -> Endianness {
+        Endianness::native()
+    }
+}
+
+pub struct EndianWrite<W, E> {
+    inner: W,
+    endianness: PhantomData<E>,
+}
+
+impl<W, E> EndianWrite<W, E> {
+    pub fn new(inner: W) -> EndianWrite<W, E> {
+        EndianWrite {
+            inner,
+            endianness: PhantomData,
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W, E> Write for EndianWrite<W, E>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W, E> Seek for EndianWrite<W, E>
+where
+    W: Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl<W, E> WriteNum for EndianWrite<W, E>
+where
+    E: NumWriter<EndianWrite<W, E>>,
+{
+    type Writer = E;
+}
+
+pub trait WriteEndianExt: Write {
+    fn be_writer(&mut self) -> EndianWrite<&mut Self, BigEndianWriter> {
+        EndianWrite::new(self)
+    }
+
+    fn le_writer(&mut self) -> EndianWrite<&mut Self, LittleEndianWriter> {
+        EndianWrite::new(self)
+    }
+
+    fn native_writer(&mut self) -> EndianWrite<&mut Self, NativeEndianWriter> {
+        EndianWrite::new(self)
+    }
+}
+
+impl<W> WriteEndianExt for W where W: Write + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct BEWriter {
+        inner: Vec<u8>,
+    }
+
+    impl Write for BEWriter {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    impl WriteNum for BEWriter {
+        type Writer = BigEndianWriter;
+    }
+
+    macro_rules! generate_write_num_be_test {
+        ($test_name: ident, $type: ty, $value: expr, $method: ident) => {
+            #[test]
+            fn $test_name() {
+                let mut writer = BEWriter { inner: Vec::new() };
+                writer.$method($value).unwrap();
+                assert_eq!(writer.inner, $value.to_be_bytes());
+            }
+        };
+    }
+
+    generate_write_num_be_test! {write_num_u8_val_be, u8, 11u8, write_u8}
+    generate_write_num_be_test! {write_num_u8_max_be, u8, u8::MAX, write_u8}
+    generate_write_num_be_test! {write_num_u8_min_be, u8, u8::MIN, write_u8}
+    generate_write_num_be_test! {write_num_u16_val_be, u16, 11u16, write_u16}
+    generate_write_num_be_test! {write_num_u16_max_be, u16, u16::MAX, write_u16}
+    generate_write_num_be_test! {write_num_u16_min_be, u16, u16::MIN, write_u16}
+    generate_write_num_be_test! {write_num_u32_val_be, u32, 11u32, write_u32}
+    generate_write_num_be_test! {write_num_u32_max_be, u32, u32::MAX, write_u32}
+    generate_write_num_be_test! {write_num_u32_min_be, u32, u32::MIN, write_u32}
+    generate_write_num_be_test! {write_num_u64_val_be, u64, 11u64, write_u64}
+    generate_write_num_be_test! {write_num_u64_max_be, u64, u64::MAX, write_u64}
+    generate_write_num_be_test! {write_num_u64_min_be, u64, u64::MIN, write_u64}
+    generate_write_num_be_test! {write_num_u128_val_be, u128, 11u128, write_u128}
+    generate_write_num_be_test! {write_num_u128_max_be, u128, u128::MAX, write_u128}
+    generate_write_num_be_test! {write_num_u128_min_be, u128, u128::MIN, write_u128}
+    generate_write_num_be_test! {write_num_i8_val_be, i8, 11i8, write_i8}
+    generate_write_num_be_test! {write_num_i8_max_be, i8, i8::MAX, write_i8}
+    generate_write_num_be_test! {write_num_i8_min_be, i8, i8::MIN, write_i8}
+    generate_write_num_be_test! {write_num_i16_val_be, i16, 11i16, write_i16}
+    generate_write_num_be_test! {write_num_i16_max_be, i16, i16::MAX, write_i16}
+    generate_write_num_be_test! {write_num_i16_min_be, i16, i16::MIN, write_i16}
+    generate_write_num_be_test! {write_num_i32_val_be, i32, 11i32, write_i32}
+    generate_write_num_be_test! {write_num_i32_max_be, i32, i32::MAX, write_i32}
+    generate_write_num_be_test! {write_num_i32_min_be, i32, i32::MIN, write_i32}
+    generate_write_num_be_test! {write_num_i64_val_be, i64, 11i64, write_i64}
+    generate_write_num_be_test! {write_num_i64_max_be, i64, i64::MAX, write_i64}
+    generate_write_num_be_test! {write_num_i64_min_be, i64, i64::MIN, write_i64}
+    generate_write_num_be_test! {write_num_i128_val_be, i128, 11i128, write_i128}
+    generate_write_num_be_test! {write_num_i128_max_be, i128, i128::MAX, write_i128}
+    generate_write_num_be_test! {write_num_i128_min_be, i128, i128::MIN, write_i128}
+    generate_write_num_be_test! {write_num_usize_val_be, usize, 11usize, write_usize}
+    generate_write_num_be_test! {write_num_usize_max_be, usize, usize::MAX, write_usize}
+    generate_write_num_be_test! {write_num_usize_min_be, usize, usize::MIN, write_usize}
+    generate_write_num_be_test! {write_num_isize_val_be, isize, 11isize, write_isize}
+    generate_write_num_be_test! {write_num_isize_max_be, isize, isize::MAX, write_isize}
+    generate_write_num_be_test! {write_num_isize_min_be, isize, isize::MIN, write_isize}
+    generate_write_num_be_test! {write_num_f32_val_be, f32, 11f32, write_f32}
+    generate_write_num_be_test! {write_num_f32_max_be, f32, f32::MAX, write_f32}
+    generate_write_num_be_test! {write_num_f32_min_be, f32, f32::MIN, write_f32}
+    generate_write_num_be_test! {write_num_f64_val_be, f64, 11f64, write_f64}
+    generate_write_num_be_test! {write_num_f64_max_be, f64, f64::MAX, write_f64}
+    generate_write_num_be_test! {write_num_f64_min_be, f64, f64::MIN, write_f64}
+
+    struct LEWriter {
+        inner: Vec<u8>,
+    }
+
+    impl Write for LEWriter {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    impl WriteNum for LEWriter {
+        type Writer = LittleEndianWriter;
+    }
+
+    macro_rules! generate_write_num_le_test {
+        ($test_name: ident, $type: ty, $value: expr, $method: ident) => {
+            #[test]
+            fn $test_name() {
+                let mut writer = LEWriter { inner: Vec::new() };
+                writer.$method($value).unwrap();
+                assert_eq!(writer.inner, $value.to_le_bytes());
+            }
+        };
+    }
+
+    generate_write_num_le_test! {write_num_u8_val_le, u8, 11u8, write_u8}
+    generate_write_num_le_test! {write_num_u8_max_le, u8, u8::MAX, write_u8}
+    generate_write_num_le_test! {write_num_u8_min_le, u8, u8::MIN, write_u8}
+    generate_write_num_le_test! {write_num_u16_val_le, u16, 11u16, write_u16}
+    generate_write_num_le_test! {write_num_u16_max_le, u16, u16::MAX, write_u16}
+    generate_write_num_le_test! {write_num_u16_min_le, u16, u16::MIN, write_u16}
+    generate_write_num_le_test! {write_num_u32_val_le, u32, 11u32, write_u32}
+    generate_write_num_le_test! {write_num_u32_max_le, u32, u32::MAX, write_u32}
+    generate_write_num_le_test! {write_num_u32_min_le, u32, u32::MIN, write_u32}
+    generate_write_num_le_test! {write_num_u64_val_le, u64, 11u64, write_u64}
+    generate_write_num_le_test! {write_num_u64_max_le, u64, u64::MAX, write_u64}
+    generate_write_num_le_test! {write_num_u64_min_le, u64, u64::MIN, write_u64}
+    generate_write_num_le_test! {write_num_u128_val_le, u128, 11u128, write_u128}
+    generate_write_num_le_test! {write_num_u128_max_le, u128, u128::MAX, write_u128}
+    generate_write_num_le_test! {write_num_u128_min_le, u128, u128::MIN, write_u128}
+    generate_write_num_le_test! {write_num_i8_val_le, i8, 11i8, write_i8}
+    generate_write_num_le_test! {write_num_i8_max_le, i8, i8::MAX, write_i8}
+    generate_write_num_le_test! {write_num_i8_min_le, i8, i8::MIN, write_i8}
+    generate_write_num_le_test! {write_num_i16_val_le, i16, 11i16, write_i16}
+    generate_write_num_le_test! {write_num_i16_max_le, i16, i16::MAX, write_i16}
+    generate_write_num_le_test! {write_num_i16_min_le, i16, i16::MIN, write_i16}
+    generate_write_num_le_test! {write_num_i32_val_le, i32, 11i32, write_i32}
+    generate_write_num_le_test! {write_num_i32_max_le, i32, i32::MAX, write_i32}
+    generate_write_num_le_test! {write_num_i32_min_le, i32, i32::MIN, write_i32}
+    generate_write_num_le_test! {write_num_i64_val_le, i64, 11i64, write_i64}
+    generate_write_num_le_test! {write_num_i64_max_le, i64, i64::MAX, write_i64}
+    generate_write_num_le_test! {write_num_i64_min_le, i64, i64::MIN, write_i64}
+    generate_write_num_le_test! {write_num_i128_val_le, i128, 11i128, write_i128}
+    generate_write_num_le_test! {write_num_i128_max_le, i128, i128::MAX, write_i128}
+    generate_write_num_le_test! {write_num_i128_min_le, i128, i128::MIN, write_i128}
+    generate_write_num_le_test! {write_num_usize_val_le, usize, 11usize, write_usize}
+    generate_write_num_le_test! {write_num_usize_max_le, usize, usize::MAX, write_usize}
+    generate_write_num_le_test! {write_num_usize_min_le, usize, usize::MIN, write_usize}
+    generate_write_num_le_test! {write_num_isize_val_le, isize, 11isize, write_isize}
+    generate_write_num_le_test! {write_num_isize_max_le, isize, isize::MAX, write_isize}
+    generate_write_num_le_test! {write_num_isize_min_le, isize, isize::MIN, write_isize}
+    generate_write_num_le_test! {write_num_f32_val_le, f32, 11f32, write_f32}
+    generate_write_num_le_test! {write_num_f32_max_le, f32, f32::MAX, write_f32}
+    generate_write_num_le_test! {write_num_f32_min_le, f32, f32::MIN, write_f32}
+    generate_write_num_le_test! {write_num_f64_val_le, f64, 11f64, write_f64}
+    generate_write_num_le_test! {write_num_f64_max_le, f64, f64::MAX, write_f64}
+    generate_write_num_le_test! {write_num_f64_min_le, f64, f64::MIN, write_f64}
+
+    struct NEWriter {
+        inner: Vec<u8>,
+    }
+
+    impl Write for NEWriter {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    impl WriteNum for NEWriter {
+        type Writer = NativeEndianWriter;
+    }
+
+    macro_rules! generate_write_num_ne_test {
+        ($test_name: ident, $type: ty, $value: expr, $method: ident) => {
+            #[test]
+            fn $test_name() {
+                let mut writer = NEWriter { inner: Vec::new() };
+                writer.$method($value).unwrap();
+                assert_eq!(writer.inner, $value.to_ne_bytes());
+            }
+        };
+    }
+
+    generate_write_num_ne_test! {write_num_u8_val_ne, u8, 11u8, write_u8}
+    generate_write_num_ne_test! {write_num_u8_max_ne, u8, u8::MAX, write_u8}
+    generate_write_num_ne_test! {write_num_u8_min_ne, u8, u8::MIN, write_u8}
+    generate_write_num_ne_test! {write_num_u16_val_ne, u16, 11u16, write_u16}
+    generate_write_num_ne_test! {write_num_u16_max_ne, u16, u16::MAX, write_u16}
+    generate_write_num_ne_test! {write_num_u16_min_ne, u16, u16::MIN, write_u16}
+    generate_write_num_ne_test! {write_num_u32_val_ne, u32, 11u32, write_u32}
+    generate_write_num_ne_test! {write_num_u32_max_ne, u32, u32::MAX, write_u32}
+    generate_write_num_ne_test! {write_num_u32_min_ne, u32, u32::MIN, write_u32}
+    generate_write_num_ne_test! {write_num_u64_val_ne, u64, 11u64, write_u64}
+    generate_write_num_ne_test! {write_num_u64_max_ne, u64, u64::MAX, write_u64}
+    generate_write_num_ne_test! {write_num_u64_min_ne, u64, u64::MIN, write_u64}
+    generate_write_num_ne_test! {write_num_u128_val_ne, u128, 11u128, write_u128}
+    generate_write_num_ne_test! {write_num_u128_max_ne, u128, u128::MAX, write_u128}
+    generate_write_num_ne_test! {write_num_u128_min_ne, u128, u128::MIN, write_u128}
+    generate_write_num_ne_test! {write_num_i8_val_ne, i8, 11i8, write_i8}
+    generate_write_num_ne_test! {write_num_i8_max_ne, i8, i8::MAX, write_i8}
+    generate_write_num_ne_test! {write_num_i8_min_ne, i8, i8::MIN, write_i8}
+    generate_write_num_ne_test! {write_num_i16_val_ne, i16, 11i16, write_i16}
+    generate_write_num_ne_test! {write_num_i16_max_ne, i16, i16::MAX, write_i16}
+    generate_write_num_ne_test! {write_num_i16_min_ne, i16, i16::MIN, write_i16}
+    generate_write_num_ne_test! {write_num_i32_val_ne, i32, 11i32, write_i32}
+    generate_write_num_ne_test! {write_num_i32_max_ne, i32, i32::MAX, write_i32}
+    generate_write_num_ne_test! {write_num_i32_min_ne, i32, i32::MIN, write_i32}
+    generate_write_num_ne_test! {write_num_i64_val_ne, i64, 11i64, write_i64}
+    generate_write_num_ne_test! {write_num_i64_max_ne, i64, i64::MAX, write_i64}
+    generate_write_num_ne_test! {write_num_i64_min_ne, i64, i64::MIN, write_i64}
+    generate_write_num_ne_test! {write_num_i128_val_ne, i128, 11i128, write_i128}
+    generate_write_num_ne_test! {write_num_i128_max_ne, i128, i128::MAX, write_i128}
+    generate_write_num_ne_test! {write_num_i128_min_ne, i128, i128::MIN, write_i128}
+    generate_write_num_ne_test! {write_num_usize_val_ne, usize, 11usize, write_usize}
+    generate_write_num_ne_test! {write_num_usize_max_ne, usize, usize::MAX, write_usize}
+    generate_write_num_ne_test! {write_num_usize_min_ne, usize, usize::MIN, write_usize}
+    generate_write_num_ne_test! {write_num_isize_val_ne, isize, 11isize, write_isize}
+    generate_write_num_ne_test! {write_num_isize_max_ne, isize, isize::MAX, write_isize}
+    generate_write_num_ne_test! {write_num_isize_min_ne, isize, isize::MIN, write_isize}
+    generate_write_num_ne_test! {write_num_f32_val_ne, f32, 11f32, write_f32}
+    generate_write_num_ne_test! {write_num_f32_max_ne, f32, f32::MAX, write_f32}
+    generate_write_num_ne_test! {write_num_f32_min_ne, f32, f32::MIN, write_f32}
+    generate_write_num_ne_test! {write_num_f64_val_ne, f64, 11f64, write_f64}
+    generate_write_num_ne_test! {write_num_f64_max_ne, f64, f64::MAX, write_f64}
+    generate_write_num_ne_test! {write_num_f64_min_ne, f64, f64::MIN, write_f64}
+
+    #[test]
+    fn endian_write_ext() {
+        let mut out = Vec::new();
+        out.be_writer().write_u32(0x0102_0304).unwrap();
+        out.le_writer().write_u16(0x0506).unwrap();
+        out.native_writer().write_u8(0x07).unwrap();
+        assert_eq!(out, [0x01, 0x02, 0x03, 0x04, 0x06, 0x05, 0x07]);
+    }
+}
@@ -0,0 +1,501 @@
+use std::{
+    collections::VecDeque,
+    io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write},
+};
+
+const SKIPPABLE_MAGIC: u32 = 0x184d_2a5e;
+const SEEKABLE_MAGIC: u32 = 0x8f92_eab1;
+const FOOTER_LEN: u64 = 9;
+const CHECKSUM_FLAG: u8 = 0x80;
+const MAX_FRAME_SIZE: usize = u32::MAX as usize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SeekEntry {
+    pub compressed_offset: u64,
+    pub decompressed_offset: u64,
+    pub compressed_size: u32,
+    pub decompressed_size: u32,
+    pub checksum: Option<u32>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SeekTable {
+    entries: Vec<SeekEntry>,
+}
+
+impl SeekTable {
+    pub fn read<R>(reader: &mut R) -> Result<SeekTable>
+    where
+        R: Read + Seek,
+    {
+        let end = reader.seek(SeekFrom::End(0))?;
+        if end < FOOTER_LEN + 8 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "stream is too short for a seek table",
+            ));
+        }
+        let mut footer = [0u8; FOOTER_LEN as usize];
+        reader.seek(SeekFrom::Start(end - FOOTER_LEN))?;
+        reader.read_exact(&mut footer)?;
+        let magic = u32::from_le_bytes(footer[5..].try_into().unwrap());
+        if magic != SEEKABLE_MAGIC {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid seek table magic {:#010x}", magic),
+            ));
+        }
+        let descriptor = footer[4];
+        if descriptor & 0x7c != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "reserved seek table descriptor bits set in {:#04x}",
+                    descriptor
+                ),
+            ));
+        }
+        let frames = u32::from_le_bytes(footer[..4].try_into().unwrap()) as u64;
+        let entry_len = if descriptor & CHECKSUM_FLAG != 0 {
+            12
+        } else {
+            8
+        };
+        let table_len = frames * entry_len + FOOTER_LEN;
+        if table_len + 8 > end {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("seek table of {} frames exceeds the stream", frames),
+            ));
+        }
+        let table_start = end - table_len - 8;
+        let mut header = [0u8; 8];
+        reader.seek(SeekFrom::Start(table_start))?;
+        reader.read_exact(&mut header)?;
+        let magic = u32::from_le_bytes(header[..4].try_into().unwrap());
+        let size = u32::from_le_bytes(header[4..].try_into().unwrap()) as u64;
+        if magic != SKIPPABLE_MAGIC || size != table_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "seek table is not wrapped in a skippable frame",
+            ));
+        }
+        let mut raw = vec![0u8; (frames * entry_len) as usize];
+        reader.read_exact(&mut raw)?;
+        let mut entries = Vec::with_capacity(frames as usize);
+        let mut compressed_offset = 0u64;
+        let mut decompressed_offset = 0u64;
+        for entry in raw.chunks_exact(entry_len as usize) {
+            let compressed_size = u32::from_le_bytes(entry[..4].try_into().unwrap());
+            let decompressed_size = u32::from_le_bytes(entry[4..8].try_into().unwrap());
+            let checksum = entry
+                .get(8..)
+                .filter(|checksum| !checksum.is_empty())
+                .map(|checksum| u32::from_le_bytes(checksum.try_into().unwrap()));
+            entries.push(SeekEntry {
+                compressed_offset,
+                decompressed_offset,
+                compressed_size,
+                decompressed_size,
+                checksum,
+            });
+            compressed_offset += compressed_size as u64;
+            decompressed_offset += decompressed_size as u64;
+        }
+        if compressed_offset > table_start {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "frames of {} bytes overlap the seek table at offset {}",
+                    compressed_offset, table_start
+                ),
+            ));
+        }
+        Ok(SeekTable { entries })
+    }
+
+    pub fn entries(&self) -> &[SeekEntry] {
+        &self.entries
+    }
+
+    pub fn compressed_len(&self) -> u64 {
+        self.entries
+            .last()
+            .map_or(0, |e| e.compressed_offset + e.compressed_size as u64)
+    }
+
+    pub fn decompressed_len(&self) -> u64 {
+        self.entries
+            .last()
+            .map_or(0, |e| e.decompressed_offset + e.decompressed_size as u64)
+    }
+
+    pub fn frame_at(&self, offset: u64) -> Option<usize> {
+        if offset >= self.decompressed_len() {
+            return None;
+        }
+        Some(
+            self.entries
+                .partition_point(|e| e.decompressed_offset + e.decompressed_size as u64 <= offset),
+        )
+    }
+
+    fn write_to<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: Write,
+    {
+        let table_len = self.entries.len() as u64 * 8 + FOOTER_LEN;
+        let table_len = u32::try_from(table_len)
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "too many frames for a seek table"))?;
+        writer.write_all(&SKIPPABLE_MAGIC.to_le_bytes())?;
+        writer.write_all(&table_len.to_le_bytes())?;
+        for entry in &self.entries {
+            writer.write_all(&entry.compressed_size.to_le_bytes())?;
+            writer.write_all(&entry.decompressed_size.to_le_bytes())?;
+        }
+        writer.write_all(&(self.entries.len() as u32).to_le_bytes())?;
+        writer.write_all(&[0])?;
+        writer.write_all(&SEEKABLE_MAGIC.to_le_bytes())
+    }
+}
+
+pub struct SeekableDecoder<R> {
+    inner: R,
+    table: SeekTable,
+    pos: u64,
+    cache: VecDeque<(usize, Vec<u8>)>,
+    cache_frames: usize,
+}
+
+impl<R> SeekableDecoder<R>
+where
+    R: Read + Seek,
+{
+    pub fn new(mut inner: R) -> Result<SeekableDecoder<R>> {
+        let table = SeekTable::read(&mut inner)?;
+        Ok(SeekableDecoder {
+            inner,
+            table,
+            pos: 0,
+            cache: VecDeque::new(),
+            cache_frames: 1,
+        })
+    }
+
+    pub fn with_cache_frames(mut self, cache_frames: usize) -> SeekableDecoder<R> {
+        self.cache_frames = cache_frames;
+        self
+    }
+
+    pub fn seek_table(&self) -> &SeekTable {
+        &self.table
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn frame(&mut self, index: usize) -> Result<&[u8]> {
+        if let Some(cached) = self.cache.iter().position(|(i, _)| *i == index) {
+            let frame = self.cache.remove(cached).unwrap();
+            self.cache.push_back(frame);
+        } else {
+            let entry = self.table.entries[index];
+            let mut compressed = vec![0u8; entry.compressed_size as usize];
+            self.inner.seek(SeekFrom::Start(entry.compressed_offset))?;
+            self.inner.read_exact(&mut compressed)?;
+            let mut frame = Vec::new();
+            zstd::stream::read::Decoder::with_buffer(&compressed[..])?
+                .single_frame()
+                .take(entry.decompressed_size as u64 + 1)
+                .read_to_end(&mut frame)?;
+            if frame.len() != entry.decompressed_size as usize {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "frame {} decompressed to {} bytes, expected {}",
+                        index,
+                        frame.len(),
+                        entry.decompressed_size
+                    ),
+                ));
+            }
+            if let Some(stored) = entry.checksum {
+                let computed = xxhash_rust::xxh64::xxh64(&frame, 0) as u32;
+                if computed != stored {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "frame {} checksum mismatch: stored {:#010x}, computed {:#010x}",
+                            index, stored, computed
+                        ),
+                    ));
+                }
+            }
+            while self.cache_frames > 0 && self.cache.len() >= self.cache_frames {
+                self.cache.pop_front();
+            }
+            self.cache.push_back((index, frame));
+        }
+        Ok(&self.cache.back().unwrap().1)
+    }
+}
+
+impl<R> Read for SeekableDecoder<R>
+where
+    R: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let index = match self.table.frame_at(self.pos) {
+            Some(index) if !buf.is_empty() => index,
+            _ => return Ok(0),
+        };
+        let offset = (self.pos - self.table.entries[index].decompressed_offset) as usize;
+        let mut frame = &self.frame(index)?[offset..];
+        let len = frame.read(buf)?;
+        if self.cache_frames == 0 {
+            self.cache.clear();
+        }
+        self.pos += len as u64;
+        Ok(len)
+    }
+}
+
+impl<R> Seek for SeekableDecoder<R>
+where
+    R: Read + Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(pos) => {
+                self.pos = pos;
+                return Ok(pos);
+            }
+            SeekFrom::End(offset) => (self.table.decompressed_len(), offset),
+            SeekFrom::Current(offset) => (self.pos, offset),
+        };
+        self.pos = base.checked_add_signed(offset).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.pos)
+    }
+}
+
+pub struct SeekableEncoder<W>
+where
+    W: Write,
+{
+    inner: W,
+    frame_size: usize,
+    level: i32,
+    buf: Vec<u8>,
+    table: SeekTable,
+}
+
+impl<W> SeekableEncoder<W>
+where
+    W: Write,
+{
+    pub fn new(inner: W, frame_size: usize) -> SeekableEncoder<W> {
+        SeekableEncoder {
+            inner,
+            frame_size: frame_size.clamp(1, MAX_FRAME_SIZE),
+            level: zstd::DEFAULT_COMPRESSION_LEVEL,
+            buf: Vec::new(),
+            table: SeekTable::default(),
+        }
+    }
+
+    pub fn level(mut self, level: i32) -> SeekableEncoder<W> {
+        self.level = level;
+        self
+    }
+
+    pub fn seek_table(&self) -> &SeekTable {
+        &self.table
+    }
+
+    fn write_frame(&mut self, len: usize) -> Result<()> {
+        let compressed = zstd::bulk::compress(&self.buf[..len], self.level)?;
+        let compressed_size = u32::try_from(compressed.len()).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "compressed frame of {} bytes does not fit in a seek table entry",
+                    compressed.len()
+                ),
+            )
+        })?;
+        self.inner.write_all(&compressed)?;
+        self.table.entries.push(SeekEntry {
+            compressed_offset: self.table.compressed_len(),
+            decompressed_offset: self.table.decompressed_len(),
+            compressed_size,
+            decompressed_size: len as u32,
+            checksum: None,
+        });
+        self.buf.drain(..len);
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<W> {
+        if !self.buf.is_empty() {
+            self.write_frame(self.buf.len())?;
+        }
+        self.table.write_to(&mut self.inner)?;
+        Ok(self.inner)
+    }
+}
+
+impl<W> Write for SeekableEncoder<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.buf.extend_from_slice(buf);
+        while self.buf.len() >= self.frame_size {
+            self.write_frame(self.frame_size)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{read_num::ReadNum, Stream};
+
+    fn archive() -> Vec<u8> {
+        let mut encoder = SeekableEncoder::new(Vec::new(), 1000).level(3);
+        for i in 0..1000u32 {
+            encoder.write_all(&i.to_be_bytes()).unwrap();
+        }
+        assert_eq!(encoder.seek_table().entries().len(), 4);
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn seek_table_round_trip() {
+        let data = archive();
+        let table = SeekTable::read(&mut Cursor::new(&data)).unwrap();
+        assert_eq!(table.entries().len(), 4);
+        assert_eq!(table.decompressed_len(), 4000);
+        assert_eq!(table.compressed_len() + 8 + 4 * 8 + 9, data.len() as u64);
+        assert_eq!(table.frame_at(999), Some(0));
+        assert_eq!(table.frame_at(1000), Some(1));
+        assert_eq!(table.frame_at(4000), None);
+        assert_eq!(table.entries()[3].decompressed_offset, 3000);
+    }
+
+    #[test]
+    fn seekable_decoder_random_access() {
+        let mut decoder = SeekableDecoder::new(Cursor::new(archive()))
+            .unwrap()
+            .with_cache_frames(2);
+        let mut stream = Stream::new(&mut decoder).be();
+        stream.seek(SeekFrom::Start(4 * 600)).unwrap();
+        assert_eq!(stream.read_u32().unwrap(), 600);
+        stream.seek(SeekFrom::Start(998)).unwrap();
+        assert_eq!(stream.read_u32().unwrap(), 0xf9_0000);
+        stream.seek(SeekFrom::End(-4)).unwrap();
+        assert_eq!(stream.read_u32().unwrap(), 999);
+        assert!(stream.read_u32().is_err());
+        let mut all = Vec::new();
+        decoder.seek(SeekFrom::Start(0)).unwrap();
+        decoder.read_to_end(&mut all).unwrap();
+        assert_eq!(all.len(), 4000);
+        assert_eq!(&all[396..400], &99u32.to_be_bytes());
+    }
+
+    #[test]
+    fn seek_table_errors() {
+        let mut data = archive();
+        let len = data.len();
+        data[len - 1] ^= 0xff;
+        let err = SeekableDecoder::new(Cursor::new(data)).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let err = SeekTable::read(&mut Cursor::new(b"short")).unwrap_err();
+        assert_eq!(err.to_string(), "stream is too short for a seek table");
+    }
+
+    fn checksummed_archive(frames: &[&[u8]], corrupt: usize) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut entries = Vec::new();
+        for (index, frame) in frames.iter().enumerate() {
+            let compressed = zstd::bulk::compress(frame, 3).unwrap();
+            let mut checksum = xxhash_rust::xxh64::xxh64(frame, 0) as u32;
+            if index == corrupt {
+                checksum ^= 1;
+            }
+            entries.extend((compressed.len() as u32).to_le_bytes());
+            entries.extend((frame.len() as u32).to_le_bytes());
+            entries.extend(checksum.to_le_bytes());
+            data.extend(compressed);
+        }
+        data.extend(SKIPPABLE_MAGIC.to_le_bytes());
+        data.extend((entries.len() as u32 + FOOTER_LEN as u32).to_le_bytes());
+        data.extend(entries);
+        data.extend((frames.len() as u32).to_le_bytes());
+        data.push(CHECKSUM_FLAG);
+        data.extend(SEEKABLE_MAGIC.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn seekable_decoder_verifies_checksums() {
+        let frames: [&[u8]; 2] = [b"first frame", b"second frame"];
+        let mut decoder =
+            SeekableDecoder::new(Cursor::new(checksummed_archive(&frames, usize::MAX))).unwrap();
+        assert!(decoder.seek_table().entries()[0].checksum.is_some());
+        let mut all = String::new();
+        decoder.read_to_string(&mut all).unwrap();
+        assert_eq!(all, "first framesecond frame");
+        let mut decoder =
+            SeekableDecoder::new(Cursor::new(checksummed_archive(&frames, 1))).unwrap();
+        let mut first = [0u8; 11];
+        decoder.read_exact(&mut first).unwrap();
+        let err = decoder.read(&mut first).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("frame 1 checksum mismatch"));
+    }
+
+    #[test]
+    fn seekable_decoder_rejects_wrong_sizes() {
+        let archive = |decompressed_size: u32| {
+            let mut data = zstd::bulk::compress(b"tiny", 3).unwrap();
+            let compressed_size = data.len() as u32;
+            data.extend(SKIPPABLE_MAGIC.to_le_bytes());
+            data.extend((8 + FOOTER_LEN as u32).to_le_bytes());
+            data.extend(compressed_size.to_le_bytes());
+            data.extend(decompressed_size.to_le_bytes());
+            data.extend(1u32.to_le_bytes());
+            data.push(0);
+            data.extend(SEEKABLE_MAGIC.to_le_bytes());
+            data
+        };
+        for decompressed_size in [u32::MAX, 2] {
+            let mut decoder =
+                SeekableDecoder::new(Cursor::new(archive(decompressed_size))).unwrap();
+            let err = decoder.read(&mut [0u8; 4]).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+            assert!(err.to_string().starts_with("frame 0 decompressed to"));
+        }
+        let mut decoder = SeekableDecoder::new(Cursor::new(archive(4))).unwrap();
+        let mut all = Vec::new();
+        decoder.read_to_end(&mut all).unwrap();
+        assert_eq!(all, b"tiny");
+    }
+}